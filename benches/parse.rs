@@ -0,0 +1,66 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use num_parser::*;
+
+/// Builds an expression with `terms` terms, each with a few operators, brackets and a
+/// function call.
+fn expression(terms: usize) -> String {
+    (0..terms)
+        .map(|i| format!("{}*x^2 - sin({}) / (1 + {})", i, i, i))
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+fn parse(c: &mut Criterion) {
+    let context = Context::default();
+    let mut group = c.benchmark_group("parse");
+    for terms in [10, 100, 1000] {
+        let input = expression(terms);
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(terms), &input, |b, input| {
+            b.iter(|| parse_with_diagnostics(input, &context))
+        });
+    }
+    group.finish();
+}
+
+fn eval(c: &mut Criterion) {
+    // Every term of a sum is a level of recursion
+    let mut context = Context::new(
+        settings::Rounding::default(),
+        settings::AngleUnit::default(),
+        settings::DepthLimit::Limit(1000),
+    );
+    eval_with_mutable_context("x = 2", &mut context).unwrap();
+    let mut group = c.benchmark_group("eval");
+    for terms in [10, 100] {
+        let input = expression(terms);
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(terms), &input, |b, input| {
+            b.iter(|| eval_with_static_context(input, &context).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn eval_with_history(c: &mut Criterion) {
+    // Every call of a user-defined function copies the context in a new scope
+    let mut context = Context::default();
+    eval_with_mutable_context("f(n) = n <= 1 ? 1 : n*f(n-1)", &mut context).unwrap();
+    let mut group = c.benchmark_group("eval_with_history");
+    for changes in [0, 100, 1000] {
+        let mut context = context.clone();
+        context.undo_limit = settings::UndoLimit::NoLimit;
+        for value in 0..changes {
+            eval_with_mutable_context(&format!("a = {}", value), &mut context).unwrap();
+        }
+        group.bench_with_input(
+            BenchmarkId::from_parameter(changes),
+            &context,
+            |b, context| b.iter(|| eval_with_static_context("f(10)", context).unwrap()),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parse, eval, eval_with_history);
+criterion_main!(benches);
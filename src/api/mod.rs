@@ -1,4 +1,11 @@
-use crate::{context::Context, interpreter, out::*, token, tree, value::Value};
+use crate::{
+    context::{settings, Context},
+    interpreter,
+    objects::{Expression, Request},
+    out::*,
+    token, tree,
+    value::Value,
+};
 
 /// Evaluate an expression with the default context.
 ///
@@ -51,21 +58,11 @@ pub fn eval(input: &str) -> EvalResult<Value> {
 /// assert_eq!(res, Value::from(4.14));
 /// ```
 pub fn eval_with_static_context(input: &str, context: &Context) -> EvalResult<Value> {
-    let input = String::from(input);
-    let stream = token::build_stream(input, &context)?;
-    let tree = tree::build_tree(stream)?;
-    let request = interpreter::interpret_tree(&tree)?;
+    let request = parse_request(input, context, &mut Diagnostics::new())?;
 
     match request {
-        crate::objects::Request::Evaluation(_) => (),
-        other => return Err(ErrorType::InvalidMutableContext { request: other }),
-    }
-
-    let mut context_clone = context.clone();
-
-    match request.execute(&mut context_clone)? {
-        Some(result) => Ok(result),
-        None => return Err(ErrorType::InvalidMutableContext { request }),
+        Request::Evaluation(expr) => Ok(expr.eval(context, 0)?.round(context)),
+        other => Err(ErrorType::InvalidMutableContext { request: other }),
     }
 }
 
@@ -96,10 +93,239 @@ pub fn eval_with_static_context(input: &str, context: &Context) -> EvalResult<Va
 ///
 /// ```
 pub fn eval_with_mutable_context(input: &str, context: &mut Context) -> EvalResult<Option<Value>> {
-    let input = String::from(input);
-    let stream = token::build_stream(input, &context)?;
-    let tree = tree::build_tree(stream)?;
-    let request = interpreter::interpret_tree(&tree)?;
+    let request = parse_request(input, context, &mut Diagnostics::new())?;
 
     request.execute(context)
 }
+
+/// Evaluate an expression or add a declaration like `eval_with_mutable_context`, also
+/// returning a warning for every part of the input that was read implicitly.
+///
+/// Identifiers split into known names, added multiplications and added function
+/// brackets are reported, sorted by their position in the input. With the `Strict`
+/// syntax setting they are errors instead.
+///
+/// ## Examples
+/// ```
+/// use num_parser::*;
+///
+/// let mut context = Context::default();
+///
+/// let (value, warnings) = eval_with_warnings("4pi/pi", &mut context).unwrap();
+///
+/// assert_eq!(value, Some(Value::from(4)));
+/// assert_eq!(warnings[0].to_string(), "WARNING: implicit multiplication at position 1.");
+///
+/// context.syntax = settings::Syntax::Strict;
+/// assert!(eval_with_warnings("4pi/pi", &mut context).is_err());
+/// ```
+pub fn eval_with_warnings(
+    input: &str,
+    context: &mut Context,
+) -> EvalResult<(Option<Value>, Vec<Warning>)> {
+    let mut diagnostics = Diagnostics::new();
+    let request = parse_request(input, context, &mut diagnostics)?;
+
+    Ok((request.execute(context)?, diagnostics.take_warnings()))
+}
+
+/// Returns the declarations listed by the `vars()` and `funcs()` commands, sorted by
+/// name, or `None` if the input is not a listing.
+///
+/// Listings have no value, so the evaluation functions return `Ok(None)` for them.
+///
+/// ## Examples
+/// ```
+/// use num_parser::*;
+///
+/// let mut context = Context::default();
+/// eval_with_mutable_context("a = 2", &mut context).unwrap();
+///
+/// let listing = list_declarations("vars()", &context).unwrap();
+///
+/// assert_eq!(listing, Some(vec!["a = 2".to_owned()]));
+/// assert_eq!(list_declarations("a + 1", &context).unwrap(), None);
+/// ```
+pub fn list_declarations(input: &str, context: &Context) -> EvalResult<Option<Vec<String>>> {
+    let request = parse_request(input, context, &mut Diagnostics::new())?;
+
+    Ok(request.list(context))
+}
+
+/// Parses an expression without evaluating it, collecting every syntax problem instead
+/// of failing on the first one.
+///
+/// Returns the expression tree, where the parts that could not be parsed are
+/// `Expression::Invalid`, together with the problems found, sorted by their position in
+/// the input. Declarations are returned as `=` and `:=` operations.
+///
+/// ## Examples
+/// ```
+/// use num_parser::*;
+///
+/// let (expression, diagnostics) = parse_with_diagnostics("(2 + ) * 3$", &Context::default());
+///
+/// assert_eq!(expression.to_string(), "(2 + _) * 3");
+/// assert_eq!(diagnostics.len(), 2);
+/// // The operator missing an argument
+/// assert_eq!(diagnostics[0].position, 3);
+/// assert_eq!(diagnostics[1].error, ErrorType::UnknownToken { token: "$".to_owned() });
+/// ```
+pub fn parse_with_diagnostics(input: &str, context: &Context) -> (Expression, Vec<Diagnostic>) {
+    let mut diagnostics = Diagnostics::recovering();
+    let length = input.chars().count();
+
+    let tree = token::build_stream(String::from(input), context, &mut diagnostics)
+        .and_then(|stream| tree::build_tree(stream, context, &mut diagnostics));
+
+    let expression = match tree {
+        Ok(tree) => {
+            // Declarations can only be checked once the whole input is valid.
+            if diagnostics.is_empty() {
+                if let Err(error) = interpreter::interpret_tree(&tree) {
+                    diagnostics.recover(error, 0, length, ()).ok();
+                }
+            }
+            tree.0
+        }
+        // Problems that cannot be recovered from invalidate the whole input.
+        Err(error) => {
+            diagnostics.recover(error, 0, length, ()).ok();
+            Expression::Invalid
+        }
+    };
+
+    (expression, diagnostics.into_vec())
+}
+
+/// Parses an expression into a request, failing on the first problem.
+fn parse_request(
+    input: &str,
+    context: &Context,
+    diagnostics: &mut Diagnostics,
+) -> EvalResult<Request> {
+    let stream = token::build_stream(String::from(input), context, diagnostics)?;
+    let tree = tree::build_tree(stream, context, diagnostics)?;
+
+    interpreter::interpret_tree(&tree)
+}
+
+/// Evaluate a script: a list of expressions and declarations separated by `;` or
+/// new lines.
+///
+/// See also `eval_with_mutable_context`.
+///
+/// Statements are executed in order on the provided context. Text following a `#` on
+/// the same line is a comment, and so are lines starting with `//`. Trailing comments
+/// can only start with `#`, as `//` after code is the integer division. Since
+/// `x = 1 // note` would look like a comment, a `//` followed by a name is an
+/// `ErrorType::AmbiguousComment`: the divisor is written in brackets instead, like in
+/// `x // (n)`. The result of each statement is returned, with errors wrapped in
+/// `ErrorType::ErrorInStatement`. The setting `on_error` of the context decides whether
+/// the script stops at the first error, and whether it reverts the changes it made.
+///
+/// ## Examples
+/// ```
+/// use num_parser::*;
+///
+/// let mut context = Context::default();
+///
+/// let script = "
+///     m = 2; v = 3  # In SI units
+///     k(m,v) = m*v^2/2
+///     // The kinetic energy
+///     k(m,v)
+/// ";
+/// let results = eval_script(script, &mut context);
+///
+/// assert_eq!(results.len(), 4);
+/// assert_eq!(results[3].as_ref().unwrap(), &Some(Value::from(9)));
+/// ```
+pub fn eval_script(input: &str, context: &mut Context) -> Vec<EvalResult<Option<Value>>> {
+    let statements = split_script(input);
+
+    if context.on_error == settings::OnError::Rollback {
+        // Fail the transaction on the first error, so the changes are reverted.
+        return context
+            .transaction(|context| {
+                let results = run_statements(&statements, context, true);
+                match results.last() {
+                    Some(Err(_)) => Err(results),
+                    _ => Ok(results),
+                }
+            })
+            .unwrap_or_else(|results| results);
+    }
+
+    let stop = context.on_error == settings::OnError::Stop;
+    run_statements(&statements, context, stop)
+}
+
+/// Executes the statements of a script in order, stopping at the first error if `stop`
+/// is true.
+fn run_statements(
+    statements: &[(usize, &str)],
+    context: &mut Context,
+    stop: bool,
+) -> Vec<EvalResult<Option<Value>>> {
+    let mut results = vec![];
+
+    for (index, (line, statement)) in statements.iter().enumerate() {
+        let result = match divisor_name(statement) {
+            Some(divisor) => Err(ErrorType::AmbiguousComment {
+                divisor: divisor.to_owned(),
+            }),
+            None => eval_with_mutable_context(statement, context),
+        }
+        .map_err(|error| ErrorType::ErrorInStatement {
+            statement: index + 1,
+            line: *line,
+            error: Box::new(error),
+        });
+        let failed = result.is_err();
+        results.push(result);
+
+        if failed && stop {
+            break;
+        }
+    }
+
+    results
+}
+
+/// Returns the name following a `//` in a statement, which could be meant as a comment
+/// but would be read as an integer division.
+fn divisor_name(statement: &str) -> Option<&str> {
+    statement.match_indices("//").find_map(|(index, _)| {
+        let divisor = statement[index + 2..].trim_start();
+        let end = divisor
+            .find(|c: char| !c.is_alphanumeric() && c != '_')
+            .unwrap_or(divisor.len());
+        match divisor.chars().next() {
+            Some(c) if c.is_alphabetic() || c == '_' => Some(&divisor[..end]),
+            _ => None,
+        }
+    })
+}
+
+/// Splits a script into its non-empty statements, together with the line they are on.
+fn split_script(input: &str) -> Vec<(usize, &str)> {
+    let mut statements = vec![];
+
+    for (index, line) in input.lines().enumerate() {
+        let code = match line.find('#') {
+            Some(comment_start) => &line[..comment_start],
+            None => line,
+        };
+        if code.trim_start().starts_with("//") {
+            continue;
+        }
+        for statement in code.split(';') {
+            if !statement.trim().is_empty() {
+                statements.push((index + 1, statement));
+            }
+        }
+    }
+
+    statements
+}
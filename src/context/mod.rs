@@ -1,19 +1,68 @@
-use std::collections::HashMap;
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
 
+mod scope;
+mod script;
+mod suggestions;
+#[cfg(feature = "serde")]
+mod serialization;
 pub mod settings;
 
-use crate::objects::Expression;
+use rand::rngs::StdRng;
 
-use self::settings::Rounding;
+use crate::{objects::Expression, out::ErrorType, value::Value};
+
+use self::{scope::Scope, settings::Rounding};
+
+/// The user-defined functions, with their parameters and body.
+type Functions = HashMap<String, (Vec<String>, Box<Expression>)>;
+
+/// The declarations of a context at some point, created by [`Context::snapshot`].
+///
+/// Taking a snapshot does not copy the declarations: they are shared with the context
+/// until one of them is changed.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    functions: Arc<Functions>,
+    variables: Arc<HashMap<String, Box<Expression>>>,
+    values: Arc<HashMap<String, Value>>,
+}
+
+/// The declarations before each change that can be undone and before each undone
+/// change, the latest last.
+#[derive(Debug, Clone, Default)]
+struct History {
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+}
 
 /// Contains user-defined functions and constants.
+///
+/// Declarations and the undo history are shared between the copies of a context and
+/// only copied when one of them is changed, so cloning a context is cheap.
+///
+/// With the `serde` feature, a context is serialized as `{ version, context }`, where
+/// `version` is the version of the format. Contexts of other versions are rejected.
 #[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(remote = "Self")
+)]
 pub struct Context {
     /// Function declared by the user at runtime.
-    pub functions: HashMap<String, (Vec<String>, Box<Expression>)>,
-    /// Variables declared by the user at runtime.
-    pub variables: HashMap<String, Box<Expression>>,
+    pub functions: Arc<Functions>,
+    /// Variables declared by the user at runtime with `:=`. Their expression is
+    /// evaluated every time they are read.
+    pub variables: Arc<HashMap<String, Box<Expression>>>,
+    /// Variables assigned by the user at runtime with `=`.
+    pub values: Arc<HashMap<String, Value>>,
 
     // Settings
     /// The decimal digits to display.
@@ -22,20 +71,57 @@ pub struct Context {
     pub angle_unit: settings::AngleUnit,
     /// Depth limit for recursion control. .
     pub depth_limit: settings::DepthLimit,
+    /// The seed of the random number generator.
+    pub seed: settings::Seed,
+    /// What a script does when one of its statements fails.
+    pub on_error: settings::OnError,
+    /// The number of changes that can be undone.
+    pub undo_limit: settings::UndoLimit,
+    /// The language of the error messages.
+    pub locale: settings::Locale,
+    /// How ambiguous input is read.
+    pub syntax: settings::Syntax,
+
+    /// The random number generator and the seed it was created from. It is shared with
+    /// the copies of the context, so that they draw from the same sequence.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    rng: Arc<Mutex<Option<(settings::Seed, StdRng)>>>,
+    /// The identifier of the next source of uncertainty. It is shared with the copies
+    /// of the context, so that their measurements are independent of each other.
+    #[cfg_attr(feature = "serde", serde(default))]
+    sources: Arc<AtomicU64>,
+    /// The scope of the user-defined function being evaluated, if any.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    scope: Option<Arc<Scope>>,
+    /// The changes that can be undone and redone.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    history: Arc<History>,
 }
 
-impl Context {
+impl Default for Context {
     /// Generates an empty context with default settings.
-    pub fn default() -> Self {
+    fn default() -> Self {
         Self {
-            functions: HashMap::new(),
-            variables: HashMap::new(),
+            functions: Arc::default(),
+            variables: Arc::default(),
+            values: Arc::default(),
             rounding: settings::Rounding::default(),
             angle_unit: settings::AngleUnit::default(),
             depth_limit: settings::DepthLimit::default(),
+            seed: settings::Seed::default(),
+            on_error: settings::OnError::default(),
+            undo_limit: settings::UndoLimit::default(),
+            locale: settings::Locale::default(),
+            syntax: settings::Syntax::default(),
+            rng: Arc::default(),
+            sources: Arc::default(),
+            scope: None,
+            history: Arc::default(),
         }
     }
+}
 
+impl Context {
     /// Generates an empty context.
     pub fn new(
         rounding: Rounding,
@@ -43,32 +129,249 @@ impl Context {
         depth_limit: settings::DepthLimit,
     ) -> Self {
         Self {
-            functions: HashMap::new(),
-            variables: HashMap::new(),
             rounding,
             angle_unit,
             depth_limit,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the seed of the random number generator, restarting its sequence.
+    ///
+    /// Copies of the context made before are not affected.
+    pub fn set_seed(&mut self, seed: settings::Seed) {
+        self.seed = seed;
+        self.rng = Arc::default();
+    }
+
+    /// Returns the message of an error in the language set by the `locale` setting.
+    pub fn error_message(&self, error: &ErrorType) -> String {
+        error.message(self.locale.catalog())
+    }
+
+    /// Runs the provided function with the random number generator of the context.
+    pub fn with_rng<T, F>(&self, f: F) -> T
+    where
+        F: FnOnce(&mut StdRng) -> T,
+    {
+        // The generator is always left in a valid state, so a poisoned lock can be used.
+        let mut state = self.rng.lock().unwrap_or_else(|err| err.into_inner());
+        match &mut *state {
+            Some((seed, rng)) if *seed == self.seed => f(rng),
+            _ => {
+                let (_, rng) = state.insert((self.seed, self.seed.generator()));
+                f(rng)
+            }
+        }
+    }
+
+    /// Returns a new identifier for an independent source of uncertainty.
+    pub(crate) fn next_source(&self) -> u64 {
+        self.sources.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Returns a copy of the context in the scope of a function call with the provided
+    /// parameters. The declarations and the history are shared, so only the parameters
+    /// are allocated.
+    pub(crate) fn with_scope(&self, params: Vec<(String, Value)>) -> Self {
+        Self {
+            scope: Some(Arc::new(Scope::new(params))),
+            ..self.clone()
+        }
+    }
+
+    /// Returns the context in the global scope, where the user-defined variables are
+    /// evaluated.
+    pub(crate) fn global(&self) -> Cow<'_, Self> {
+        match self.scope {
+            Some(_) => Cow::Owned(Self {
+                scope: None,
+                ..self.clone()
+            }),
+            None => Cow::Borrowed(self),
+        }
+    }
+
+    /// Returns the value of a parameter of the function being evaluated.
+    pub(crate) fn get_local(&self, identifier: &str) -> Option<&Value> {
+        self.scope.as_ref()?.get(identifier)
+    }
+
+    /// Returns the identifiers of the parameters of the function being evaluated.
+    pub(crate) fn local_identifiers(&self) -> Vec<&str> {
+        match &self.scope {
+            Some(scope) => scope.identifiers().collect(),
+            None => vec![],
         }
     }
 
     /// Add all the functions and variables of another context to this one.
     pub fn join_with(&mut self, context: &Self) {
-        for (identifier, (params, body)) in context.functions.clone() {
-            self.add_function(identifier, params, body);
+        for (identifier, (params, body)) in context.functions.iter() {
+            self.add_function(identifier.clone(), params.clone(), body.clone());
+        }
+        for (identifier, expression) in context.variables.iter() {
+            self.add_variable(identifier.clone(), expression.clone())
         }
-        for (identifier, expression) in context.variables.clone() {
-            self.add_variable(identifier, expression)
+        for (identifier, value) in context.values.iter() {
+            self.add_value(identifier.clone(), value.clone())
         }
     }
 
     /// Add a function to the user-defined ones.
     pub fn add_function(&mut self, identifier: String, params: Vec<String>, body: Box<Expression>) {
-        self.functions.insert(identifier, (params, body));
+        Arc::make_mut(&mut self.functions).insert(identifier, (params, body));
     }
 
-    /// Add a variable to the user-defined ones.
+    /// Add a variable to the user-defined ones. Its expression is evaluated every time
+    /// it is read. It replaces any value assigned to the same identifier.
     pub fn add_variable(&mut self, identifier: String, expression: Box<Expression>) {
-        self.variables.insert(identifier, expression);
+        if self.values.contains_key(&identifier) {
+            Arc::make_mut(&mut self.values).remove(&identifier);
+        }
+        Arc::make_mut(&mut self.variables).insert(identifier, expression);
+    }
+
+    /// Assign a value to a variable. It replaces any expression declared with the
+    /// same identifier.
+    pub fn add_value(&mut self, identifier: String, value: Value) {
+        if self.variables.contains_key(&identifier) {
+            Arc::make_mut(&mut self.variables).remove(&identifier);
+        }
+        Arc::make_mut(&mut self.values).insert(identifier, value);
+    }
+
+    /// Removes a user-defined function. Returns false if it was not declared.
+    pub fn remove_function(&mut self, identifier: &str) -> bool {
+        if self.functions.contains_key(identifier) {
+            Arc::make_mut(&mut self.functions).remove(identifier);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Removes a user-defined variable, either declared with `:=` or assigned with `=`.
+    /// Returns false if it was not declared.
+    pub fn remove_variable(&mut self, identifier: &str) -> bool {
+        if self.variables.contains_key(identifier) {
+            Arc::make_mut(&mut self.variables).remove(identifier);
+            true
+        } else if self.values.contains_key(identifier) {
+            Arc::make_mut(&mut self.values).remove(identifier);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Removes all the user-defined functions and variables. Settings are kept.
+    pub fn clear(&mut self) {
+        self.functions = Arc::default();
+        self.variables = Arc::default();
+        self.values = Arc::default();
+    }
+
+    /// Returns the current declarations, which can be brought back with
+    /// [`restore`](Self::restore). Settings are not included.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            functions: Arc::clone(&self.functions),
+            variables: Arc::clone(&self.variables),
+            values: Arc::clone(&self.values),
+        }
+    }
+
+    /// Replaces the declarations with the ones of a snapshot. Settings are kept.
+    pub fn restore(&mut self, snapshot: Snapshot) {
+        self.functions = snapshot.functions;
+        self.variables = snapshot.variables;
+        self.values = snapshot.values;
+    }
+
+    /// Runs the provided function and, if it fails, restores the declarations and the
+    /// undo history as they were before, so that the context is left untouched.
+    pub fn transaction<T, E, F>(&mut self, f: F) -> Result<T, E>
+    where
+        F: FnOnce(&mut Self) -> Result<T, E>,
+    {
+        let snapshot = self.snapshot();
+        let history = Arc::clone(&self.history);
+
+        let result = f(self);
+        if result.is_err() {
+            self.restore(snapshot);
+            self.history = history;
+        }
+        result
+    }
+
+    /// Records a change made by a request, given the declarations before it, so that it
+    /// can be undone. Changes undone before can no longer be redone.
+    pub(crate) fn record_change(&mut self, before: Snapshot) {
+        let history = Arc::make_mut(&mut self.history);
+        history.redo.clear();
+        history.undo.push(before);
+        if let settings::UndoLimit::Limit(limit) = self.undo_limit {
+            let excess = history.undo.len().saturating_sub(limit);
+            history.undo.drain(..excess);
+        }
+    }
+
+    /// Reverts the last `steps` declarations, assignments and removals made by
+    /// evaluating requests. Returns the number of changes actually undone.
+    ///
+    /// Changes made through the methods of the context are not recorded.
+    pub fn undo(&mut self, steps: usize) -> usize {
+        let steps = steps.min(self.history.undo.len());
+        for _ in 0..steps {
+            let current = self.snapshot();
+            let history = Arc::make_mut(&mut self.history);
+            if let Some(snapshot) = history.undo.pop() {
+                history.redo.push(current);
+                self.restore(snapshot);
+            }
+        }
+        steps
+    }
+
+    /// Applies again the last `steps` undone changes. Returns the number of changes
+    /// actually redone.
+    pub fn redo(&mut self, steps: usize) -> usize {
+        let steps = steps.min(self.history.redo.len());
+        for _ in 0..steps {
+            let current = self.snapshot();
+            let history = Arc::make_mut(&mut self.history);
+            if let Some(snapshot) = history.redo.pop() {
+                history.undo.push(current);
+                self.restore(snapshot);
+            }
+        }
+        steps
+    }
+
+    /// Returns an iterator over the user-defined functions, with their parameters and
+    /// body, in arbitrary order.
+    pub fn iter_functions(&self) -> impl Iterator<Item = (&str, &[String], &Expression)> {
+        self.functions
+            .iter()
+            .map(|(identifier, (params, body))| (&identifier[..], &params[..], &**body))
+    }
+
+    /// Returns an iterator over the variables declared with `:=` and their expression,
+    /// in arbitrary order.
+    pub fn iter_variables(&self) -> impl Iterator<Item = (&str, &Expression)> {
+        self.variables
+            .iter()
+            .map(|(identifier, expression)| (&identifier[..], &**expression))
+    }
+
+    /// Returns an iterator over the variables assigned with `=` and their value, in
+    /// arbitrary order.
+    pub fn iter_values(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.values
+            .iter()
+            .map(|(identifier, value)| (&identifier[..], value))
     }
 
     /// Returns a user-defined function given an identifier.
@@ -76,26 +379,23 @@ impl Context {
         self.functions.get(identifier).cloned()
     }
 
-    /// Returns a user-defined constant given an identifier.
+    /// Returns the expression of a user-defined variable given an identifier.
     pub fn get_var(&self, identifier: &str) -> Option<Box<Expression>> {
         self.variables.get(identifier).cloned()
     }
 
+    /// Returns the value assigned to a variable given an identifier.
+    pub fn get_value(&self, identifier: &str) -> Option<Value> {
+        self.values.get(identifier).cloned()
+    }
+
     /// Returns true if the identifier refers to a user-defined function.
     pub fn is_function(&self, identifier: &str) -> bool {
-        if let Some(_) = self.get_function(identifier) {
-            true
-        } else {
-            false
-        }
+        self.functions.contains_key(identifier)
     }
 
-    /// Returns true if the identifier refers to a user-defined constant.
+    /// Returns true if the identifier refers to a user-defined variable or value.
     pub fn is_var(&self, identifier: &str) -> bool {
-        if let Some(_) = self.get_var(identifier) {
-            true
-        } else {
-            false
-        }
+        self.variables.contains_key(identifier) || self.values.contains_key(identifier)
     }
 }
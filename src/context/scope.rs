@@ -0,0 +1,35 @@
+//!
+//! Contains the local scopes of user-defined function calls.
+//!
+
+use crate::value::Value;
+
+/// The parameters of a user-defined function call.
+///
+/// Identifiers are resolved lexically: first in the scope of the function being
+/// evaluated, then in the global declarations of the context. The values assigned with
+/// `=` are the ones visible where the function was declared, as they are captured in
+/// its body. The parameters of the caller are never visible to the callee.
+#[derive(Debug, Clone)]
+pub struct Scope {
+    variables: Vec<(String, Value)>,
+}
+
+impl Scope {
+    pub fn new(variables: Vec<(String, Value)>) -> Self {
+        Self { variables }
+    }
+
+    /// Returns the value of a parameter given its identifier.
+    pub fn get(&self, identifier: &str) -> Option<&Value> {
+        self.variables
+            .iter()
+            .find(|(name, _)| name == identifier)
+            .map(|(_, value)| value)
+    }
+
+    /// Returns the identifiers of all the parameters.
+    pub fn identifiers(&self) -> impl Iterator<Item = &str> {
+        self.variables.iter().map(|(name, _)| &name[..])
+    }
+}
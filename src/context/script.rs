@@ -0,0 +1,287 @@
+//!
+//! Converts contexts to and from num_parser scripts.
+//!
+
+use std::sync::Arc;
+
+use crate::{
+    objects::Expression,
+    out::{ErrorType, EvalResult},
+};
+
+use super::{settings, Context};
+
+/// The prefix of the lines of a script encoding a setting.
+const DIRECTIVE_PREFIX: &str = "#!";
+
+impl Context {
+    /// Writes the settings and the declarations of the context as a script, which can be
+    /// loaded again with [`from_script`](Self::from_script).
+    ///
+    /// Settings are written as header directives, like `#! rounding: 8`, which are
+    /// comments for `eval_script`. Declarations follow, sorted by name.
+    ///
+    /// ## Examples
+    /// ```
+    /// use num_parser::*;
+    ///
+    /// let mut context = Context::default();
+    /// eval_with_mutable_context("f(x) = 2x + 1", &mut context).unwrap();
+    ///
+    /// assert!(context.to_script().ends_with("f(x) = 2 * x + 1\n"));
+    /// ```
+    pub fn to_script(&self) -> String {
+        let mut script = String::new();
+
+        for (name, value) in self.directives() {
+            script += &format!("{} {}: {}\n", DIRECTIVE_PREFIX, name, value);
+        }
+        script += "\n";
+
+        let variables = self.variable_declarations();
+        let functions = self.function_declarations();
+
+        for declaration in variables.into_iter().chain(functions) {
+            script += &declaration;
+            script += "\n";
+        }
+
+        script
+    }
+
+    /// Creates a context from a script, such as the ones written by
+    /// [`to_script`](Self::to_script).
+    ///
+    /// Header directives set the settings of the context, and settings without a
+    /// directive keep their default value. The script is then evaluated, failing at the
+    /// first error.
+    ///
+    /// ## Examples
+    /// ```
+    /// use num_parser::*;
+    ///
+    /// let context = Context::from_script("
+    ///     #! angle_unit: degree
+    ///     a = 90
+    ///     f(x) = sin(x)
+    /// ").unwrap();
+    ///
+    /// assert_eq!(eval_with_static_context("f(a)", &context).unwrap(), Value::from(1));
+    /// ```
+    pub fn from_script(input: &str) -> EvalResult<Self> {
+        let mut context = Self::default();
+
+        for (index, line) in input.lines().enumerate() {
+            if let Some(directive) = line.trim().strip_prefix(DIRECTIVE_PREFIX) {
+                context
+                    .apply_directive(directive)
+                    .ok_or_else(|| ErrorType::InvalidDirective {
+                        directive: directive.trim().to_owned(),
+                        line: index + 1,
+                    })?;
+            }
+        }
+
+        let on_error = context.on_error;
+        context.on_error = settings::OnError::Stop;
+        for result in crate::api::eval_script(input, &mut context) {
+            result?;
+        }
+        context.on_error = on_error;
+        // Loading is not a change that can be undone.
+        context.history = Arc::default();
+
+        Ok(context)
+    }
+
+    /// Returns the names of the settings and their values, as written in directives.
+    fn directives(&self) -> Vec<(&'static str, String)> {
+        let limit = |limit: Option<String>| limit.unwrap_or_else(|| "none".to_owned());
+
+        vec![
+            (
+                "rounding",
+                limit(match self.rounding {
+                    settings::Rounding::Round(digits) => Some(digits.to_string()),
+                    settings::Rounding::NoRounding => None,
+                }),
+            ),
+            (
+                "angle_unit",
+                match self.angle_unit {
+                    settings::AngleUnit::Radian => "radian",
+                    settings::AngleUnit::Degree => "degree",
+                    settings::AngleUnit::Turn => "turn",
+                }
+                .to_owned(),
+            ),
+            (
+                "depth_limit",
+                limit(match self.depth_limit {
+                    settings::DepthLimit::Limit(depth) => Some(depth.to_string()),
+                    settings::DepthLimit::NoLimit => None,
+                }),
+            ),
+            (
+                "seed",
+                match self.seed {
+                    settings::Seed::Fixed(seed) => seed.to_string(),
+                    settings::Seed::Entropy => "entropy".to_owned(),
+                },
+            ),
+            (
+                "on_error",
+                match self.on_error {
+                    settings::OnError::Stop => "stop",
+                    settings::OnError::Continue => "continue",
+                    settings::OnError::Rollback => "rollback",
+                }
+                .to_owned(),
+            ),
+            (
+                "locale",
+                match self.locale {
+                    settings::Locale::English => "english",
+                    settings::Locale::Italian => "italian",
+                }
+                .to_owned(),
+            ),
+            (
+                "undo_limit",
+                limit(match self.undo_limit {
+                    settings::UndoLimit::Limit(changes) => Some(changes.to_string()),
+                    settings::UndoLimit::NoLimit => None,
+                }),
+            ),
+            (
+                "syntax",
+                match self.syntax {
+                    settings::Syntax::Implicit => "implicit",
+                    settings::Syntax::Strict => "strict",
+                    settings::Syntax::Explicit => "explicit",
+                }
+                .to_owned(),
+            ),
+        ]
+    }
+
+    /// Sets a setting from a directive like `rounding: 8`. Returns `None` if the
+    /// directive is not valid.
+    fn apply_directive(&mut self, directive: &str) -> Option<()> {
+        let (name, value) = directive.split_once(':')?;
+        let value = value.trim();
+        // `none` disables a limit.
+        fn limit<T: std::str::FromStr>(value: &str) -> Option<Option<T>> {
+            match value {
+                "none" => Some(None),
+                number => number.parse().ok().map(Some),
+            }
+        }
+
+        match name.trim() {
+            "rounding" => {
+                self.rounding = match limit(value)? {
+                    Some(digits) => settings::Rounding::Round(digits),
+                    None => settings::Rounding::NoRounding,
+                }
+            }
+            "angle_unit" => {
+                self.angle_unit = match value {
+                    "radian" => settings::AngleUnit::Radian,
+                    "degree" => settings::AngleUnit::Degree,
+                    "turn" => settings::AngleUnit::Turn,
+                    _ => return None,
+                }
+            }
+            "depth_limit" => {
+                self.depth_limit = match limit(value)? {
+                    Some(depth) => settings::DepthLimit::Limit(depth),
+                    None => settings::DepthLimit::NoLimit,
+                }
+            }
+            "seed" => self.set_seed(match value {
+                "entropy" => settings::Seed::Entropy,
+                seed => settings::Seed::Fixed(seed.parse().ok()?),
+            }),
+            "on_error" => {
+                self.on_error = match value {
+                    "stop" => settings::OnError::Stop,
+                    "continue" => settings::OnError::Continue,
+                    "rollback" => settings::OnError::Rollback,
+                    _ => return None,
+                }
+            }
+            "locale" => {
+                self.locale = match value {
+                    "english" => settings::Locale::English,
+                    "italian" => settings::Locale::Italian,
+                    _ => return None,
+                }
+            }
+            "undo_limit" => {
+                self.undo_limit = match limit(value)? {
+                    Some(changes) => settings::UndoLimit::Limit(changes),
+                    None => settings::UndoLimit::NoLimit,
+                }
+            }
+            "syntax" => {
+                self.syntax = match value {
+                    "implicit" => settings::Syntax::Implicit,
+                    "strict" => settings::Syntax::Strict,
+                    "explicit" => settings::Syntax::Explicit,
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        }
+
+        Some(())
+    }
+
+    /// Returns the user-defined variables written as declarations, sorted by
+    /// identifier.
+    ///
+    /// ```
+    /// use num_parser::*;
+    ///
+    /// let mut context = Context::default();
+    /// eval_with_mutable_context("b := 2a", &mut context).unwrap();
+    /// eval_with_mutable_context("a = -1.5", &mut context).unwrap();
+    ///
+    /// assert_eq!(context.variable_declarations(), vec!["a = -1.5", "b := 2 * a"]);
+    /// ```
+    pub fn variable_declarations(&self) -> Vec<String> {
+        let mut declarations: Vec<(&str, String)> = self
+            .iter_values()
+            .map(|(identifier, value)| {
+                let literal = Expression::Literal(value.clone());
+                (identifier, format!("{} = {}", identifier, literal))
+            })
+            .chain(self.iter_variables().map(|(identifier, expression)| {
+                (identifier, format!("{} := {}", identifier, expression))
+            }))
+            .collect();
+        declarations.sort();
+        declarations
+            .into_iter()
+            .map(|(_, declaration)| declaration)
+            .collect()
+    }
+
+    /// Returns the user-defined functions written as declarations, sorted by
+    /// identifier.
+    pub fn function_declarations(&self) -> Vec<String> {
+        let mut declarations: Vec<(&str, String)> = self
+            .iter_functions()
+            .map(|(identifier, params, body)| {
+                let declaration = format!("{}({}) = {}", identifier, params.join(", "), body);
+                (identifier, declaration)
+            })
+            .collect();
+        declarations.sort();
+        declarations
+            .into_iter()
+            .map(|(_, declaration)| declaration)
+            .collect()
+    }
+}
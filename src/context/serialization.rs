@@ -0,0 +1,43 @@
+//!
+//! Serializes contexts with the version of their format.
+//!
+
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::Context;
+
+/// The version of the format of serialized contexts. It changes whenever a context of
+/// the previous version could not be read correctly.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// A context together with the version of its format.
+#[derive(Serialize, Deserialize)]
+struct VersionedContext {
+    version: u32,
+    #[serde(with = "Context")]
+    context: Context,
+}
+
+impl Serialize for Context {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        VersionedContext {
+            version: FORMAT_VERSION,
+            // The declarations are shared, so the copy is cheap.
+            context: self.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Context {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let versioned = VersionedContext::deserialize(deserializer)?;
+        if versioned.version != FORMAT_VERSION {
+            return Err(D::Error::custom(format!(
+                "unsupported context format version {}, expected {}",
+                versioned.version, FORMAT_VERSION
+            )));
+        }
+        Ok(versioned.context)
+    }
+}
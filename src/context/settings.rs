@@ -4,7 +4,12 @@
 
 use std::f64::consts;
 
-use crate::{EvalResult, Value};
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    locale::{self, MessageCatalog},
+    EvalResult, Value,
+};
 
 /// The number of decimal places shown.
 ///
@@ -28,9 +33,9 @@ pub enum Rounding {
     NoRounding,
 }
 
-impl Rounding {
+impl Default for Rounding {
     /// Returns the rounding default value.
-    pub fn default() -> Self {
+    fn default() -> Self {
         Rounding::Round(8)
     }
 }
@@ -59,12 +64,14 @@ pub enum AngleUnit {
     Turn,
 }
 
-impl AngleUnit {
+impl Default for AngleUnit {
     /// Returns the angle unit default value.
-    pub fn default() -> Self {
+    fn default() -> Self {
         AngleUnit::Radian
     }
+}
 
+impl AngleUnit {
     /// Converts a value from an angle unit to another.
     pub fn convert_value(self, to: Self, value: Value) -> EvalResult<Value> {
         let as_radians = match self {
@@ -110,9 +117,170 @@ pub enum DepthLimit {
     NoLimit,
 }
 
-impl DepthLimit {
+impl Default for DepthLimit {
     /// Return the depth limit default value.
-    pub fn default() -> Self {
+    fn default() -> Self {
         DepthLimit::Limit(49)
     }
 }
+
+/// The seed of the random number generator.
+///
+/// ## Examples
+/// ```
+/// use num_parser::*;
+///
+/// let mut my_context = Context::default();
+/// my_context.set_seed(settings::Seed::Fixed(42));
+///
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Seed {
+    /// A fixed seed. Random results are reproducible.
+    Fixed(u64),
+    /// A seed taken from the operating system.
+    Entropy,
+}
+
+impl Default for Seed {
+    /// Returns the seed default value.
+    fn default() -> Self {
+        Seed::Entropy
+    }
+}
+
+impl Seed {
+    /// Creates a random number generator from the seed.
+    pub fn generator(self) -> StdRng {
+        match self {
+            Self::Fixed(seed) => StdRng::seed_from_u64(seed),
+            Self::Entropy => StdRng::from_entropy(),
+        }
+    }
+}
+
+/// What a script does when one of its statements fails.
+///
+/// ## Examples
+/// ```
+/// use num_parser::*;
+///
+/// let mut my_context = Context::default();
+/// my_context.on_error = settings::OnError::Continue;
+///
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OnError {
+    /// Stop at the first failing statement.
+    Stop,
+    /// Run the following statements anyway.
+    Continue,
+    /// Stop at the first failing statement and revert the changes made by the script.
+    Rollback,
+}
+
+impl Default for OnError {
+    /// Returns the script error behaviour default value.
+    fn default() -> Self {
+        OnError::Stop
+    }
+}
+
+/// The number of changes made by requests that can be undone.
+///
+/// ## Examples
+/// ```
+/// use num_parser::*;
+///
+/// let mut my_context = Context::default();
+/// my_context.undo_limit = settings::UndoLimit::Limit(10);
+///
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UndoLimit {
+    /// Keep only the last changes.
+    Limit(usize),
+    /// Keep every change.
+    NoLimit,
+}
+
+impl Default for UndoLimit {
+    /// Returns the undo limit default value.
+    fn default() -> Self {
+        UndoLimit::Limit(100)
+    }
+}
+
+/// The language of the error messages returned by `Context::error_message`.
+///
+/// ## Examples
+/// ```
+/// use num_parser::*;
+///
+/// let mut my_context = Context::default();
+/// my_context.locale = settings::Locale::Italian;
+///
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Locale {
+    /// Messages in English.
+    English,
+    /// Messages in Italian.
+    Italian,
+}
+
+impl Default for Locale {
+    /// Returns the locale default value.
+    fn default() -> Self {
+        Locale::English
+    }
+}
+
+impl Locale {
+    /// Returns the message catalog of the language.
+    pub fn catalog(&self) -> &'static dyn MessageCatalog {
+        match self {
+            Self::English => &locale::English,
+            Self::Italian => &locale::Italian,
+        }
+    }
+}
+
+/// How ambiguous input is read.
+///
+/// ## Examples
+/// ```
+/// use num_parser::*;
+///
+/// let mut my_context = Context::default();
+/// my_context.syntax = settings::Syntax::Strict;
+///
+/// assert!(eval_with_static_context("2pi", &my_context).is_err());
+/// assert!(eval_with_static_context("2 * pi", &my_context).is_ok());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Syntax {
+    /// Identifiers are split into known names, and missing multiplications and
+    /// function brackets are added, like in `2pisinx`.
+    Implicit,
+    /// What would be read implicitly is an error, except for imaginary numbers
+    /// like `2i`.
+    Strict,
+    /// Like `Strict`, and identifiers are never split, so that `xy` is a single
+    /// name. Names can also contain underscores, and digits after their first char,
+    /// like `k_ab` or `rate2`. New names longer than one letter can only be declared
+    /// with this syntax.
+    Explicit,
+}
+
+impl Default for Syntax {
+    /// Returns the syntax default value.
+    fn default() -> Self {
+        Syntax::Implicit
+    }
+}
@@ -0,0 +1,107 @@
+//!
+//! Finds the declarations closest to an unknown identifier.
+//!
+
+use crate::function::builtin;
+
+use super::Context;
+
+/// The maximum number of suggestions returned.
+const MAX_SUGGESTIONS: usize = 3;
+
+impl Context {
+    /// Returns the functions, built-in or user-defined, whose name is closest to the
+    /// provided one.
+    pub(crate) fn suggest_functions(&self, identifier: &str) -> Vec<String> {
+        let functions = builtin::get_built_in_functions_vec();
+        let candidates = functions
+            .iter()
+            .map(|func| func.func_identifier)
+            .chain(self.functions.keys().map(|name| &name[..]));
+
+        suggest(identifier, candidates)
+    }
+
+    /// Returns the variables, built-in or user-defined, whose name is closest to the
+    /// provided one, including the parameters of the function being evaluated.
+    pub(crate) fn suggest_vars(&self, identifier: &str) -> Vec<String> {
+        let consts = builtin::get_built_in_consts_map();
+        let candidates = consts
+            .iter()
+            .map(|(name, _)| *name)
+            .chain(self.variables.keys().map(|name| &name[..]))
+            .chain(self.values.keys().map(|name| &name[..]))
+            .chain(self.local_identifiers());
+
+        suggest(identifier, candidates)
+    }
+}
+
+/// Returns the candidates closest to the identifier, ranked by edit distance and then
+/// by the length of the common prefix.
+fn suggest<'a>(identifier: &str, candidates: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let original = identifier;
+    let identifier = identifier.to_lowercase();
+    // Allow more typos in longer names. Any letter is one typo away from another one,
+    // so single letters only match a different case.
+    let max_distance = match identifier.chars().count() {
+        0..=1 => 0,
+        2..=4 => 1,
+        5..=8 => 2,
+        _ => 3,
+    };
+
+    let mut ranked = candidates
+        .filter(|candidate| *candidate != original)
+        .filter_map(|candidate| {
+            let lowercase = candidate.to_lowercase();
+            let distance = edit_distance(&identifier, &lowercase);
+            let prefix = common_prefix(&identifier, &lowercase);
+            // A name starting with the identifier is likely, however long it is.
+            let length = identifier.chars().count();
+            let is_completion = length >= 3 && prefix == length;
+            if distance <= max_distance || is_completion {
+                Some((distance, std::cmp::Reverse(prefix), candidate))
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    ranked.sort();
+    ranked.dedup_by(|a, b| a.2 == b.2);
+    ranked
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, _, candidate)| candidate.to_owned())
+        .collect()
+}
+
+/// Returns the number of insertions, deletions, substitutions and transpositions of
+/// adjacent characters needed to turn one string into the other.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    // The distances between the prefixes of `a` and `b`, one row for each prefix of `a`.
+    let mut rows = vec![(0..=b.len()).collect::<Vec<usize>>()];
+
+    for i in 1..=a.len() {
+        let mut row = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            row[j] = (rows[i - 1][j] + 1)
+                .min(row[j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(rows[i - 2][j - 2] + 1);
+            }
+        }
+        rows.push(row);
+    }
+
+    rows[a.len()][b.len()]
+}
+
+/// Returns the number of characters the two strings start with in common.
+fn common_prefix(a: &str, b: &str) -> usize {
+    a.chars().zip(b.chars()).take_while(|(a, b)| a == b).count()
+}
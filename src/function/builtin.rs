@@ -5,12 +5,17 @@
 use std::collections::HashMap;
 
 use crate::{
-    create_func, decl_func, function::Function, function::*, out::ErrorType, read_vec_values,
-    value::Value, EvalResult, ValueType,
+    create_func, decl_func,
+    function::{distributions::Distribution, Function, *},
+    out::ErrorType,
+    read_vec_values,
+    settings::AngleUnit,
+    value::{dual::Dual, interval, FloatValue, Value},
+    EvalResult, ValueType,
 };
 use lazy_static::*;
 use num::complex::ComplexFloat;
-use rand::Rng;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
 use std::sync::RwLock;
 use tuple_conv::RepeatedTuple;
 
@@ -25,6 +30,7 @@ lazy_static! {
         m.insert("e", Value::Float(consts::E));
         m.insert("tau", Value::Float(consts::TAU));
         m.insert("phi", Value::Float(1.618_033_988_749_894));
+        m.insert("inf", Value::Float(f64::INFINITY));
 
         // Literal values
         m.insert("true", Value::Bool(true));
@@ -42,12 +48,48 @@ lazy_static! {
         create_func!(round, Arguments::Const(1)),
         create_func!(abs, Arguments::Const(1)),
         create_func!(sqrt, Arguments::Const(1)),
+        create_func!(cbrt, Arguments::Const(1)),
         create_func!(ln, Arguments::Const(1)),
         create_func!(log, Arguments::Const(2)),
         create_func!(exp, Arguments::Const(1)),
-        create_func!(rand, Arguments::Const(2)),
+
+        create_func!(popcount, Arguments::Const(1)),
+        create_func!(bitand, Arguments::Const(2)),
+        create_func!(bitor, Arguments::Const(2)),
+
+        create_func!(rand, Arguments::Dynamic),
+        create_func!(uniform, Arguments::Dynamic),
+        create_func!(randint, Arguments::Dynamic),
+        create_func!(randn, Arguments::Between(0, 1)),
+        create_func!(normal, Arguments::Dynamic),
+        create_func!(exponential, Arguments::Dynamic),
+        create_func!(poisson, Arguments::Dynamic),
+        create_func!(binomial, Arguments::Dynamic),
+        create_func!(choice, Arguments::Dynamic),
+        create_func!(shuffle, Arguments::Dynamic),
+
+        create_func!(normalpdf, Arguments::Const(3)),
+        create_func!(normalcdf, Arguments::Const(3)),
+        create_func!(normalquantile, Arguments::Const(3)),
+        create_func!(uniformpdf, Arguments::Const(3)),
+        create_func!(uniformcdf, Arguments::Const(3)),
+        create_func!(uniformquantile, Arguments::Const(3)),
+        create_func!(exponentialpdf, Arguments::Const(2)),
+        create_func!(exponentialcdf, Arguments::Const(2)),
+        create_func!(exponentialquantile, Arguments::Const(2)),
+        create_func!(poissonpdf, Arguments::Const(2)),
+        create_func!(poissoncdf, Arguments::Const(2)),
+        create_func!(poissonquantile, Arguments::Const(2)),
+        create_func!(binomialpdf, Arguments::Const(3)),
+        create_func!(binomialcdf, Arguments::Const(3)),
+        create_func!(binomialquantile, Arguments::Const(3)),
 
         create_func!(branch, Arguments::Const(3)),
+        Function::new("if", branch, Arguments::Const(3)),
+        create_func!(piecewise, Arguments::Dynamic),
+
+        create_func!(grad, Arguments::Const(2)),
+        create_func!(jacobian, Arguments::Const(2)),
 
         create_func!(sin, Arguments::Const(1)),
         create_func!(cos, Arguments::Const(1)),
@@ -61,6 +103,7 @@ lazy_static! {
         create_func!(asinh, Arguments::Const(1)),
         create_func!(acosh, Arguments::Const(1)),
         create_func!(atanh, Arguments::Const(1)),
+        create_func!(deg, Arguments::Const(1)),
 
         create_func!(re, Arguments::Const(1)),
         create_func!(im, Arguments::Const(1)),
@@ -87,6 +130,14 @@ pub fn get_built_in_const(identifier: &str) -> Option<Value> {
         .map(|x| x.1.clone())
 }
 
+/// The commands, which can only be used on their own.
+pub const COMMANDS: [&str; 3] = ["clear", "vars", "funcs"];
+
+/// Returns true if the identifier is a command.
+pub fn is_command(identifier: &str) -> bool {
+    COMMANDS.contains(&identifier)
+}
+
 /// Returns all reserved keywords.
 pub fn reserved_keywords<'a>() -> Vec<&'a str> {
     [
@@ -98,6 +149,7 @@ pub fn reserved_keywords<'a>() -> Vec<&'a str> {
             .iter()
             .map(|x| x.func_identifier)
             .collect::<Vec<&str>>(),
+        COMMANDS.to_vec(),
     ]
     .concat()
 }
@@ -113,7 +165,7 @@ pub fn get_built_in_consts_map() -> Vec<(&'static str, Value)> {
         .read()
         .unwrap()
         .iter()
-        .map(|x| (x.0.clone(), x.1.clone()))
+        .map(|(&identifier, value)| (identifier, value.clone()))
         .collect()
 }
 
@@ -156,18 +208,27 @@ pub fn remove_built_in_const(const_identifier: &str) -> Option<Value> {
 
 // STD
 
+/// Returns the element chosen by `min` or `max`. Measured values and dual numbers are
+/// compared by their value, but are returned whole.
+fn pick(elem: &Value) -> EvalResult<Value> {
+    match elem {
+        Value::Uncertain(_) | Value::Dual(_) => Ok(elem.clone()),
+        other => Ok(Value::Float(other.as_float()?)),
+    }
+}
+
 decl_func!(
     min,
     FunctionType::Std,
     |v| {
         let vec = v.as_vector();
-        let mut min = vec[0].as_float()?;
-        for elem in vec {
-            if elem.as_float()? < min {
-                min = elem.as_float()?;
+        let mut min = &vec[0];
+        for elem in &vec {
+            if elem.as_mean()? < min.as_mean()? {
+                min = elem;
             }
         }
-        Ok(Value::Float(min))
+        pick(min)
     },
     ValueType::VectorType
 );
@@ -177,13 +238,13 @@ decl_func!(
     FunctionType::Std,
     |v| {
         let vec = v.as_vector();
-        let mut max = vec[0].as_float()?;
-        for elem in vec {
-            if elem.as_float()? > max {
-                max = elem.as_float()?;
+        let mut max = &vec[0];
+        for elem in &vec {
+            if elem.as_mean()? > max.as_mean()? {
+                max = elem;
             }
         }
-        Ok(Value::Float(max))
+        pick(max)
     },
     ValueType::VectorType
 );
@@ -191,42 +252,104 @@ decl_func!(
 decl_func!(
     floor,
     FunctionType::Std,
-    |v| Ok(v.as_float()?.floor()),
+    |v: Value| match v {
+        Value::Interval(lower, upper) => Ok(interval::monotonic((lower, upper), true, f64::floor)),
+        v @ (Value::Uncertain(_) | Value::Dual(_)) => v.propagate(f64::floor, |_| 0.0),
+        v => Ok(Value::Float(v.as_float()?.floor())),
+    },
     ValueType::FloatType
 );
 
 decl_func!(
     ceil,
     FunctionType::Std,
-    |v| Ok(v.as_float()?.ceil()),
+    |v: Value| match v {
+        Value::Interval(lower, upper) => Ok(interval::monotonic((lower, upper), true, f64::ceil)),
+        v @ (Value::Uncertain(_) | Value::Dual(_)) => v.propagate(f64::ceil, |_| 0.0),
+        v => Ok(Value::Float(v.as_float()?.ceil())),
+    },
     ValueType::FloatType
 );
 
 decl_func!(
     round,
     FunctionType::Std,
-    |v| Ok(v.as_float()?.round()),
+    |v: Value| match v {
+        Value::Interval(lower, upper) => Ok(interval::monotonic((lower, upper), true, f64::round)),
+        v @ (Value::Uncertain(_) | Value::Dual(_)) => v.propagate(f64::round, |_| 0.0),
+        v => Ok(Value::Float(v.as_float()?.round())),
+    },
     ValueType::FloatType
 );
 
 decl_func!(
     abs,
     FunctionType::Std,
-    |v: Value| Ok(Value::Float(v.as_complex()?.abs())),
+    |v: Value| match v {
+        Value::Interval(lower, upper) => Ok(interval::abs((lower, upper))),
+        v @ (Value::Uncertain(_) | Value::Dual(_)) => v.propagate(f64::abs, f64::signum),
+        v => Ok(Value::Float(v.as_complex()?.abs())),
+    },
     ValueType::ComplexType
 );
 
 decl_func!(
     sqrt,
     FunctionType::Std,
-    |v: Value| Ok(Value::Complex(v.as_complex()?.sqrt())),
+    |v: Value| match v {
+        Value::Interval(lower, upper) => interval::monotonic_in_domain(
+            "sqrt",
+            (lower, upper),
+            (0.0, f64::INFINITY),
+            true,
+            f64::sqrt
+        ),
+        v @ (Value::Uncertain(_) | Value::Dual(_)) => v.propagate_in_domain(
+            "sqrt",
+            (0.0, f64::INFINITY),
+            f64::sqrt,
+            |x| 0.5 / x.sqrt()
+        ),
+        v => Ok(Value::Complex(v.as_complex()?.sqrt())),
+    },
+    ValueType::ComplexType
+);
+
+decl_func!(
+    cbrt,
+    FunctionType::Std,
+    |v: Value| match v {
+        Value::Interval(lower, upper) => Ok(interval::monotonic((lower, upper), true, f64::cbrt)),
+        v @ (Value::Uncertain(_) | Value::Dual(_)) =>
+            v.propagate(f64::cbrt, |x| 1.0 / (3.0 * x.cbrt().powi(2))),
+        v => {
+            let c = v.as_complex()?;
+            // Real numbers have a real cube root, like `cbrt(-8) = -2`.
+            if c.im == 0.0 {
+                Ok(Value::Float(c.re.cbrt()))
+            } else {
+                Ok(Value::Complex(c.cbrt()))
+            }
+        }
+    },
     ValueType::ComplexType
 );
 
 decl_func!(
     ln,
     FunctionType::Std,
-    |v| Ok(v.as_complex()?.ln()),
+    |v: Value| match v {
+        Value::Interval(lower, upper) => interval::monotonic_in_domain(
+            "ln",
+            (lower, upper),
+            (0.0, f64::INFINITY),
+            true,
+            f64::ln
+        ),
+        v @ (Value::Uncertain(_) | Value::Dual(_)) =>
+            v.propagate_in_domain("ln", (0.0, f64::INFINITY), f64::ln, |x| 1.0 / x),
+        v => Ok(Value::Complex(v.as_complex()?.ln())),
+    },
     ValueType::ComplexType
 );
 
@@ -235,7 +358,14 @@ decl_func!(
     FunctionType::Std,
     |v| {
         read_vec_values!(v, base, argument);
-        Ok(argument.as_complex()?.log(base.as_float()?))
+        if base.is_dual() || argument.is_dual() {
+            // The change of base formula, `log(b, x) = ln(x) / ln(b)`.
+            let ln = |value: &Value| {
+                value.propagate_in_domain("log", (0.0, f64::INFINITY), f64::ln, |x| 1.0 / x)
+            };
+            return ln(argument)?.div(ln(base)?);
+        }
+        Ok(Value::from(argument.as_complex()?.log(base.as_float()?)))
     },
     ValueType::VectorType
 );
@@ -243,116 +373,634 @@ decl_func!(
 decl_func!(
     exp,
     FunctionType::Std,
-    |v| Ok(v.as_complex()?.exp()),
+    |v: Value| match v {
+        Value::Interval(lower, upper) => Ok(interval::monotonic((lower, upper), true, f64::exp)),
+        v @ (Value::Uncertain(_) | Value::Dual(_)) => v.propagate(f64::exp, f64::exp),
+        v => Ok(Value::Complex(v.as_complex()?.exp())),
+    },
     ValueType::ComplexType
 );
 
+// BITWISE
+
+decl_func!(
+    popcount,
+    FunctionType::Std,
+    |v: Value| Ok(Value::Int(v.as_int()?.count_ones().into())),
+    ValueType::IntType
+);
+
+decl_func!(
+    bitand,
+    FunctionType::Std,
+    |v| {
+        read_vec_values!(v, lhs, rhs);
+        lhs.clone().bitwise_and(rhs.clone())
+    },
+    ValueType::VectorType
+);
+
 decl_func!(
-    rand,
+    bitor,
     FunctionType::Std,
     |v| {
-        read_vec_values!(v, min, max);
-        Ok(Value::Float(
-            rand::thread_rng().gen_range(min.as_float()?..max.as_float()?),
-        ))
+        read_vec_values!(v, lhs, rhs);
+        lhs.clone().bitwise_or(rhs.clone())
+    },
+    ValueType::VectorType
+);
+
+// RANDOM
+
+/// Evaluates the arguments of a sampling function: the parameters of the distribution,
+/// optionally followed by the number of values to draw.
+fn read_sampling_arguments(
+    func_name: &str,
+    arguments: &[Box<Expression>],
+    context: &Context,
+    depth: u32,
+    params: usize,
+) -> EvalResult<(Vec<Value>, Option<usize>)> {
+    if arguments.len() != params && arguments.len() != params + 1 {
+        return Err(ErrorType::WrongFunctionArgumentsAmount {
+            func_name: func_name.to_owned(),
+            expected: if arguments.len() < params {
+                params as u8
+            } else {
+                params as u8 + 1
+            },
+            given: arguments.len() as u8,
+        });
+    }
+
+    let mut values = arguments
+        .iter()
+        .map(|argument| argument.eval(context, depth))
+        .collect::<EvalResult<Vec<Value>>>()?;
+
+    let count = match values.get(params) {
+        Some(count) => Some(read_count(func_name, count)?),
+        None => None,
+    };
+    values.truncate(params);
+
+    Ok((values, count))
+}
+
+/// Reads the number of values to draw.
+fn read_count(func_name: &str, count: &Value) -> EvalResult<usize> {
+    let count_value = count.as_int()?;
+    if count_value < 0 {
+        return Err(ErrorType::OutOfDomain {
+            func_name: func_name.to_owned(),
+            value: count.clone(),
+        });
+    }
+    Ok(count_value as usize)
+}
+
+/// Draws a value, or a vector of `count` values, using the generator of the context.
+fn draw<F>(context: &Context, count: Option<usize>, mut draw_one: F) -> EvalResult<Value>
+where
+    F: FnMut(&mut StdRng) -> EvalResult<Value>,
+{
+    context.with_rng(|rng| match count {
+        Some(count) => Ok(Value::Vector(
+            (0..count)
+                .map(|_| draw_one(rng))
+                .collect::<EvalResult<Vec<Value>>>()?,
+        )),
+        None => draw_one(rng),
+    })
+}
+
+fn rand(arguments: &[Box<Expression>], context: &Context, depth: u32) -> EvalResult<Value> {
+    let (params, count) = read_sampling_arguments("rand", arguments, context, depth, 2)?;
+    let distribution = Distribution::uniform("rand", &params[0], &params[1])?;
+    draw(context, count, |rng| distribution.sample(rng))
+}
+
+fn uniform(arguments: &[Box<Expression>], context: &Context, depth: u32) -> EvalResult<Value> {
+    let (params, count) = read_sampling_arguments("uniform", arguments, context, depth, 2)?;
+    let distribution = Distribution::uniform("uniform", &params[0], &params[1])?;
+    draw(context, count, |rng| distribution.sample(rng))
+}
+
+fn randint(arguments: &[Box<Expression>], context: &Context, depth: u32) -> EvalResult<Value> {
+    let (params, count) = read_sampling_arguments("randint", arguments, context, depth, 2)?;
+    let (min, max) = (params[0].as_int()?, params[1].as_int()?);
+    if min > max {
+        return Err(ErrorType::OutOfDomain {
+            func_name: "randint".to_owned(),
+            value: params[1].clone(),
+        });
+    }
+    draw(context, count, |rng| Ok(Value::Int(rng.gen_range(min..=max))))
+}
+
+fn randn(arguments: &[Box<Expression>], context: &Context, depth: u32) -> EvalResult<Value> {
+    let (_, count) = read_sampling_arguments("randn", arguments, context, depth, 0)?;
+    let distribution = Distribution::Normal {
+        mu: 0.0,
+        sigma: 1.0,
+    };
+    draw(context, count, |rng| distribution.sample(rng))
+}
+
+fn normal(arguments: &[Box<Expression>], context: &Context, depth: u32) -> EvalResult<Value> {
+    let (params, count) = read_sampling_arguments("normal", arguments, context, depth, 2)?;
+    let distribution = Distribution::normal("normal", &params[0], &params[1])?;
+    draw(context, count, |rng| distribution.sample(rng))
+}
+
+fn exponential(arguments: &[Box<Expression>], context: &Context, depth: u32) -> EvalResult<Value> {
+    let (params, count) = read_sampling_arguments("exponential", arguments, context, depth, 1)?;
+    let distribution = Distribution::exponential("exponential", &params[0])?;
+    draw(context, count, |rng| distribution.sample(rng))
+}
+
+fn poisson(arguments: &[Box<Expression>], context: &Context, depth: u32) -> EvalResult<Value> {
+    let (params, count) = read_sampling_arguments("poisson", arguments, context, depth, 1)?;
+    let distribution = Distribution::poisson("poisson", &params[0])?;
+    draw(context, count, |rng| distribution.sample(rng))
+}
+
+fn binomial(arguments: &[Box<Expression>], context: &Context, depth: u32) -> EvalResult<Value> {
+    let (params, count) = read_sampling_arguments("binomial", arguments, context, depth, 2)?;
+    let distribution = Distribution::binomial("binomial", &params[0], &params[1])?;
+    draw(context, count, |rng| distribution.sample(rng))
+}
+
+fn choice(arguments: &[Box<Expression>], context: &Context, depth: u32) -> EvalResult<Value> {
+    let mut values = arguments
+        .iter()
+        .map(|argument| argument.eval(context, depth))
+        .collect::<EvalResult<Vec<Value>>>()?;
+
+    // Either `choice(a, b, c, ...)` or `choice(values, count)`.
+    let count = if values.len() == 2 && values[0].is_vector() {
+        let count = read_count("choice", &values[1])?;
+        values.truncate(1);
+        Some(count)
+    } else {
+        None
+    };
+    if values.len() == 1 {
+        values = values[0].as_vector();
+    }
+
+    draw(context, count, |rng| match values.choose(rng) {
+        Some(value) => Ok(value.clone()),
+        None => Err(ErrorType::EmptyUnion),
+    })
+}
+
+fn shuffle(arguments: &[Box<Expression>], context: &Context, depth: u32) -> EvalResult<Value> {
+    let mut values = unbox_parameters(arguments, context, depth)?.as_vector();
+    context.with_rng(|rng| values.shuffle(rng));
+    Ok(Value::Vector(values))
+}
+
+// STATISTICS
+
+decl_func!(
+    normalpdf,
+    FunctionType::Std,
+    |v| {
+        read_vec_values!(v, x, mu, sigma);
+        Distribution::normal("normalpdf", mu, sigma)?.pdf_value(x)
+    },
+    ValueType::VectorType
+);
+
+decl_func!(
+    normalcdf,
+    FunctionType::Std,
+    |v| {
+        read_vec_values!(v, x, mu, sigma);
+        Distribution::normal("normalcdf", mu, sigma)?.cdf_value(x)
+    },
+    ValueType::VectorType
+);
+
+decl_func!(
+    normalquantile,
+    FunctionType::Std,
+    |v| {
+        read_vec_values!(v, p, mu, sigma);
+        Distribution::normal("normalquantile", mu, sigma)?.quantile_value("normalquantile", p)
+    },
+    ValueType::VectorType
+);
+
+decl_func!(
+    uniformpdf,
+    FunctionType::Std,
+    |v| {
+        read_vec_values!(v, x, min, max);
+        Distribution::uniform("uniformpdf", min, max)?.pdf_value(x)
+    },
+    ValueType::VectorType
+);
+
+decl_func!(
+    uniformcdf,
+    FunctionType::Std,
+    |v| {
+        read_vec_values!(v, x, min, max);
+        Distribution::uniform("uniformcdf", min, max)?.cdf_value(x)
+    },
+    ValueType::VectorType
+);
+
+decl_func!(
+    uniformquantile,
+    FunctionType::Std,
+    |v| {
+        read_vec_values!(v, p, min, max);
+        Distribution::uniform("uniformquantile", min, max)?.quantile_value("uniformquantile", p)
+    },
+    ValueType::VectorType
+);
+
+decl_func!(
+    exponentialpdf,
+    FunctionType::Std,
+    |v| {
+        read_vec_values!(v, x, lambda);
+        Distribution::exponential("exponentialpdf", lambda)?.pdf_value(x)
+    },
+    ValueType::VectorType
+);
+
+decl_func!(
+    exponentialcdf,
+    FunctionType::Std,
+    |v| {
+        read_vec_values!(v, x, lambda);
+        Distribution::exponential("exponentialcdf", lambda)?.cdf_value(x)
+    },
+    ValueType::VectorType
+);
+
+decl_func!(
+    exponentialquantile,
+    FunctionType::Std,
+    |v| {
+        read_vec_values!(v, p, lambda);
+        Distribution::exponential("exponentialquantile", lambda)?
+            .quantile_value("exponentialquantile", p)
+    },
+    ValueType::VectorType
+);
+
+decl_func!(
+    poissonpdf,
+    FunctionType::Std,
+    |v| {
+        read_vec_values!(v, x, lambda);
+        Distribution::poisson("poissonpdf", lambda)?.pdf_value(x)
+    },
+    ValueType::VectorType
+);
+
+decl_func!(
+    poissoncdf,
+    FunctionType::Std,
+    |v| {
+        read_vec_values!(v, x, lambda);
+        Distribution::poisson("poissoncdf", lambda)?.cdf_value(x)
+    },
+    ValueType::VectorType
+);
+
+decl_func!(
+    poissonquantile,
+    FunctionType::Std,
+    |v| {
+        read_vec_values!(v, p, lambda);
+        Distribution::poisson("poissonquantile", lambda)?.quantile_value("poissonquantile", p)
+    },
+    ValueType::VectorType
+);
+
+decl_func!(
+    binomialpdf,
+    FunctionType::Std,
+    |v| {
+        read_vec_values!(v, x, n, p);
+        Distribution::binomial("binomialpdf", n, p)?.pdf_value(x)
+    },
+    ValueType::VectorType
+);
+
+decl_func!(
+    binomialcdf,
+    FunctionType::Std,
+    |v| {
+        read_vec_values!(v, x, n, p);
+        Distribution::binomial("binomialcdf", n, p)?.cdf_value(x)
+    },
+    ValueType::VectorType
+);
+
+decl_func!(
+    binomialquantile,
+    FunctionType::Std,
+    |v| {
+        read_vec_values!(v, prob, n, p);
+        Distribution::binomial("binomialquantile", n, p)?.quantile_value("binomialquantile", prob)
     },
     ValueType::VectorType
 );
 
 // LOGIC
 
-fn branch(arguments: &Vec<Box<Expression>>, context: &Context, depth: u32) -> EvalResult<Value> {
-    let condition = arguments[0].eval(context, None, depth)?.as_bool()?;
+fn branch(arguments: &[Box<Expression>], context: &Context, depth: u32) -> EvalResult<Value> {
+    let condition = arguments[0].eval(context, depth)?.as_bool()?;
     if condition {
-        Ok(arguments[1].eval(context, None, depth)?)
+        Ok(arguments[1].eval(context, depth)?)
     } else {
-        Ok(arguments[2].eval(context, None, depth)?)
+        Ok(arguments[2].eval(context, depth)?)
     }
 }
 
+fn piecewise(arguments: &[Box<Expression>], context: &Context, depth: u32) -> EvalResult<Value> {
+    // Conditions and values come in pairs, followed by the default value.
+    if arguments.len() % 2 != 1 {
+        return Err(ErrorType::WrongFunctionArgumentsAmount {
+            func_name: "piecewise".to_owned(),
+            expected: arguments.len() as u8 + 1,
+            given: arguments.len() as u8,
+        });
+    }
+
+    for pair in arguments.chunks_exact(2) {
+        if pair[0].eval(context, depth)?.as_bool()? {
+            return pair[1].eval(context, depth);
+        }
+    }
+    arguments[arguments.len() - 1].eval(context, depth)
+}
+
+// CALCULUS
+
+/// Reads the function and the point passed to a differentiation function.
+fn read_differentiation_arguments(
+    func_name: &str,
+    arguments: &[Box<Expression>],
+    context: &Context,
+    depth: u32,
+) -> EvalResult<(String, Vec<FloatValue>)> {
+    let identifier = match &*arguments[0] {
+        Expression::Var(identifier)
+            if get_built_in_function(identifier).is_some() || context.is_function(identifier) =>
+        {
+            identifier.clone()
+        }
+        _ => {
+            return Err(ErrorType::ExpectedFunctionArgument {
+                func_name: func_name.to_owned(),
+            })
+        }
+    };
+    let point = arguments[1]
+        .eval(context, depth)?
+        .as_vector()
+        .iter()
+        .map(|value| value.as_float())
+        .collect::<EvalResult<Vec<FloatValue>>>()?;
+    Ok((identifier, point))
+}
+
+/// Evaluates the function on dual numbers and returns the partial derivatives of
+/// all of its outputs with respect to the variable at the provided index.
+fn partial_derivatives(
+    identifier: &str,
+    point: &[FloatValue],
+    index: usize,
+    context: &Context,
+    depth: u32,
+) -> EvalResult<Vec<Value>> {
+    let arguments = point
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let dual = if i == index {
+                Dual::variable(*value)
+            } else {
+                Dual::constant(*value)
+            };
+            Box::new(Expression::Literal(Value::Dual(dual)))
+        })
+        .collect();
+
+    Expression::Func(identifier.to_owned(), arguments)
+        .eval(context, depth)?
+        .as_vector()
+        .iter()
+        .map(|output| Ok(Value::Float(output.as_dual()?.derivative)))
+        .collect()
+}
+
+fn grad(arguments: &[Box<Expression>], context: &Context, depth: u32) -> EvalResult<Value> {
+    let (identifier, point) = read_differentiation_arguments("grad", arguments, context, depth)?;
+
+    let mut gradient = vec![];
+    for index in 0..point.len() {
+        let mut derivatives = partial_derivatives(&identifier, &point, index, context, depth)?;
+        if derivatives.len() != 1 {
+            return Err(ErrorType::TypeError {
+                expected: ValueType::FloatType,
+                given: ValueType::VectorType,
+            });
+        }
+        gradient.push(derivatives.remove(0));
+    }
+
+    if gradient.len() == 1 {
+        Ok(gradient.remove(0))
+    } else {
+        Ok(Value::Vector(gradient))
+    }
+}
+
+fn jacobian(arguments: &[Box<Expression>], context: &Context, depth: u32) -> EvalResult<Value> {
+    let (identifier, point) =
+        read_differentiation_arguments("jacobian", arguments, context, depth)?;
+
+    // Every pass returns a column of the matrix.
+    let mut columns = vec![];
+    for index in 0..point.len() {
+        columns.push(partial_derivatives(&identifier, &point, index, context, depth)?);
+    }
+
+    let rows = columns.first().map_or(0, |column| column.len());
+    Ok(Value::Vector(
+        (0..rows)
+            .map(|row| Value::Vector(columns.iter().map(|column| column[row].clone()).collect()))
+            .collect(),
+    ))
+}
+
 // TRIGONOMETRY
 
 decl_func!(
     sin,
     FunctionType::Trig,
-    |v| Ok(v.as_complex()?.sin()),
+    |v: Value| match v {
+        Value::Interval(lower, upper) => Ok(interval::sin((lower, upper))),
+        v @ (Value::Uncertain(_) | Value::Dual(_)) => v.propagate(f64::sin, f64::cos),
+        v => Ok(Value::Complex(v.as_complex()?.sin())),
+    },
     ValueType::ComplexType
 );
 
 decl_func!(
     cos,
     FunctionType::Trig,
-    |v| Ok(v.as_complex()?.cos()),
+    |v: Value| match v {
+        Value::Interval(lower, upper) => Ok(interval::cos((lower, upper))),
+        v @ (Value::Uncertain(_) | Value::Dual(_)) => v.propagate(f64::cos, |x| -x.sin()),
+        v => Ok(Value::Complex(v.as_complex()?.cos())),
+    },
     ValueType::ComplexType
 );
 
 decl_func!(
     tan,
     FunctionType::Trig,
-    |v| Ok(v.as_complex()?.tan()),
+    |v: Value| match v {
+        Value::Interval(lower, upper) => interval::tan((lower, upper)),
+        v @ (Value::Uncertain(_) | Value::Dual(_)) =>
+            v.propagate(f64::tan, |x| 1.0 / (x.cos() * x.cos())),
+        v => Ok(Value::Complex(v.as_complex()?.tan())),
+    },
     ValueType::ComplexType
 );
 
 decl_func!(
     asin,
     FunctionType::InverseTrig,
-    |v| Ok(v.as_complex()?.asin()),
+    |v: Value| match v {
+        Value::Interval(lower, upper) =>
+            interval::monotonic_in_domain("asin", (lower, upper), (-1.0, 1.0), true, f64::asin),
+        v @ (Value::Uncertain(_) | Value::Dual(_)) => v.propagate_in_domain(
+            "asin",
+            (-1.0, 1.0),
+            f64::asin,
+            |x| 1.0 / (1.0 - x * x).sqrt()
+        ),
+        v => Ok(Value::Complex(v.as_complex()?.asin())),
+    },
     ValueType::ComplexType
 );
 
 decl_func!(
     acos,
     FunctionType::InverseTrig,
-    |v| Ok(v.as_complex()?.acos()),
+    |v: Value| match v {
+        Value::Interval(lower, upper) =>
+            interval::monotonic_in_domain("acos", (lower, upper), (-1.0, 1.0), false, f64::acos),
+        v @ (Value::Uncertain(_) | Value::Dual(_)) => v.propagate_in_domain(
+            "acos",
+            (-1.0, 1.0),
+            f64::acos,
+            |x| -1.0 / (1.0 - x * x).sqrt()
+        ),
+        v => Ok(Value::Complex(v.as_complex()?.acos())),
+    },
     ValueType::ComplexType
 );
 
 decl_func!(
     atan,
     FunctionType::InverseTrig,
-    |v| Ok(v.as_complex()?.atan()),
+    |v: Value| match v {
+        Value::Interval(lower, upper) => Ok(interval::monotonic((lower, upper), true, f64::atan)),
+        v @ (Value::Uncertain(_) | Value::Dual(_)) =>
+            v.propagate(f64::atan, |x| 1.0 / (1.0 + x * x)),
+        v => Ok(Value::Complex(v.as_complex()?.atan())),
+    },
     ValueType::ComplexType
 );
 
 decl_func!(
     sinh,
     FunctionType::Trig,
-    |v| Ok(v.as_complex()?.sinh()),
+    |v: Value| match v {
+        v @ (Value::Uncertain(_) | Value::Dual(_)) => v.propagate(f64::sinh, f64::cosh),
+        v => Ok(Value::Complex(v.as_complex()?.sinh())),
+    },
     ValueType::ComplexType
 );
 
 decl_func!(
     cosh,
     FunctionType::Trig,
-    |v| Ok(v.as_complex()?.cosh()),
+    |v: Value| match v {
+        v @ (Value::Uncertain(_) | Value::Dual(_)) => v.propagate(f64::cosh, f64::sinh),
+        v => Ok(Value::Complex(v.as_complex()?.cosh())),
+    },
     ValueType::ComplexType
 );
 
 decl_func!(
     tanh,
     FunctionType::Trig,
-    |v| Ok(v.as_complex()?.tanh()),
+    |v: Value| match v {
+        v @ (Value::Uncertain(_) | Value::Dual(_)) =>
+            v.propagate(f64::tanh, |x| 1.0 / (x.cosh() * x.cosh())),
+        v => Ok(Value::Complex(v.as_complex()?.tanh())),
+    },
     ValueType::ComplexType
 );
 
 decl_func!(
     asinh,
     FunctionType::InverseTrig,
-    |v| Ok(v.as_complex()?.asinh()),
+    |v: Value| match v {
+        v @ (Value::Uncertain(_) | Value::Dual(_)) =>
+            v.propagate(f64::asinh, |x| 1.0 / (x * x + 1.0).sqrt()),
+        v => Ok(Value::Complex(v.as_complex()?.asinh())),
+    },
     ValueType::ComplexType
 );
 
 decl_func!(
     acosh,
     FunctionType::InverseTrig,
-    |v| Ok(v.as_complex()?.acosh()),
+    |v: Value| match v {
+        v @ (Value::Uncertain(_) | Value::Dual(_)) => v.propagate_in_domain(
+            "acosh",
+            (1.0, f64::INFINITY),
+            f64::acosh,
+            |x| 1.0 / (x * x - 1.0).sqrt()
+        ),
+        v => Ok(Value::Complex(v.as_complex()?.acosh())),
+    },
     ValueType::ComplexType
 );
 
 decl_func!(
     atanh,
     FunctionType::InverseTrig,
-    |v| Ok(v.as_complex()?.atanh()),
+    |v: Value| match v {
+        v @ (Value::Uncertain(_) | Value::Dual(_)) => v.propagate_in_domain(
+            "atanh",
+            (-1.0, 1.0),
+            f64::atanh,
+            |x| 1.0 / (1.0 - x * x)
+        ),
+        v => Ok(Value::Complex(v.as_complex()?.atanh())),
+    },
+    ValueType::ComplexType
+);
+
+decl_func!(
+    deg,
+    FunctionType::InverseTrig,
+    |v: Value| AngleUnit::convert_value(AngleUnit::Degree, AngleUnit::Radian, v),
     ValueType::ComplexType
 );
 
@@ -361,34 +1009,53 @@ decl_func!(
 decl_func!(
     re,
     FunctionType::Std,
-    |v| Ok(v.as_complex()?.re),
+    |v: Value| match v {
+        v @ Value::Dual(_) => Ok(v),
+        v => Ok(Value::Float(v.as_complex()?.re)),
+    },
     ValueType::ComplexType
 );
 
 decl_func!(
     im,
     FunctionType::Std,
-    |v| Ok(v.as_complex()?.im),
+    |v: Value| match v {
+        Value::Dual(_) => Ok(Value::Float(0.0)),
+        v => Ok(Value::Float(v.as_complex()?.im)),
+    },
     ValueType::ComplexType
 );
 
 decl_func!(
     polar,
     FunctionType::Std,
-    |v| Ok(v.as_complex()?.to_polar().to_vec()),
+    |v: Value| match v {
+        v @ Value::Dual(dual) => Ok(Value::Vector(vec![
+            v.propagate(f64::abs, f64::signum)?,
+            Value::Float(num::Complex::from(dual.value).arg()),
+        ])),
+        v => Ok(Value::from(v.as_complex()?.to_polar().to_vec())),
+    },
     ValueType::ComplexType
 );
 
 decl_func!(
     arg,
     FunctionType::Std,
-    |v| Ok(v.as_complex()?.arg()),
+    |v: Value| match v {
+        // The argument of a real number is constant.
+        Value::Dual(dual) => Ok(Value::Float(num::Complex::from(dual.value).arg())),
+        v => Ok(Value::Float(v.as_complex()?.arg())),
+    },
     ValueType::ComplexType
 );
 
 decl_func!(
     norm,
     FunctionType::Std,
-    |v| Ok(v.as_complex()?.norm()),
+    |v: Value| match v {
+        v @ Value::Dual(_) => v.propagate(f64::abs, f64::signum),
+        v => Ok(Value::Float(v.as_complex()?.norm())),
+    },
     ValueType::ComplexType
 );
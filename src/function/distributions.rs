@@ -0,0 +1,338 @@
+//!
+//! Contains the probability distributions used by the built-in functions.
+//!
+
+use rand::{rngs::StdRng, Rng};
+use rand_distr::{Binomial, Distribution as _, Exp, Normal, Poisson};
+
+use crate::{
+    out::{ErrorType, EvalResult},
+    value::{FloatValue, Value},
+};
+
+/// A probability distribution and its parameters.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Distribution {
+    /// A normal distribution with mean `mu` and standard deviation `sigma`.
+    Normal { mu: FloatValue, sigma: FloatValue },
+    /// A continuous uniform distribution over `[min, max]`.
+    Uniform { min: FloatValue, max: FloatValue },
+    /// An exponential distribution with rate `lambda`.
+    Exponential { lambda: FloatValue },
+    /// A Poisson distribution with mean `lambda`.
+    Poisson { lambda: FloatValue },
+    /// A binomial distribution of `n` trials with success probability `p`.
+    Binomial { n: u64, p: FloatValue },
+}
+
+/// Returns an `OutOfDomain` error if the condition does not hold.
+fn check(condition: bool, func_name: &str, value: &Value) -> EvalResult<()> {
+    if condition {
+        Ok(())
+    } else {
+        Err(ErrorType::OutOfDomain {
+            func_name: func_name.to_owned(),
+            value: value.clone(),
+        })
+    }
+}
+
+impl Distribution {
+    pub fn normal(func_name: &str, mu: &Value, sigma: &Value) -> EvalResult<Self> {
+        let sigma_value = sigma.as_float()?;
+        check(sigma_value > 0.0, func_name, sigma)?;
+        Ok(Self::Normal {
+            mu: mu.as_float()?,
+            sigma: sigma_value,
+        })
+    }
+
+    pub fn uniform(func_name: &str, min: &Value, max: &Value) -> EvalResult<Self> {
+        let (min_value, max_value) = (min.as_float()?, max.as_float()?);
+        check(min_value < max_value, func_name, max)?;
+        Ok(Self::Uniform {
+            min: min_value,
+            max: max_value,
+        })
+    }
+
+    pub fn exponential(func_name: &str, lambda: &Value) -> EvalResult<Self> {
+        let lambda_value = lambda.as_float()?;
+        check(lambda_value > 0.0, func_name, lambda)?;
+        Ok(Self::Exponential {
+            lambda: lambda_value,
+        })
+    }
+
+    pub fn poisson(func_name: &str, lambda: &Value) -> EvalResult<Self> {
+        let lambda_value = lambda.as_float()?;
+        check(lambda_value > 0.0, func_name, lambda)?;
+        Ok(Self::Poisson {
+            lambda: lambda_value,
+        })
+    }
+
+    pub fn binomial(func_name: &str, n: &Value, p: &Value) -> EvalResult<Self> {
+        let (n_value, p_value) = (n.as_int()?, p.as_float()?);
+        check(n_value >= 0, func_name, n)?;
+        check((0.0..=1.0).contains(&p_value), func_name, p)?;
+        Ok(Self::Binomial {
+            n: n_value as u64,
+            p: p_value,
+        })
+    }
+
+    /// Draws a value from the distribution.
+    pub fn sample(&self, rng: &mut StdRng) -> EvalResult<Value> {
+        fn internal_error<E: std::fmt::Display>(err: E) -> ErrorType {
+            ErrorType::InternalError {
+                message: format!("invalid distribution: {}", err),
+            }
+        }
+
+        Ok(match *self {
+            Self::Normal { mu, sigma } => {
+                Value::Float(Normal::new(mu, sigma).map_err(internal_error)?.sample(rng))
+            }
+            Self::Uniform { min, max } => Value::Float(rng.gen_range(min..=max)),
+            Self::Exponential { lambda } => {
+                Value::Float(Exp::new(lambda).map_err(internal_error)?.sample(rng))
+            }
+            Self::Poisson { lambda } => {
+                let sample: FloatValue = Poisson::new(lambda).map_err(internal_error)?.sample(rng);
+                Value::Int(sample as i64)
+            }
+            Self::Binomial { n, p } => {
+                Value::Int(Binomial::new(n, p).map_err(internal_error)?.sample(rng) as i64)
+            }
+        })
+    }
+
+    /// Returns the probability density at `x`. For discrete distributions, it is the
+    /// probability of `x`.
+    pub fn pdf(&self, x: FloatValue) -> FloatValue {
+        match *self {
+            Self::Normal { mu, sigma } => {
+                let z = (x - mu) / sigma;
+                (-0.5 * z * z).exp() / (sigma * (2.0 * std::f64::consts::PI).sqrt())
+            }
+            Self::Uniform { min, max } => {
+                if x >= min && x <= max {
+                    1.0 / (max - min)
+                } else {
+                    0.0
+                }
+            }
+            Self::Exponential { lambda } => {
+                if x >= 0.0 {
+                    lambda * (-lambda * x).exp()
+                } else {
+                    0.0
+                }
+            }
+            Self::Poisson { lambda } => {
+                if x < 0.0 || x.fract() != 0.0 {
+                    0.0
+                } else {
+                    (x * lambda.ln() - lambda - libm::lgamma(x + 1.0)).exp()
+                }
+            }
+            Self::Binomial { n, p } => {
+                let n = n as FloatValue;
+                if x < 0.0 || x > n || x.fract() != 0.0 {
+                    0.0
+                } else if p == 0.0 || p == 1.0 {
+                    // Avoid the logarithm of zero.
+                    let certain = if p == 0.0 { 0.0 } else { n };
+                    if x == certain {
+                        1.0
+                    } else {
+                        0.0
+                    }
+                } else {
+                    (libm::lgamma(n + 1.0) - libm::lgamma(x + 1.0) - libm::lgamma(n - x + 1.0)
+                        + x * p.ln()
+                        + (n - x) * (1.0 - p).ln())
+                    .exp()
+                }
+            }
+        }
+    }
+
+    /// Returns the probability of a value lower or equal to `x`.
+    pub fn cdf(&self, x: FloatValue) -> FloatValue {
+        match *self {
+            Self::Normal { mu, sigma } => {
+                0.5 * libm::erfc(-(x - mu) / (sigma * std::f64::consts::SQRT_2))
+            }
+            Self::Uniform { min, max } => ((x - min) / (max - min)).clamp(0.0, 1.0),
+            Self::Exponential { lambda } => {
+                if x >= 0.0 {
+                    1.0 - (-lambda * x).exp()
+                } else {
+                    0.0
+                }
+            }
+            Self::Poisson { .. } | Self::Binomial { .. } => {
+                if x < 0.0 {
+                    return 0.0;
+                }
+                let mut sum = 0.0;
+                let mut k = 0.0;
+                while k <= x.floor() {
+                    let term = self.pdf(k);
+                    sum += term;
+                    // Stop once the remaining terms are negligible.
+                    if sum >= 1.0 || (term == 0.0 && sum > 0.0) {
+                        break;
+                    }
+                    k += 1.0;
+                }
+                sum.min(1.0)
+            }
+        }
+    }
+
+    /// Returns true for the distributions of a real variable.
+    fn is_continuous(&self) -> bool {
+        matches!(
+            self,
+            Self::Normal { .. } | Self::Uniform { .. } | Self::Exponential { .. }
+        )
+    }
+
+    /// Returns the derivative of the probability density at `x`. Only defined for
+    /// continuous distributions.
+    fn pdf_derivative(&self, x: FloatValue) -> FloatValue {
+        match *self {
+            Self::Normal { mu, sigma } => -(x - mu) / (sigma * sigma) * self.pdf(x),
+            Self::Exponential { lambda } => -lambda * self.pdf(x),
+            _ => 0.0,
+        }
+    }
+
+    /// Returns the probability density at `x`, propagating measured values and dual
+    /// numbers through continuous distributions.
+    pub fn pdf_value(&self, x: &Value) -> EvalResult<Value> {
+        if self.is_continuous() {
+            x.propagate(|x| self.pdf(x), |x| self.pdf_derivative(x))
+        } else {
+            Ok(Value::Float(self.pdf(x.as_float()?)))
+        }
+    }
+
+    /// Returns the cumulative probability at `x`. See `Distribution::pdf_value`.
+    pub fn cdf_value(&self, x: &Value) -> EvalResult<Value> {
+        if self.is_continuous() {
+            x.propagate(|x| self.cdf(x), |x| self.pdf(x))
+        } else {
+            Ok(Value::Float(self.cdf(x.as_float()?)))
+        }
+    }
+
+    /// Returns the quantile of `p`. See `Distribution::pdf_value`.
+    pub fn quantile_value(&self, func_name: &str, p: &Value) -> EvalResult<Value> {
+        if self.is_continuous() && (p.is_uncertain() || p.is_dual()) {
+            let quantile = self.quantile(func_name, &Value::Float(p.as_mean()?))?;
+            // The derivative of the inverse of the cumulative distribution.
+            p.propagate(|_| quantile, |_| 1.0 / self.pdf(quantile))
+        } else {
+            Ok(Value::Float(self.quantile(func_name, p)?))
+        }
+    }
+
+    /// Returns the lowest value whose cumulative probability is at least `p`.
+    pub fn quantile(&self, func_name: &str, p: &Value) -> EvalResult<FloatValue> {
+        let p_value = p.as_float()?;
+        check((0.0..=1.0).contains(&p_value), func_name, p)?;
+
+        Ok(match *self {
+            Self::Normal { mu, sigma } => mu + sigma * standard_normal_quantile(p_value),
+            Self::Uniform { min, max } => min + p_value * (max - min),
+            Self::Exponential { lambda } => -(1.0 - p_value).ln() / lambda,
+            Self::Poisson { .. } => self.discrete_quantile(p_value, FloatValue::INFINITY),
+            Self::Binomial { n, .. } => self.discrete_quantile(p_value, n as FloatValue),
+        })
+    }
+
+    /// Returns the quantile of a discrete distribution whose values are lower or equal
+    /// to `upper`.
+    fn discrete_quantile(&self, p: FloatValue, upper: FloatValue) -> FloatValue {
+        if p == 1.0 {
+            return upper;
+        }
+        let mut sum = 0.0;
+        let mut k = 0.0;
+        loop {
+            let term = self.pdf(k);
+            sum += term;
+            // The sum may never reach `p` due to rounding, so stop at the end of the tail.
+            if sum >= p || k >= upper || (term == 0.0 && sum > 0.0) {
+                return k;
+            }
+            k += 1.0;
+        }
+    }
+}
+
+/// Returns the quantile of the standard normal distribution, using Acklam's
+/// approximation refined with a step of Halley's method.
+fn standard_normal_quantile(p: FloatValue) -> FloatValue {
+    const A: [FloatValue; 6] = [
+        -3.969683028665376e1,
+        2.209460984245205e2,
+        -2.759285104469687e2,
+        1.38357751867269e2,
+        -3.066479806614716e1,
+        2.506628277459239,
+    ];
+    const B: [FloatValue; 5] = [
+        -5.447609879822406e1,
+        1.615858368580409e2,
+        -1.556989798598866e2,
+        6.680131188771972e1,
+        -1.328068155288572e1,
+    ];
+    const C: [FloatValue; 6] = [
+        -7.784894002430293e-3,
+        -3.223964580411365e-1,
+        -2.400758277161838,
+        -2.549732539343734,
+        4.374664141464968,
+        2.938163982698783,
+    ];
+    const D: [FloatValue; 4] = [
+        7.784695709041462e-3,
+        3.224671290700398e-1,
+        2.445134137142996,
+        3.754408661907416,
+    ];
+    const LOW: FloatValue = 0.02425;
+
+    if p <= 0.0 {
+        return FloatValue::NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return FloatValue::INFINITY;
+    }
+
+    let x = if p < LOW {
+        let q = (-2.0 * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    } else if p <= 1.0 - LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        let q = (-2.0 * (1.0 - p).ln()).sqrt();
+        -(((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+
+    // Refine the approximation to full precision.
+    let e = 0.5 * libm::erfc(-x / std::f64::consts::SQRT_2) - p;
+    let u = e * (2.0 * std::f64::consts::PI).sqrt() * (x * x / 2.0).exp();
+    x - u / (1.0 + x * u / 2.0)
+}
@@ -139,6 +139,7 @@
 //!
 
 pub mod builtin;
+mod distributions;
 
 use crate::{
     objects::Expression,
@@ -150,12 +151,15 @@ use crate::{
 
 /// A function object. You can pass this object to `builtin::add_built_in_function` to
 /// make it available in all evaluations.
+///
+/// With the `serde` feature, a function is serialized as its identifier, and
+/// deserialized by looking up the built-in function with that identifier.
 #[derive(Clone)]
 pub struct Function {
     /// The identifier needed to call this function.
     pub func_identifier: &'static str,
     /// The actual function.
-    pub func: fn(&Vec<Box<Expression>>, &Context, u32) -> EvalResult<Value>,
+    pub func: fn(&[Box<Expression>], &Context, u32) -> EvalResult<Value>,
     /// The function arguments type.
     pub args: Arguments,
 }
@@ -166,8 +170,10 @@ pub struct Function {
 pub enum Arguments {
     /// Expects a constant number of arguments.
     Const(usize),
-    /// Expects any amount greater than one.
+    /// Expects at least one argument.
     Dynamic,
+    /// Expects an amount between the two, both included.
+    Between(usize, usize),
 }
 
 /// The function type. Handles angle conversions.
@@ -186,7 +192,7 @@ impl Function {
     /// Creates a new function with the specified data.
     pub fn new(
         func_identifier: &'static str,
-        func: fn(&Vec<Box<Expression>>, &Context, u32) -> EvalResult<Value>,
+        func: fn(&[Box<Expression>], &Context, u32) -> EvalResult<Value>,
         args: Arguments,
     ) -> Self {
         Self {
@@ -199,9 +205,8 @@ impl Function {
     /// Call a function.
     pub fn call(
         &self,
-        arguments: &Vec<Box<Expression>>,
+        arguments: &[Box<Expression>],
         context: &Context,
-        scope: Option<&Context>,
         depth: u32,
     ) -> EvalResult<Value> {
         match self.args {
@@ -214,16 +219,42 @@ impl Function {
                     });
                 }
             }
-            _ => (),
+            Arguments::Dynamic => {
+                if arguments.is_empty() {
+                    return Err(ErrorType::MissingFunctionParameters {
+                        func_name: self.func_identifier.to_owned(),
+                    });
+                }
+            }
+            Arguments::Between(min, max) => {
+                if arguments.len() < min || arguments.len() > max {
+                    return Err(ErrorType::WrongFunctionArgumentsAmount {
+                        func_name: self.func_identifier.to_owned(),
+                        expected: arguments.len().clamp(min, max) as u8,
+                        given: arguments.len() as u8,
+                    });
+                }
+            }
         }
 
-        let mut joined_context = context.clone();
-        joined_context.join_with(context);
-        if let Some(c) = scope {
-            joined_context.join_with(c);
-        }
+        (self.func)(arguments, context, depth)
+    }
+}
 
-        (self.func)(arguments, &joined_context, depth)
+#[cfg(feature = "serde")]
+impl serde::Serialize for Function {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.func_identifier)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Function {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let identifier = String::deserialize(deserializer)?;
+        builtin::get_built_in_function(&identifier).ok_or_else(|| {
+            serde::de::Error::custom(format!("unknown built-in function `{}`", identifier))
+        })
     }
 }
 
@@ -247,7 +278,13 @@ where
 {
     let original_type = value.get_type();
 
-    let value = value.as_type(&target_type)?;
+    // Intervals and measured values are passed as they are, as they cannot be
+    // converted to other types.
+    let value = if value.is_self_contained() {
+        value
+    } else {
+        value.as_type(&target_type)?
+    };
     // Input angle conversion
     let value = match func_type {
         FunctionType::Trig => {
@@ -274,11 +311,11 @@ where
 /// which can be either a `VectorType` with all the values inside, or any other type if the argument
 /// was just one.
 pub fn unbox_parameters(
-    arguments: &Vec<Box<Expression>>,
+    arguments: &[Box<Expression>],
     context: &Context,
     depth: u32,
 ) -> EvalResult<Value> {
-    Expression::Union(arguments.clone()).eval(context, None, depth)
+    Expression::eval_union(arguments, context, depth)
 }
 
 /// Given a function name, a `FunctionType`, a predicate and a target `ValueType` declares a function. It generates
@@ -320,7 +357,7 @@ pub fn unbox_parameters(
 /// ```
 /// use num_parser::{*, function::*};
 ///
-/// fn hypotenuse(arguments: &Vec<Box<Expression>>, context: &Context, depth: u32) -> EvalResult<Value> {
+/// fn hypotenuse(arguments: &[Box<Expression>], context: &Context, depth: u32) -> EvalResult<Value> {
 ///     let unboxed = unbox_parameters(arguments, context, depth)?;
 ///     type_wrapper(
 ///         unboxed,
@@ -347,7 +384,7 @@ pub fn unbox_parameters(
 macro_rules! decl_func {
     ( $identifier:ident, $func_type:expr, $predicate:expr, $target:expr ) => {
         fn $identifier(
-            arguments: &Vec<Box<Expression>>,
+            arguments: &[Box<Expression>],
             context: &Context,
             depth: u32,
         ) -> EvalResult<Value> {
@@ -402,7 +439,7 @@ macro_rules! read_vec_values {
 /// See `Function::new` for additional information.
 ///
 /// The generated `Function` has the same name as the provided function. The provided function
-/// needs `&Vec<Box<Expression>>`, a `&Context` and a u32 (for depth controls) as parameters and
+/// needs `&[Box<Expression>]`, a `&Context` and a u32 (for depth controls) as parameters and
 /// returns an `EvalResult<Values>`. You can easily declare one using the `decl_func!` macro.
 ///
 /// ## Examples
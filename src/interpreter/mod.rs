@@ -1,4 +1,5 @@
 use crate::{
+    function::builtin,
     objects::Expression,
     objects::Request,
     out::{ErrorType, EvalResult},
@@ -14,44 +15,35 @@ impl Node {
             Self::Func(_, nodes) => {
                 (*nodes.iter().map(|x| &(**x)).collect::<Vec<&Node>>()).to_vec()
             }
-            Self::Literal(_) | Self::Var(_) => vec![],
+            Self::Literal(_) | Self::Var(_) | Self::Invalid => vec![],
             Self::Union(nodes) => (*nodes.iter().map(|x| &(**x)).collect::<Vec<&Node>>()).to_vec(),
+            Self::Comparison(first, chain) => std::iter::once(&**first)
+                .chain(chain.iter().map(|(_, node)| &**node))
+                .collect(),
         }
     }
 }
 
 /// Converts a tree into a comprehensible request by the user.
 pub fn interpret_tree(tree: &Tree) -> EvalResult<Request> {
+    if let Expression::Func(identifier, arguments) = &tree.0 {
+        if builtin::is_command(identifier) {
+            return interpret_command(identifier, arguments);
+        }
+    }
+
     let equal_tokens = match_all(&tree.0, 0, &|node| match node {
-        Expression::Binary(_, token_type, _) => *token_type == TokenType::Equal,
+        Expression::Binary(_, token_type, _) => {
+            *token_type == TokenType::Equal || *token_type == TokenType::ColonEqual
+        }
         _ => false,
     });
 
     if let Some(equals) = equal_tokens {
-        // Function or var declaration
-        if equals.len() > 1 {
-            return Err(ErrorType::InvalidTokenPosition {
-                token: TokenType::Equal,
-            });
-        }
-
         let equal_node_info = &equals[0];
-        // Avoid equals in brackets
-        if equal_node_info.depth != 0 {
-            return Err(ErrorType::InvalidTokenPosition {
-                token: TokenType::Equal,
-            });
-        }
-
-        let (left, right) = match equal_node_info.node {
+        let (left, operator, right) = match equal_node_info.node {
             Expression::Binary(left_expr, operator, right_expr) => {
-                if *operator != TokenType::Equal {
-                    return Err(ErrorType::InternalError {
-                        message: "operator was not of expected type".to_owned(),
-                    });
-                } else {
-                    (&(**left_expr), &(**right_expr))
-                }
+                (&(**left_expr), *operator, &(**right_expr))
             }
             _ => {
                 return Err(ErrorType::InternalError {
@@ -60,7 +52,24 @@ pub fn interpret_tree(tree: &Tree) -> EvalResult<Request> {
             }
         };
 
+        // Function or var declaration
+        if equals.len() > 1 {
+            return Err(ErrorType::InvalidTokenPosition { token: operator });
+        }
+
+        // Avoid equals in brackets
+        if equal_node_info.depth != 0 {
+            return Err(ErrorType::InvalidTokenPosition { token: operator });
+        }
+
         match left {
+            // `=` stores the current value, `:=` the expression.
+            Expression::Var(identifier) if operator == TokenType::Equal => {
+                return Ok(Request::VarAssignment(
+                    identifier.clone(),
+                    Box::new(right.clone()),
+                ))
+            }
             Expression::Var(identifier) => {
                 return Ok(Request::VarDeclaration(
                     identifier.clone(),
@@ -89,6 +98,36 @@ pub fn interpret_tree(tree: &Tree) -> EvalResult<Request> {
     }
 }
 
+/// Converts a command and its arguments into a request.
+fn interpret_command(command: &str, arguments: &[Box<Node>]) -> EvalResult<Request> {
+    match command {
+        "clear" => {
+            let mut identifiers = vec![];
+            for argument in arguments {
+                match &**argument {
+                    Expression::Var(identifier) => identifiers.push(identifier.clone()),
+                    _ => {
+                        return Err(ErrorType::InvalidCommand {
+                            command: command.to_owned(),
+                        })
+                    }
+                }
+            }
+            Ok(Request::Removal(identifiers))
+        }
+        "vars" | "funcs" if !arguments.is_empty() => Err(ErrorType::WrongFunctionArgumentsAmount {
+            func_name: command.to_owned(),
+            expected: 0,
+            given: arguments.len() as u8,
+        }),
+        "vars" => Ok(Request::VarsListing),
+        "funcs" => Ok(Request::FuncsListing),
+        _ => Err(ErrorType::InternalError {
+            message: format!("unknown command `{}`", command),
+        }),
+    }
+}
+
 #[derive(Clone, Debug)]
 struct NodeInfo<'a> {
     node: &'a Node,
@@ -16,14 +16,18 @@
 //!     * Float,
 //!     * [Complex](num::complex::Complex64),
 //!     * Vector
+//!     * Interval: `[1.9 .. 2.1]` or `2 ± 0.1`
+//!     * Measurement: `9.81 +- 0.02`
 //! * Built-in functions
 //! * Built-in constants
 //! * **User-defined functions**: `f(x,y) = xsin(y)+ysin(x)`
-//! * **User-defined var**: `a = pi/2` or `b = a+2`
+//! * **User-defined var**: `a = pi/2`, `b = a+2` or `c := rand(0, 1)`
 //! * Define you own functions with **macros**.
-//! * Understands **ambiguous syntax**, like: `g(x) = pisinx`
+//! * Understands **ambiguous syntax**, like: `g(x) = pisinx`, or an explicit one with names like `k_ab`
 //! * **Recursion**: `f(x) = branch(x<=2, 1, f(x-1)+f(x-2))`
+//! * **Scripts**: `a = 2; f(x) = a*x # comment`
 //! * Serde support
+//! * Parses in **linear time**, even inputs with thousands of terms (`cargo bench`)
 //! * No panicking
 //!
 //! Much more will be implemented in future releases!
@@ -59,6 +63,28 @@
 //! assert_eq!(res, Some(Value::from(55)));
 //! ```
 //!
+//! Running **scripts**, with statements separated by `;` or new lines:
+//!
+//! ```
+//! use num_parser::*;
+//!
+//! let mut context = Context::default();
+//! let results = eval_script("
+//!     // Comments start with `#`, or with `//` at the start of a line only
+//!     a = 2; b = 3  # In meters
+//!     f(x) = a*x + b
+//!     f(10)
+//! ", &mut context);
+//!
+//! assert_eq!(results.len(), 4);
+//! assert_eq!(results[3].as_ref().unwrap(), &Some(Value::from(23)));
+//! ```
+//!
+//! Errors are reported with the statement and the line they occurred on. By default the script
+//! stops at the first one; set the `on_error` setting of the context to
+//! `settings::OnError::Continue` to run the remaining statements anyway, or to
+//! `settings::OnError::Rollback` to stop and revert all the changes made by the script.
+//!
 //! ## Values
 //! **Values** are contained inside the [Value enum](Value), which provides useful functions
 //! to access the contained data:
@@ -89,29 +115,87 @@
 //!
 //! | Operator | Description | Precedence |
 //! |----------|-------------|------------|
-//! | ^  | Exponentiation                                       | 90 |
+//! | ^  | Exponentiation, grouped from the right               | 90 |
 //! | /  | Division                                             | 70 |
 //! | *  | Multiplication                                       | 70 |
 //! | %  | Modulo                                               | 70 |
+//! | // | Integer division, rounded down                       | 70 |
+//! | ±  | Interval from its center and radius                  | 65 |
+//! | +- | Measurement from its value and standard deviation    | 65 |
 //! | +  | Sum                                                  | 60 |
 //! | -  | Subtraction                                          | 60 |
-//! | <  | Less than                                            | 50 |
-//! | >  | Greater than                                         | 50 |
-//! | <= | Less or equal to                                     | 50 |
-//! | >= | Greater or equal to                                  | 50 |
-//! | == | Equal to                                             | 40 |
-//! | != | Not equal to                                         | 40 |
+//! | << | Left shift                                           | 59 |
+//! | >> | Right shift                                          | 59 |
+//! | &  | Bitwise AND                                          | 58 |
+//! | xor | Bitwise XOR                                         | 57 |
+//! | &#124; | Bitwise OR                                       | 56 |
+//! | .. | Interval from its bounds                             | 55 |
+//! | <  | Less than, chainable                                 | 50 |
+//! | >  | Greater than, chainable                              | 50 |
+//! | <= | Less or equal to, chainable                          | 50 |
+//! | >= | Greater or equal to, chainable                       | 50 |
+//! | == | Equal to, chainable                                  | 50 |
+//! | != | Not equal to, chainable                              | 50 |
 //! | && | Logical AND                                          | 30 |
 //! | &#124;&#124; | Logical OR                                 | 20 |
+//! | ? : | Conditional: `cond ? a : b`. Only the taken arm is evaluated | 15 |
 //! | ,  | Aggregation. Creates vectors                         | 10 |
-//! | =  | Assignment. Used for functions and vars declarations | 0  |
+//! | =  | Assignment. Stores the value of a var or declares a function | 0  |
+//! | := | Definition. Declares a var evaluated every time it is read | 0  |
+//!
+//! Bitwise operators and shifts work on ints, so `240 & 60` is `48`, and like the others they are
+//! applied element by element to vectors. As bars also delimit absolute values, `|` is written
+//! with spaces around it next to one, like in `|-3| | 4`.
+//!
+//! Operators with the same precedence are grouped from the left, so `8 / 4 / 2` is `(8 / 4) / 2`, except
+//! for `^` and `? :`, which are grouped from the right: `2^3^2` is `2^(3^2)`, that is `512`.
+//!
+//! Comparisons are chained, so `0 < x <= 10` means `0 < x && x <= 10`, with `x` evaluated once,
+//! and `a < b == c` means `a < b && b == c`. Brackets stop a chain, so `(1 < 2) == true` compares
+//! the result of `1 < 2` to `true`.
+//!
+//! `&&` and `||` do not evaluate their right operand when the left one already determines the
+//! result, so `x != 0 && 1/x > 2` is safe. `branch`, `if`, `piecewise` and `? :` only evaluate
+//! the arms they take.
+//!
+//! `a = 2a` evaluates the right side immediately and stores its value, so it updates `a`.
+//! `b := 2a` stores the expression instead: `b` follows the changes of `a`, and `c := rand(0, 1)`
+//! returns a new number every time it is read.
 //!
 //! **Unary** operators:
 //!
 //! | Operator | Description | Precedence |
 //! |----------|-------------|------------|
 //! | ! | Logical NOT | 80 |
-//! | - | Negation    | 60 |
+//! | ~ | Bitwise NOT | 80 |
+//! | - | Negation    | 75 |
+//!
+//! Negation binds tighter than `*` and looser than `^`, so `-2^2` is `-(2^2)`, that is `-4`, and
+//! `-7 // 2` is `(-7) // 2`, that is `-4`. A `-` following an operator is a negation, so `2 * -3` is
+//! `-6` and `2^-1` is `0.5`. Operators of more than one char are written without spaces, so `2 + -3`
+//! is `-1`. A measurement is written with spaces around `+-`, so `2 +- 3` is a measurement while
+//! `2+-3` and `2 +-3` are `-1`.
+//!
+//! **Postfix** operators follow their operand:
+//!
+//! | Operator | Description | Precedence |
+//! |----------|-------------|------------|
+//! | !  | Factorial: `5!` is `120`                      | 95 |
+//! | !! | Double factorial: `7!!` is `7*5*3*1`          | 95 |
+//! | %  | Percentage: `20%` is `0.2`                    | 95 |
+//!
+//! A `%` followed by an operand is a modulo, so `7 % 3` is `1`, and a `!` followed by `=` is part of `!=`.
+//!
+//! `|x|` is the absolute value of `x`, and `⌊x⌋` and `⌈x⌉` are its floor and ceiling. A bar following an
+//! operand closes the last one open within the same brackets, so `|(1|2)|` is `3`. It is the `|` operator
+//! instead when written with spaces on both sides, like in `|1 | 2|`, or as part of `||`, and it opens another
+//! absolute value when written with a space only before it, like in `2 |x|`, or when followed by a sign, like
+//! in `2|-3|`.
+//!
+//! Common **math symbols** can be used in place of their ASCII version: `×` and `·` for `*`, `÷` for `/`,
+//! `−` for `-`, `≤`, `≥` and `≠` for `<=`, `>=` and `!=`, `√` and `∛` for `sqrt` and `cbrt`, `π`, `τ`, `φ`
+//! and `∞` for `pi`, `tau`, `phi` and `inf`. Superscripts are exponents, so `x²` is `x^2` and `10⁻³`
+//! is `10^(-3)`, and `°` converts an angle from degrees, so `sin 90°` is `1` with any angle unit.
 //!
 //! ## Functions
 //!
@@ -125,10 +209,32 @@
 //! | `ln`     | 1                          | Returns the natural log of the number.                        |
 //! | `log`    | 2 (base, arg)              | Returns the logarithm of the number with the specified base.  |
 //! | `exp`    | 1                          | Returns e^(arg).                                              |
+//! | `popcount` | 1                        | Returns the number of set bits of an int.                     |
+//! | `bitand` | 2                          | Same as `&`.                                                  |
+//! | `bitor`  | 2                          | Same as `&#124;`.                                             |
 //! | `abs`    | 1                          | Returns the absolute value of a number.                       |
 //! | `sqrt`   | 1                          | Returns the square root of a number.                          |
-//! | `rand`   | 2 (min, max)               | Returns a random float between the two number specified.      |
+//! | `cbrt`   | 1                          | Returns the cube root of a number, real for real numbers.     |
+//! | `rand`   | 2 or 3 (min, max, n)       | Returns a random float between the two number specified, or a vector of `n` of them. |
+//! | `uniform` | 2 or 3 (min, max, n)      | Same as `rand`.                                               |
+//! | `randint` | 2 or 3 (min, max, n)      | Returns a random integer between the two numbers, included.   |
+//! | `randn`  | 0 or 1 (n)                 | Returns a value, or `n` values, from the standard normal distribution. |
+//! | `normal` | 2 or 3 (mu, sigma, n)      | Returns a value from the normal distribution.                 |
+//! | `exponential` | 1 or 2 (lambda, n)    | Returns a value from the exponential distribution.            |
+//! | `poisson` | 1 or 2 (lambda, n)        | Returns a value from the Poisson distribution.                |
+//! | `binomial` | 2 or 3 (trials, p, n)    | Returns a value from the binomial distribution.               |
+//! | `choice` | >=1                        | Returns one of the arguments, or `n` values picked from a vector with `choice(values, n)`. |
+//! | `shuffle` | >=1                       | Returns the arguments in random order.                        |
+//! | `normalpdf`, `normalcdf`, `normalquantile` | 3 (x, mu, sigma) | Returns the density, the cumulative probability or the quantile of the normal distribution. |
+//! | `uniformpdf`, `uniformcdf`, `uniformquantile` | 3 (x, min, max) | Same as above, for the uniform distribution.        |
+//! | `exponentialpdf`, `exponentialcdf`, `exponentialquantile` | 2 (x, lambda) | Same as above, for the exponential distribution. |
+//! | `poissonpdf`, `poissoncdf`, `poissonquantile` | 2 (x, lambda) | Same as above, for the Poisson distribution.        |
+//! | `binomialpdf`, `binomialcdf`, `binomialquantile` | 3 (x, trials, p) | Same as above, for the binomial distribution.   |
 //! | `branch` | 3 (condition, true, false) | Returns the second argument if the condition is true, the third if it is false. |
+//! | `if`     | 3 (condition, true, false) | Same as `branch`.                                             |
+//! | `piecewise` | odd, >=1 (c1, v1, ..., default) | Returns the value following the first true condition, or the last argument. |
+//! | `grad`   | 2 (function, point)        | Returns the gradient of the function at the point.            |
+//! | `jacobian` | 2 (function, point)      | Returns the jacobian matrix of the function at the point.     |
 //! | `sin`    | 1                          | Returns the sine of the angle.                                |
 //! | `cos`    | 1                          | Returns the cosine of the angle.                              |
 //! | `tan`    | 1                          | Returns the tangent of the angle.                             |
@@ -141,12 +247,86 @@
 //! | `asinh`  | 1                          | Returns the hyperbolic arcsine of the angle.                  |
 //! | `acosh`  | 1                          | Returns the hyperbolic arccosine of the angle.                |
 //! | `atanh`  | 1                          | Returns the hyperbolic arctangent of the angle.               |
+//! | `deg`    | 1                          | Converts an angle from degrees to the angle unit of the context. |
 //! | `re`     | 1                          | Returns the natural part of the number.                       |
 //! | `im`     | 1                          | Returns the imaginary part of the number.                     |
 //! | `polar`  | 1                          | Returns the polar form (r, theta) of the complex number.      |
 //! | `arg`    | 1                          | Returns the principal arg of the number.                      |
 //! | `norm`   | 1                          | Returns the length of the vector (re, im).                    |
 //!
+//! ## Commands
+//!
+//! Commands manage the declarations of a context and can only be used on their own:
+//!
+//! | Command        | Description                                                        |
+//! |----------------|--------------------------------------------------------------------|
+//! | `clear(a, f)`  | Removes the listed variables and functions.                        |
+//! | `clear()`      | Removes all the variables and functions.                           |
+//! | `vars()`       | Lists the declared variables.                                      |
+//! | `funcs()`      | Lists the declared functions.                                      |
+//!
+//! Listings have no value: they are read with `list_declarations`, while the evaluation functions return `None` for them.
+//!
+//! ## Intervals
+//!
+//! [Intervals](Value::Interval) follow the rules of interval arithmetic: the result of an
+//! operation contains every value the operation could return for the values in the
+//! operands.
+//!
+//! ```
+//! use num_parser::*;
+//!
+//! assert_eq!(eval("[1.9 .. 2.1] * 2").unwrap(), Value::Interval(3.8, 4.2));
+//! assert_eq!(eval("sin([0 .. pi])").unwrap(), Value::Interval(0.0, 1.0));
+//! // Comparisons fail when the result depends on the values in the intervals
+//! assert!(eval("[1 .. 3] < 2").is_err());
+//! assert!(eval("[1 .. 3] == [1 .. 3]").is_err());
+//! ```
+//!
+//! `sqrt`, `ln`, `exp`, `abs`, `floor`, `ceil`, `round`, the trigonometric functions and
+//! their inverses accept intervals too.
+//!
+//! ## Measurements
+//!
+//! [Measurements](Value::Uncertain) carry a standard deviation, which is propagated
+//! through operators and functions to the first order. Every measurement written in an
+//! expression is an independent source of uncertainty, so correlated values cancel out.
+//! Results are rounded to two significant figures of their deviation, keeping no more
+//! decimal places than the [rounding setting](settings::Rounding) allows, unless they
+//! are needed to show the first significant figure of the deviation. Powers of negative
+//! measurements are only defined for exact integer exponents.
+//!
+//! ```
+//! use num_parser::*;
+//!
+//! let mut context = Context::default();
+//! eval_with_mutable_context("x = 9.81 +- 0.02", &mut context).unwrap();
+//!
+//! let res = eval_with_mutable_context("x + x", &mut context).unwrap().unwrap();
+//! assert_eq!(res.to_string(), "19.62 ± 0.04");
+//! let res = eval_with_mutable_context("x - x", &mut context).unwrap().unwrap();
+//! assert_eq!(res.to_string(), "0 ± 0");
+//! ```
+//!
+//! ## Differentiation
+//!
+//! `grad` and `jacobian` differentiate a function, passed by name, using dual numbers.
+//! Derivatives are exact to machine precision, and any function can be differentiated,
+//! including recursive ones.
+//!
+//! ```
+//! use num_parser::*;
+//!
+//! let mut context = Context::default();
+//! eval_with_mutable_context("f(x,y) = x^2*y", &mut context).unwrap();
+//! eval_with_mutable_context("g(x,y) = (x*y, x+y)", &mut context).unwrap();
+//!
+//! let res = eval_with_mutable_context("grad(f, (3, 2))", &mut context).unwrap().unwrap();
+//! assert_eq!(res, Value::from(vec![12, 9]));
+//! let res = eval_with_mutable_context("jacobian(g, (3, 2))", &mut context).unwrap().unwrap();
+//! assert_eq!(res, Value::Vector(vec![Value::from(vec![2, 3]), Value::from(vec![1, 1])]));
+//! ```
+//!
 //! ## Context
 //!
 //! [Contexts](Context) allows you keep track of **user-defined functions** and **variables**, as well
@@ -166,26 +346,177 @@
 //! );
 //! ```
 //!
+//! Random functions use the generator of the context. Set a fixed seed to make their results
+//! reproducible:
+//!
+//! ```
+//! use num_parser::*;
+//!
+//! let mut context = Context::default();
+//! context.set_seed(settings::Seed::Fixed(42));
+//! let first = eval_with_mutable_context("rand(0, 1, 3)", &mut context).unwrap();
+//!
+//! context.set_seed(settings::Seed::Fixed(42));
+//! let second = eval_with_mutable_context("rand(0, 1, 3)", &mut context).unwrap();
+//! assert_eq!(first, second);
+//! ```
+//!
+//! User-defined functions are **lexically scoped**: their body sees its own parameters and the
+//! global declarations of the context, never the parameters of the function calling them.
+//! Values assigned with `=` are captured when the function is declared, so assigning them again
+//! does not change the function. Variables declared with `:=` are evaluated in the global scope
+//! and read when the function is called.
+//!
+//! Declarations can be saved and brought back with **snapshots**, and the changes made by
+//! requests can be **undone**. `transaction` reverts every change if the provided function fails:
+//!
+//! ```rust
+//! use num_parser::*;
+//!
+//! let mut context = Context::default();
+//! eval_with_mutable_context("a = 1", &mut context).unwrap();
+//! let snapshot = context.snapshot();
+//!
+//! eval_with_mutable_context("a = 2", &mut context).unwrap();
+//! context.undo(1);
+//! assert_eq!(context.get_value("a"), Some(Value::from(1)));
+//!
+//! let result = context.transaction(|context| {
+//!     eval_with_mutable_context("b = 3", context)?;
+//!     eval_with_mutable_context("c = unknown", context)
+//! });
+//! assert!(result.is_err());
+//! assert!(!context.is_var("b"));
+//!
+//! context.restore(snapshot);
+//! ```
+//!
+//! A context can be saved as a readable script with `to_script`, which writes its settings as
+//! header directives and its declarations as source code, and loaded again with `from_script`:
+//!
+//! ```rust
+//! use num_parser::*;
+//!
+//! let mut context = Context::default();
+//! eval_with_mutable_context("f(x) = x^2 + 1", &mut context).unwrap();
+//!
+//! let script = context.to_script(); // "#! rounding: 8\n...\nf(x) = x ^ 2 + 1\n"
+//! let loaded = Context::from_script(&script).unwrap();
+//! assert_eq!(eval_with_static_context("f(2)", &loaded).unwrap(), Value::from(5));
+//! ```
+//!
 //! ### Serde
 //!
-//! You can use the optional feature `serde_support` to let all the public structs
-//! derive  [`Serialize`](https://docs.rs/serde/1.0.71/serde/trait.Serializer.html) and
-//! [`Deserialize`](https://docs.rs/serde/1.0.71/serde/trait.Serializer.html).
+//! You can use the optional feature `serde` to let all the public types
+//! implement [`Serialize`](https://docs.rs/serde/1.0.71/serde/trait.Serializer.html) and
+//! [`Deserialize`](https://docs.rs/serde/1.0.71/serde/trait.Serializer.html). The former
+//! `serde_support` feature is an alias of it.
 //!
 //! ```text
 //! [dependencies]
-//! num = { version = "<version>", features = [ "serde_support" ] }
+//! num_parser = { version = "<version>", features = [ "serde" ] }
+//! ```
+//!
+//! Contexts are serialized together with the version of their format, and contexts of other
+//! versions are rejected. Errors are serialized as `{ code, message, data }`, where `data`
+//! contains their fields. Built-in functions are serialized as their identifier.
+//!
+//! ## Errors
+//!
+//! Every [error](ErrorType) has a stable `code`, like `E0006`, and a `category`, so that it can
+//! be handled without reading its message. Errors wrapping others, like the ones returned by
+//! scripts, expose them with `causes`:
+//!
+//! ```rust
+//! use num_parser::*;
+//!
+//! let mut context = Context::default();
+//! let error = eval_script("a = 1 / b", &mut context).pop().unwrap().unwrap_err();
+//!
+//! assert_eq!(error.category(), ErrorCategory::Syntax);
+//! assert_eq!(error.root_cause().code(), "E0018"); // Unknown variable
+//! ```
+//!
+//! Messages are written in English by default. Set the `locale` setting of a context and use
+//! `Context::error_message`, or pass a [catalog](locale::MessageCatalog) of templates keyed by
+//! error code to `ErrorType::message`:
+//!
+//! ```rust
+//! use num_parser::*;
+//!
+//! let mut context = Context::default();
+//! context.locale = settings::Locale::Italian;
+//! let error = eval_with_static_context("2 + x", &context).unwrap_err();
+//!
+//! assert_eq!(context.error_message(&error), "ERRORE DI SINTASSI: variabile sconosciuta `x`.");
+//! assert_eq!(error.message(&locale::English), error.to_string());
 //! ```
 //!
+//! Unknown functions and variables carry the closest known names, which are also added to the
+//! message:
+//!
+//! ```rust
+//! use num_parser::*;
+//!
+//! let error = eval("cso(pi)").unwrap_err();
+//!
+//! assert_eq!(error.suggestions(), ["cos"]);
+//! assert_eq!(error.to_string(), "SYNTAX ERROR: unknown function `cso`. Did you mean `cos`?");
+//! ```
+//!
+//! To find every syntax problem at once, like an editor checking a formula as it is written, use
+//! `parse_with_diagnostics`. It returns the parsed expression, with the parts that could not be parsed
+//! replaced by `Expression::Invalid`, and the problems found with their position in the input:
+//!
+//! ```rust
+//! use num_parser::*;
+//!
+//! let (expression, diagnostics) = parse_with_diagnostics("max(1, 2 +) * (3", &Context::default());
+//!
+//! assert_eq!(expression.to_string(), "max(1, 2 + _) * 3");
+//! assert_eq!(diagnostics[0].error.code(), "E0010"); // Missing argument of `+`
+//! assert_eq!(diagnostics[1].position, 14); // Missing closing bracket
+//! ```
+//!
+//! Ambiguous input like `2pisinx` is read implicitly by default. `eval_with_warnings` also returns a
+//! warning for every identifier it split and every multiplication or function bracket it added, and
+//! the `Strict` syntax setting turns them into errors:
+//!
+//! ```rust
+//! use num_parser::*;
+//!
+//! let mut context = Context::default();
+//! let (_, warnings) = eval_with_warnings("sin 2pi", &mut context).unwrap();
+//! assert_eq!(warnings.len(), 2);
+//!
+//! context.syntax = settings::Syntax::Strict;
+//! assert!(eval("sin 2pi").is_ok());
+//! assert_eq!(eval_with_static_context("sin 2pi", &context).unwrap_err().code(), "E0033");
+//! ```
+//!
+//! The `Explicit` syntax is strict too, and never splits identifiers, so that names can have several
+//! letters, underscores and digits:
+//!
+//! ```rust
+//! use num_parser::*;
+//!
+//! let mut context = Context::default();
+//! context.syntax = settings::Syntax::Explicit;
+//! eval_script("k_ab = 2; rate2 = 3; speed := k_ab * rate2", &mut context);
+//!
+//! assert_eq!(eval_with_static_context("speed + 1", &context).unwrap(), Value::from(7));
+//! ```
+//! 
+//! With the other syntaxes, a new name longer than one letter would be split when read, so declaring
+//! it, like in `foo(x) = 2x` or `xy := 3`, is an `ErrorType::ExplicitSyntaxRequired`. An unknown
+//! name called like a function, such as `foo(3)`, is an `ErrorType::UnknownFunction`.
+//!
 //! ## License and contribution
 //! num_parser is licensed under a **MIT License**.
 //!
 //! Feel free to open issues and pull requests for any problems or ideas you come up with.
 //!
 
-#[cfg(feature = "serde_support")]
-extern crate serde;
-
 extern crate num;
 
 pub mod function;
@@ -206,7 +537,7 @@ mod tests;
 
 pub use crate::{
     api::*,
-    context::{settings, Context},
+    context::{settings, Context, Snapshot},
     objects::Expression,
     out::*,
     value::{valuetype::*, Value},
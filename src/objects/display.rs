@@ -1,6 +1,10 @@
-use std::fmt::Display;
+use std::fmt::{self, Display};
 
-use crate::objects::Request;
+use crate::{
+    objects::{Expression, Request},
+    token::tokentype::{Associativity, TokenType},
+    value::Value,
+};
 
 impl Display for Request {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -8,6 +12,146 @@ impl Display for Request {
             Self::Evaluation(_) => write!(f, "evaluation"),
             Self::FuncDeclaration(_, _, _) => write!(f, "function declaration"),
             Self::VarDeclaration(_, _) => write!(f, "variable declaration"),
+            Self::VarAssignment(_, _) => write!(f, "variable assignment"),
+            Self::Removal(_) => write!(f, "removal"),
+            Self::VarsListing => write!(f, "variables listing"),
+            Self::FuncsListing => write!(f, "functions listing"),
         }
     }
 }
+
+/// Writes an expression as source code, which can be parsed again.
+impl Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Binary(lhs, operator, rhs) => {
+                write_operand(f, lhs, *operator, false)?;
+                write!(f, " {} ", operator)?;
+                write_operand(f, rhs, *operator, true)
+            }
+            Self::Unary(operator, expr) if operator.is_postfix_operator() => {
+                match &**expr {
+                    Self::Binary(_, _, _) | Self::Unary(_, _) => write!(f, "({})", expr)?,
+                    Self::Literal(value) if is_negative(value) => write!(f, "({})", expr)?,
+                    other => write!(f, "{}", other)?,
+                }
+                write!(f, "{}", operator)
+            }
+            Self::Unary(operator, expr) => {
+                write!(f, "{}", operator)?;
+                match &**expr {
+                    Self::Binary(_, _, _) | Self::Unary(_, _) => write!(f, "({})", expr),
+                    other => write!(f, "{}", other),
+                }
+            }
+            Self::Var(identifier) => write!(f, "{}", identifier),
+            Self::Func(identifier, arguments) => {
+                write!(f, "{}(", identifier)?;
+                write_list(f, arguments)?;
+                write!(f, ")")
+            }
+            Self::Literal(value) => write_literal(f, value),
+            Self::Union(expressions) => {
+                write!(f, "(")?;
+                write_list(f, expressions)?;
+                write!(f, ")")
+            }
+            Self::Comparison(first, chain) => {
+                write_operand(f, first, chain[0].0, false)?;
+                for (operator, operand) in chain {
+                    write!(f, " {} ", operator)?;
+                    write_operand(f, operand, *operator, true)?;
+                }
+                Ok(())
+            }
+            Self::Invalid => write!(f, "_"),
+        }
+    }
+}
+
+/// Writes the operand of a binary operator, adding brackets if it would be grouped
+/// differently otherwise.
+fn write_operand(
+    f: &mut fmt::Formatter<'_>,
+    operand: &Expression,
+    operator: TokenType,
+    is_right: bool,
+) -> fmt::Result {
+    let binds_looser = |inner: TokenType| {
+        let (inner_precedence, precedence) = (
+            inner.precedence().unwrap_or(0),
+            operator.precedence().unwrap_or(0),
+        );
+        inner_precedence < precedence
+            || (inner_precedence == precedence
+                && match operator.associativity() {
+                    Associativity::Left => is_right,
+                    Associativity::Right => !is_right,
+                    // Comparisons with the same precedence would be read as a chain.
+                    Associativity::Chain => true,
+                })
+    };
+    let needs_brackets = match operand {
+        Expression::Binary(_, inner, _) => binds_looser(*inner),
+        Expression::Comparison(_, chain) => binds_looser(chain[0].0),
+        // Unary operators bind differently depending on the operator that follows.
+        Expression::Unary(_, _) => true,
+        Expression::Literal(value) => is_negative(value),
+        _ => false,
+    };
+    // In scripts, a `//` followed by a name would be taken for a comment.
+    let needs_brackets = needs_brackets
+        || (operator == TokenType::DoubleSlash
+            && is_right
+            && operand
+                .to_string()
+                .starts_with(|c: char| c.is_alphabetic() || c == '_'));
+
+    if needs_brackets {
+        write!(f, "({})", operand)
+    } else {
+        write!(f, "{}", operand)
+    }
+}
+
+fn write_list(f: &mut fmt::Formatter<'_>, expressions: &[Box<Expression>]) -> fmt::Result {
+    for (i, expr) in expressions.iter().enumerate() {
+        if i != 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{}", expr)?;
+    }
+    Ok(())
+}
+
+/// Writes a value in a form that evaluates to it again.
+fn write_literal(f: &mut fmt::Formatter<'_>, value: &Value) -> fmt::Result {
+    match value {
+        Value::Complex(c) if c.re != 0.0 && c.im != 0.0 => write!(f, "({})", value),
+        Value::Uncertain(measurement) => {
+            write!(f, "({} +- {})", measurement.value, measurement.sigma())
+        }
+        Value::Dual(dual) => write!(f, "{}", dual.value),
+        Value::Vector(vec) => {
+            write!(f, "[")?;
+            for (i, elem) in vec.iter().enumerate() {
+                if i != 0 {
+                    write!(f, ", ")?;
+                }
+                write_literal(f, elem)?;
+            }
+            write!(f, "]")
+        }
+        other => write!(f, "{}", other),
+    }
+}
+
+fn is_negative(value: &Value) -> bool {
+    match value {
+        Value::Int(n) => *n < 0,
+        Value::Float(n) => n.is_sign_negative(),
+        Value::Complex(c) => c.re.is_sign_negative() || (c.re == 0.0 && c.im < 0.0),
+        Value::Interval(_, _) | Value::Vector(_) => false,
+        other => other.as_mean().is_ok_and(|mean| mean < 0.0),
+    }
+}
@@ -12,26 +12,79 @@ use crate::{
     value::Value,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Request {
+    /// A variable storing an expression, evaluated every time it is read.
     VarDeclaration(String, Box<Expression>),
+    /// A variable storing the value of an expression, evaluated immediately.
+    VarAssignment(String, Box<Expression>),
+    /// The removal of the listed declarations, or of all of them if none is listed.
+    Removal(Vec<String>),
+    /// The list of the declared variables.
+    VarsListing,
+    /// The list of the declared functions.
+    FuncsListing,
     FuncDeclaration(String, Vec<String>, Box<Expression>),
     Evaluation(Box<Expression>),
 }
 
 impl Request {
     pub fn execute(&self, context: &mut Context) -> EvalResult<Option<Value>> {
+        let before = context.snapshot();
+        let result = self.apply(context);
+        if result.is_ok() && self.is_change() {
+            context.record_change(before);
+        }
+        result
+    }
+
+    /// Returns true if the request changes the declarations of the context.
+    fn is_change(&self) -> bool {
+        !matches!(
+            self,
+            Self::Evaluation(_) | Self::VarsListing | Self::FuncsListing
+        )
+    }
+
+    /// Returns the declarations listed by a listing request, sorted by name.
+    pub fn list(&self, context: &Context) -> Option<Vec<String>> {
         match self {
-            Self::Evaluation(expr) => {
-                Ok(Some(expr.eval(context, None, 0)?.round(context.rounding)))
+            Self::VarsListing => Some(context.variable_declarations()),
+            Self::FuncsListing => Some(context.function_declarations()),
+            _ => None,
+        }
+    }
+
+    fn apply(&self, context: &mut Context) -> EvalResult<Option<Value>> {
+        if let Self::FuncDeclaration(identifier, _, _)
+        | Self::VarDeclaration(identifier, _)
+        | Self::VarAssignment(identifier, _) = self
+        {
+            // New names longer than one letter would be split into several identifiers
+            // when read, so they can only be declared with the explicit syntax.
+            if context.syntax != settings::Syntax::Explicit
+                && identifier.chars().count() > 1
+                && !context.is_var(identifier)
+                && !context.is_function(identifier)
+                && !builtin::reserved_keywords().contains(&&identifier[..])
+            {
+                return Err(ErrorType::ExplicitSyntaxRequired {
+                    identifier: identifier.clone(),
+                });
             }
+        }
+
+        match self {
+            Self::Evaluation(expr) => Ok(Some(expr.eval(context, 0)?.round(context))),
             Self::FuncDeclaration(identifier, params, body) => {
                 if builtin::reserved_keywords().contains(&&identifier[..]) {
                     Err(ErrorType::ReservedVarName {
                         var_name: identifier.clone(),
                     })
                 } else {
-                    context.add_function(identifier.clone(), params.clone(), body.clone());
+                    let body = Box::new(body.capture_values(params, context));
+                    context.add_function(identifier.clone(), params.clone(), body);
                     Ok(None)
                 }
             }
@@ -45,6 +98,37 @@ impl Request {
                     Ok(None)
                 }
             }
+            Self::VarAssignment(identifier, expression) => {
+                if builtin::reserved_keywords().contains(&&identifier[..]) {
+                    Err(ErrorType::ReservedVarName {
+                        var_name: identifier.clone(),
+                    })
+                } else {
+                    let value = expression.eval(context, 0)?;
+                    context.add_value(identifier.clone(), value);
+                    Ok(None)
+                }
+            }
+            Self::Removal(identifiers) => {
+                if identifiers.is_empty() {
+                    context.clear();
+                }
+                // Check all the identifiers first, so nothing is removed on failure.
+                for identifier in identifiers {
+                    if !context.is_var(identifier) && !context.is_function(identifier) {
+                        return Err(ErrorType::UnknownDeclaration {
+                            identifier: identifier.clone(),
+                        });
+                    }
+                }
+                for identifier in identifiers {
+                    context.remove_variable(identifier);
+                    context.remove_function(identifier);
+                }
+                Ok(None)
+            }
+            // Listings have no value, they are read with `Request::list`.
+            Self::VarsListing | Self::FuncsListing => Ok(None),
         }
     }
 }
@@ -65,15 +149,16 @@ pub enum Expression {
     Literal(Value),
     /// A union of values.
     Union(Vec<Box<Expression>>),
+    /// A chain of comparisons, like `a < b <= c`, which is true if every comparison is.
+    /// Each operand is evaluated once.
+    Comparison(Box<Expression>, Vec<(TokenType, Box<Expression>)>),
+    /// A part of the input that could not be parsed, written as `_`. Only found in the
+    /// expressions returned by `parse_with_diagnostics`, and cannot be evaluated.
+    Invalid,
 }
 
 impl Expression {
-    pub fn eval(
-        &self,
-        context: &Context,
-        scope: Option<&Context>,
-        depth: u32,
-    ) -> EvalResult<Value> {
+    pub fn eval(&self, context: &Context, depth: u32) -> EvalResult<Value> {
         let depth = depth + 1;
 
         // Check depth limit
@@ -87,9 +172,35 @@ impl Expression {
         }
 
         match self {
+            Self::Binary(left_expr, TokenType::Question, right_expr) => {
+                // Only the arm taken by the conditional operator is evaluated.
+                match &**right_expr {
+                    Self::Binary(if_true, TokenType::Colon, if_false) => {
+                        if (**left_expr).eval(context, depth)?.as_bool()? {
+                            (**if_true).eval(context, depth)
+                        } else {
+                            (**if_false).eval(context, depth)
+                        }
+                    }
+                    _ => Err(ErrorType::InvalidTokenPosition {
+                        token: TokenType::Question,
+                    }),
+                }
+            }
             Self::Binary(left_expr, token_type, right_expr) => {
-                let left_value = (**left_expr).eval(context, scope, depth)?;
-                let right_value = (**right_expr).eval(context, scope, depth)?;
+                let left_value = (**left_expr).eval(context, depth)?;
+
+                // Logical operators skip the right operand if the left one already
+                // determines the result.
+                if !left_value.is_vector() {
+                    match (token_type, left_value.as_bool()) {
+                        (TokenType::DoubleAnd, Ok(false)) => return Ok(Value::Bool(false)),
+                        (TokenType::DoubleOr, Ok(true)) => return Ok(Value::Bool(true)),
+                        _ => (),
+                    }
+                }
+
+                let right_value = (**right_expr).eval(context, depth)?;
                 Ok(match token_type {
                     // Sum
                     TokenType::Plus => Value::add(left_value, right_value)?,
@@ -103,76 +214,79 @@ impl Expression {
                     TokenType::Caret => Value::exponentiation(left_value, right_value)?,
                     // Modulo
                     TokenType::Percentage => Value::modulo(left_value, right_value)?,
-                    // Less than
-                    TokenType::LessThan => Value::less_than(left_value, right_value)?,
-                    // Greater than
-                    TokenType::GreaterThan => Value::greater_than(left_value, right_value)?,
-                    // Less or equal to
-                    TokenType::LessOrEqualTo => Value::less_or_equal_to(left_value, right_value)?,
-                    // Greater or equal to
-                    TokenType::GreaterOrEqualTo => {
-                        Value::greater_or_equal_to(left_value, right_value)?
-                    }
                     // Logical AND
                     TokenType::DoubleAnd => Value::logical_and(left_value, right_value)?,
                     // Logical OR
                     TokenType::DoubleOr => Value::logical_or(left_value, right_value)?,
-                    // Equal to
-                    TokenType::DoubleEqual => Value::equal_to(left_value, right_value)?,
-                    // Not equal to
-                    TokenType::NotEqual => Value::not_equal_to(left_value, right_value)?,
+                    // Comparisons
+                    operator if operator.is_comparison_operator() => {
+                        Self::compare(left_value, *operator, right_value)?
+                    }
+                    // Integer division
+                    TokenType::DoubleSlash => Value::integer_division(left_value, right_value)?,
+                    // Bitwise AND
+                    TokenType::And => Value::bitwise_and(left_value, right_value)?,
+                    // Bitwise OR
+                    TokenType::Or => Value::bitwise_or(left_value, right_value)?,
+                    // Bitwise XOR
+                    TokenType::Xor => Value::bitwise_xor(left_value, right_value)?,
+                    // Left shift
+                    TokenType::DoubleLessThan => Value::shift_left(left_value, right_value)?,
+                    // Right shift
+                    TokenType::DoubleGreaterThan => Value::shift_right(left_value, right_value)?,
+                    // Interval from its bounds
+                    TokenType::DoubleDot => Value::interval(left_value, right_value)?,
+                    // Interval from its center and radius
+                    TokenType::PlusMinus => Value::plus_minus(left_value, right_value)?,
+                    // Measured value and its standard deviation
+                    TokenType::Uncertainty => {
+                        Value::with_uncertainty(left_value, right_value, context)?
+                    }
 
                     _ => return Err(ErrorType::InvalidTokenPosition { token: *token_type }),
                 })
             }
             Self::Unary(token_type, expr) => Ok(match token_type {
                 // Negate
-                TokenType::Minus => Value::negate(expr.eval(context, scope, depth)?)?,
+                TokenType::Minus => Value::negate(expr.eval(context, depth)?)?,
                 // Not
-                TokenType::Exclamation => Value::not(expr.eval(context, scope, depth)?)?,
+                TokenType::Exclamation => Value::not(expr.eval(context, depth)?)?,
+                // Bitwise NOT
+                TokenType::Tilde => Value::bitwise_not(expr.eval(context, depth)?)?,
+                // Factorial
+                TokenType::Factorial => Value::factorial(expr.eval(context, depth)?)?,
+                // Double factorial
+                TokenType::DoubleFactorial => Value::double_factorial(expr.eval(context, depth)?)?,
+                // Percentage
+                TokenType::Percent => Value::percent(expr.eval(context, depth)?)?,
                 _ => return Err(ErrorType::InvalidTokenPosition { token: *token_type }),
             }),
-            Self::Union(expressions) => {
-                let mut vec = vec![];
-                for expr in expressions {
-                    vec.push(expr.eval(context, scope, depth)?);
-                }
-                if vec.len() == 1 {
-                    Ok(vec[0].clone())
-                } else {
-                    Ok(Value::Vector(vec))
-                }
-            }
+            Self::Union(expressions) => Self::eval_union(expressions, context, depth),
+            Self::Comparison(first, chain) => Self::eval_comparison(first, chain, context, depth),
             Self::Var(identifier) => {
                 // Check built-in vars
                 if let Some(var) = builtin::get_built_in_const(identifier) {
                     return Ok(var);
                 }
 
-                // Check scope vars
-                if let Some(c) = scope {
-                    if let Some(expr) = c.get_var(identifier) {
-                        return Ok(expr.eval(context, scope, depth)?);
-                    }
+                // Check the parameters of the function being evaluated
+                if let Some(value) = context.get_local(identifier) {
+                    return Ok(value.clone());
                 }
 
-                // Check context
-                if let Some(expr) = context.get_var(identifier) {
-                    return Ok(expr.eval(context, scope, depth)?);
+                // Check context. Variables are evaluated in the global scope, where
+                // they were declared.
+                if let Some(value) = context.values.get(identifier) {
+                    return Ok(value.clone());
+                }
+                if let Some(expr) = context.variables.get(identifier) {
+                    return Ok(expr.eval(&context.global(), depth)?);
                 }
 
                 // Try to split the identifier, as it might have not been interpreted correctly
                 // in a function declaration, where function parameters were not know at the
                 // time of "tokenization".
-                let mut joined_context = context.clone();
-                // Create a new context with all the data.
-                joined_context.join_with(context);
-                if let Some(c) = scope {
-                    joined_context.join_with(&c);
-                }
-
-                let identifiers =
-                    token::split_into_identifiers(identifier.clone(), &joined_context);
+                let identifiers = split_identifier(identifier, context);
                 let mut product = Value::Float(1.0);
                 let mut valid = true;
                 let mut argument = Option::None;
@@ -194,12 +308,11 @@ impl Expression {
                                 product = Value::mul(
                                     product,
                                     Self::Func(func_ident, vec![Box::new(Self::Var(i))])
-                                        .eval(context, scope, depth)?,
+                                        .eval(context, depth)?,
                                 )?;
                                 argument = Option::None;
                             } else {
-                                product =
-                                    Value::mul(product, Self::Var(i).eval(context, scope, depth)?)?;
+                                product = Value::mul(product, Self::Var(i).eval(context, depth)?)?;
                             }
                         }
                     }
@@ -210,67 +323,53 @@ impl Expression {
                 } else {
                     Err(ErrorType::UnknownVar {
                         var_name: identifier.clone(),
+                        suggestions: context.suggest_vars(identifier),
                     })
                 }
             }
             Self::Func(identifier, arguments) => {
+                // Commands cannot be part of an expression
+                if builtin::is_command(identifier) {
+                    return Err(ErrorType::InvalidCommand {
+                        command: identifier.clone(),
+                    });
+                }
                 // Check built-in functions
                 if let Some(func) = builtin::get_built_in_function(identifier) {
-                    return Ok(func.call(arguments, context, scope, depth)?);
+                    return Ok(func.call(arguments, context, depth)?);
                 }
                 // Check user-defined ones
-                if let Some((names, body)) = context.get_function(&identifier) {
-                    let mut inner_scope = {
-                        let mut cont = context.clone();
-                        // Retrieve the parameters values
-                        let params = match value_to_params(
-                            names,
-                            &Expression::Union(arguments.clone()).eval(context, scope, depth)?,
-                        ) {
-                            Ok(value) => value,
-                            Err(err) => {
-                                return match err {
-                                    ErrorType::WrongFunctionArgumentsAmount {
-                                        func_name: _,
-                                        expected,
-                                        given,
-                                    } => Err(ErrorType::WrongFunctionArgumentsAmount {
-                                        func_name: identifier.clone(),
-                                        expected,
-                                        given,
-                                    }),
-                                    other => Err(other),
-                                }
+                if let Some((names, body)) = context.functions.get(identifier) {
+                    // Retrieve the parameters values
+                    let params = match value_to_params(
+                        names,
+                        &Self::eval_union(arguments, context, depth)?,
+                    ) {
+                        Ok(value) => value,
+                        Err(err) => {
+                            return match err {
+                                ErrorType::WrongFunctionArgumentsAmount {
+                                    func_name: _,
+                                    expected,
+                                    given,
+                                } => Err(ErrorType::WrongFunctionArgumentsAmount {
+                                    func_name: identifier.clone(),
+                                    expected,
+                                    given,
+                                }),
+                                other => Err(other),
                             }
-                        };
-
-                        for (name, val) in params {
-                            cont.add_variable(name, Box::new(Expression::Literal(val)))
                         }
-
-                        cont
                     };
 
-                    match scope {
-                        Some(cont) => inner_scope.join_with(cont),
-                        None => (),
-                    };
-
-                    return Ok(body.eval(context, Some(&inner_scope), depth)?);
+                    // The body only sees its own parameters and the global declarations.
+                    return Ok(body.eval(&context.with_scope(params), depth)?);
                 }
 
                 // Try to split the identifier, as it might have not been interpreted correctly
                 // in a function declaration, where function parameters were not know at the
                 // time of "tokenization".
-                let mut joined_context = context.clone();
-                // Create a new context with all the data.
-                joined_context.join_with(context);
-                if let Some(c) = scope {
-                    joined_context.join_with(&c);
-                }
-
-                let identifiers =
-                    token::split_into_identifiers(identifier.clone(), &joined_context);
+                let identifiers = split_identifier(identifier, context);
                 let mut product = Value::Float(1.0);
                 let mut valid = true;
                 let mut argument = Option::None;
@@ -295,13 +394,13 @@ impl Expression {
                                 product = Value::mul(
                                     product,
                                     Self::Func(func_ident, vec![Box::new(Self::Var(i.clone()))])
-                                        .eval(context, scope, depth)?,
+                                        .eval(context, depth)?,
                                 )?;
                                 argument = Option::None;
                             } else {
                                 product = Value::mul(
                                     product,
-                                    Self::Var(i.clone()).eval(context, scope, depth)?,
+                                    Self::Var(i.clone()).eval(context, depth)?,
                                 )?;
                             }
                         }
@@ -315,20 +414,150 @@ impl Expression {
                 if valid && last_i_type == IdentifierType::Function {
                     Value::mul(
                         product,
-                        Self::Func(last_i, arguments.clone()).eval(context, scope, depth)?,
+                        Self::Func(last_i, arguments.clone()).eval(context, depth)?,
                     )
                 } else {
                     Err(ErrorType::UnknownFunction {
                         func_name: identifier.clone(),
+                        suggestions: context.suggest_functions(identifier),
                     })
                 }
             }
             Self::Literal(value) => Ok(value.clone()),
+            Self::Invalid => Err(ErrorType::InternalError {
+                message: "trying to evaluate an invalid expression".to_owned(),
+            }),
+        }
+    }
+
+    /// Evaluates the expressions of a union. Returns a vector, or the only value if there
+    /// is just one expression.
+    pub(crate) fn eval_union(
+        expressions: &[Box<Expression>],
+        context: &Context,
+        depth: u32,
+    ) -> EvalResult<Value> {
+        let mut vec = vec![];
+        for expr in expressions {
+            vec.push(expr.eval(context, depth)?);
+        }
+        if vec.len() == 1 {
+            Ok(vec[0].clone())
+        } else {
+            Ok(Value::Vector(vec))
+        }
+    }
+
+    /// Evaluates a chain of comparisons, stopping as soon as one of them is false.
+    fn eval_comparison(
+        first: &Expression,
+        chain: &[(TokenType, Box<Expression>)],
+        context: &Context,
+        depth: u32,
+    ) -> EvalResult<Value> {
+        let mut left_value = first.eval(context, depth)?;
+        let mut result = Value::Bool(true);
+        for (operator, expr) in chain {
+            let right_value = expr.eval(context, depth)?;
+            let comparison = Self::compare(left_value, *operator, right_value.clone())?;
+            result = Value::logical_and(result, comparison)?;
+            if !result.is_vector() && !result.as_bool()? {
+                return Ok(Value::Bool(false));
+            }
+            left_value = right_value;
+        }
+        Ok(result)
+    }
+
+    /// Compares two values with a comparison operator.
+    fn compare(lhs: Value, operator: TokenType, rhs: Value) -> EvalResult<Value> {
+        match operator {
+            // Less than
+            TokenType::LessThan => Value::less_than(lhs, rhs),
+            // Greater than
+            TokenType::GreaterThan => Value::greater_than(lhs, rhs),
+            // Less or equal to
+            TokenType::LessOrEqualTo => Value::less_or_equal_to(lhs, rhs),
+            // Greater or equal to
+            TokenType::GreaterOrEqualTo => Value::greater_or_equal_to(lhs, rhs),
+            // Equal to
+            TokenType::DoubleEqual => Value::equal_to(lhs, rhs),
+            // Not equal to
+            TokenType::NotEqual => Value::not_equal_to(lhs, rhs),
+            _ => Err(ErrorType::InvalidTokenPosition { token: operator }),
+        }
+    }
+
+    /// Replaces the variables assigned with `=` that the body of a function refers to
+    /// with their current value, so that the function keeps seeing the values visible
+    /// where it was declared. Parameters, variables declared with `:=` and functions
+    /// are left to be read when the function is called.
+    fn capture_values(&self, params: &[String], context: &Context) -> Self {
+        let capture = |expr: &Self| Box::new(expr.capture_values(params, context));
+        match self {
+            Self::Binary(lhs, operator, rhs) => Self::Binary(capture(lhs), *operator, capture(rhs)),
+            Self::Unary(operator, expr) => Self::Unary(*operator, capture(expr)),
+            Self::Func(identifier, arguments) => Self::Func(
+                identifier.clone(),
+                arguments.iter().map(|arg| capture(arg)).collect(),
+            ),
+            Self::Union(items) => Self::Union(items.iter().map(|item| capture(item)).collect()),
+            Self::Comparison(first, chain) => Self::Comparison(
+                capture(first),
+                chain
+                    .iter()
+                    .map(|(operator, expr)| (*operator, capture(expr)))
+                    .collect(),
+            ),
+            Self::Var(identifier) if !params.contains(identifier) => {
+                if let Some(value) = context.values.get(identifier) {
+                    return Self::Literal(value.clone());
+                }
+                if context.is_var(identifier) || builtin::get_built_in_const(identifier).is_some() {
+                    return self.clone();
+                }
+                // Identifiers made of several names, like `ax`, are split knowing the
+                // parameters. Those calling a function are left as they are.
+                let locals = params
+                    .iter()
+                    .map(|param| (param.clone(), Value::Int(0)))
+                    .collect();
+                let names = split_identifier(identifier, &context.with_scope(locals));
+                let captures =
+                    |name: &String| !params.contains(name) && context.values.contains_key(name);
+                if names
+                    .iter()
+                    .any(|(_, r#type)| *r#type != IdentifierType::Var)
+                    || !names.iter().any(|(name, _)| captures(name))
+                {
+                    return self.clone();
+                }
+                names
+                    .into_iter()
+                    .map(|(name, _)| Self::Var(name).capture_values(params, context))
+                    .reduce(|product, factor| {
+                        Self::Binary(Box::new(product), TokenType::Star, Box::new(factor))
+                    })
+                    .unwrap_or_else(|| self.clone())
+            }
+            Self::Var(_) | Self::Literal(_) | Self::Invalid => self.clone(),
+        }
+    }
+}
+
+/// Splits an identifier into the known names it is made of. With the strict and
+/// explicit syntaxes identifiers are never split, so the identifier is returned as
+/// unknown.
+fn split_identifier(identifier: &str, context: &Context) -> Vec<(String, IdentifierType)> {
+    match context.syntax {
+        settings::Syntax::Implicit => token::split_into_identifiers(identifier.to_owned(), context),
+        settings::Syntax::Strict | settings::Syntax::Explicit => {
+            vec![(identifier.to_owned(), IdentifierType::Unknown)]
         }
     }
 }
 
-fn value_to_params(names: Vec<String>, value: &Value) -> EvalResult<Vec<(String, Value)>> {
+fn value_to_params(names: &[String], value: &Value) -> EvalResult<Vec<(String, Value)>> {
     match value {
         Value::Vector(vec) => {
             if names.len() != vec.len() {
@@ -341,7 +570,7 @@ fn value_to_params(names: Vec<String>, value: &Value) -> EvalResult<Vec<(String,
                 let mut zipped = std::iter::zip(names, vec);
                 let mut out = vec![];
                 while let Some((name, val)) = zipped.next() {
-                    out.push((name, val.clone()));
+                    out.push((name.clone(), val.clone()));
                 }
                 Ok(out)
             }
@@ -1,6 +1,6 @@
 use crate::{
     out::{ErrorType, EvalResult},
-    value::{valuetype::ValueType, Value},
+    value::{dual, interval, uncertain, valuetype::ValueType, FloatValue, IntValue, Value},
 };
 
 // Implement operators for values. The values should be converted
@@ -82,9 +82,17 @@ where
     }
 
     if lhs_as_vector.len() == 1 && rhs_as_vector.len() == 1 {
-        // Convert the values to the right type
-        let lhs_converted = lhs.as_type(&target_value_type)?;
-        let rhs_converted = rhs.as_type(&target_value_type)?;
+        // Convert the values to the right type. Intervals and measured values cannot be
+        // represented by any other type, so they are left to the operation.
+        let (lhs_converted, rhs_converted) =
+            if lhs_as_vector[0].is_self_contained() || rhs_as_vector[0].is_self_contained() {
+                (lhs_as_vector[0].clone(), rhs_as_vector[0].clone())
+            } else {
+                (
+                    lhs.as_type(&target_value_type)?,
+                    rhs.as_type(&target_value_type)?,
+                )
+            };
 
         if inverse {
             Ok(operation(rhs_converted, lhs_converted)?.try_as_type(highest_complexity))
@@ -133,7 +141,7 @@ where
         Err(ErrorType::MismatchedArrayLengths {
             first: lhs_as_vector.len(),
             second: rhs_as_vector.len(),
-            operation_name: operation_name,
+            operation_name: operation_name.to_owned(),
         })
     }
 }
@@ -143,7 +151,17 @@ impl Value {
         convert_and_apply(
             &self,
             &rhs,
-            &mut |lhs, rhs| Ok(Value::Complex(lhs.as_complex()? + rhs.as_complex()?)),
+            &mut |lhs, rhs| {
+                if let Some((a, b)) = interval::operands(&lhs, &rhs)? {
+                    Ok(Value::from(interval::add(a, b)))
+                } else if let Some((a, b)) = uncertain::operands(&lhs, &rhs)? {
+                    Ok(Value::from(a.add(&b)))
+                } else if let Some((a, b)) = dual::operands(&lhs, &rhs)? {
+                    Ok(Value::from(a.add(b)))
+                } else {
+                    Ok(Value::Complex(lhs.as_complex()? + rhs.as_complex()?))
+                }
+            },
             "Sum",
             ValueType::ComplexType,
             false,
@@ -154,7 +172,17 @@ impl Value {
         convert_and_apply(
             &self,
             &rhs,
-            &mut |lhs, rhs| Ok(Value::Complex(lhs.as_complex()? - rhs.as_complex()?)),
+            &mut |lhs, rhs| {
+                if let Some((a, b)) = interval::operands(&lhs, &rhs)? {
+                    Ok(Value::from(interval::sub(a, b)))
+                } else if let Some((a, b)) = uncertain::operands(&lhs, &rhs)? {
+                    Ok(Value::from(a.sub(&b)))
+                } else if let Some((a, b)) = dual::operands(&lhs, &rhs)? {
+                    Ok(Value::from(a.sub(b)))
+                } else {
+                    Ok(Value::Complex(lhs.as_complex()? - rhs.as_complex()?))
+                }
+            },
             "Subtraction",
             ValueType::ComplexType,
             false,
@@ -165,7 +193,17 @@ impl Value {
         convert_and_apply(
             &self,
             &rhs,
-            &mut |lhs, rhs| Ok(Value::Complex(lhs.as_complex()? * rhs.as_complex()?)),
+            &mut |lhs, rhs| {
+                if let Some((a, b)) = interval::operands(&lhs, &rhs)? {
+                    Ok(Value::from(interval::mul(a, b)))
+                } else if let Some((a, b)) = uncertain::operands(&lhs, &rhs)? {
+                    Ok(Value::from(a.mul(&b)))
+                } else if let Some((a, b)) = dual::operands(&lhs, &rhs)? {
+                    Ok(Value::from(a.mul(b)))
+                } else {
+                    Ok(Value::Complex(lhs.as_complex()? * rhs.as_complex()?))
+                }
+            },
             "Multiplication",
             ValueType::ComplexType,
             false,
@@ -176,19 +214,79 @@ impl Value {
         convert_and_apply(
             &self,
             &rhs,
-            &mut |lhs, rhs| Ok(Value::Complex(lhs.as_complex()? / rhs.as_complex()?)),
+            &mut |lhs, rhs| {
+                if let Some((a, b)) = interval::operands(&lhs, &rhs)? {
+                    Ok(Value::from(interval::div(a, b)?))
+                } else if let Some((a, b)) = uncertain::operands(&lhs, &rhs)? {
+                    Ok(Value::from(a.div(&b)?))
+                } else if let Some((a, b)) = dual::operands(&lhs, &rhs)? {
+                    Ok(Value::from(a.div(b)))
+                } else {
+                    Ok(Value::Complex(lhs.as_complex()? / rhs.as_complex()?))
+                }
+            },
             "Division",
             ValueType::ComplexType,
             false,
         )
     }
 
+    /// Divides and rounds towards negative infinity. Ints are divided exactly, other
+    /// numbers as floats.
+    pub fn integer_division(self, rhs: Self) -> EvalResult<Self> {
+        let only_ints = self
+            .as_vector()
+            .iter()
+            .chain(rhs.as_vector().iter())
+            .all(Value::is_int);
+        convert_and_apply(
+            &self,
+            &rhs,
+            &mut |lhs, rhs| match (&lhs, &rhs) {
+                (_, Value::Int(0)) => Err(ErrorType::DivideByZero { numerator: lhs }),
+                (Value::Int(a), Value::Int(b)) if a.checked_div(*b).is_some() => {
+                    // The euclidean division rounds up for negative divisors.
+                    let quotient = a.div_euclid(*b);
+                    if *b < 0 && a.rem_euclid(*b) != 0 {
+                        Ok(Value::Int(quotient - 1))
+                    } else {
+                        Ok(Value::Int(quotient))
+                    }
+                }
+                _ => {
+                    let (a, b) = (lhs.as_float()?, rhs.as_float()?);
+                    if b == 0.0 {
+                        return Err(ErrorType::DivideByZero { numerator: lhs });
+                    }
+                    Ok(Value::Float((a / b).floor()))
+                }
+            },
+            "Integer division",
+            if only_ints {
+                ValueType::IntType
+            } else {
+                ValueType::FloatType
+            },
+            false,
+        )
+    }
+
     pub fn negate(self) -> EvalResult<Self> {
         let zero = Value::Int(0);
         convert_and_apply(
             &zero,
             &self,
-            &mut |lhs, rhs| Ok(Value::Complex(lhs.as_complex()? - rhs.as_complex()?)),
+            &mut |lhs, rhs| {
+                if let Some((a, b)) = interval::operands(&lhs, &rhs)? {
+                    Ok(Value::from(interval::sub(a, b)))
+                } else if let Some((a, b)) = uncertain::operands(&lhs, &rhs)? {
+                    Ok(Value::from(a.sub(&b)))
+                } else if let Some((a, b)) = dual::operands(&lhs, &rhs)? {
+                    Ok(Value::from(a.sub(b)))
+                } else {
+                    Ok(Value::Complex(lhs.as_complex()? - rhs.as_complex()?))
+                }
+            },
             "Negation",
             ValueType::ComplexType,
             false,
@@ -200,6 +298,16 @@ impl Value {
             &self,
             &rhs,
             &mut |lhs, rhs| {
+                if let Some((a, b)) = interval::operands(&lhs, &rhs)? {
+                    return Ok(Value::from(interval::pow(a, b)?));
+                }
+                if let Some((a, b)) = uncertain::operands(&lhs, &rhs)? {
+                    return Ok(Value::from(a.pow(&b)?));
+                }
+                if let Some((a, b)) = dual::operands(&lhs, &rhs)? {
+                    return Ok(Value::from(a.pow(b)));
+                }
+
                 let lhs_as_complex = lhs.as_complex()?;
                 let rhs_as_complex = rhs.as_complex()?;
 
@@ -227,7 +335,13 @@ impl Value {
         convert_and_apply(
             &self,
             &rhs,
-            &mut |lhs, rhs| Ok(Value::Bool(lhs.as_float()? < rhs.as_float()?)),
+            &mut |lhs, rhs| match interval::operands(&lhs, &rhs)? {
+                Some((a, b)) => match interval::less_than(a, b) {
+                    Some(result) => Ok(Value::Bool(result)),
+                    None => Err(ErrorType::IndeterminateComparison { lhs, rhs }),
+                },
+                None => Ok(Value::Bool(lhs.as_mean()? < rhs.as_mean()?)),
+            },
             "Less than",
             ValueType::FloatType,
             false,
@@ -238,7 +352,13 @@ impl Value {
         convert_and_apply(
             &self,
             &rhs,
-            &mut |lhs, rhs| Ok(Value::Bool(lhs.as_float()? > rhs.as_float()?)),
+            &mut |lhs, rhs| match interval::operands(&lhs, &rhs)? {
+                Some((a, b)) => match interval::less_than(b, a) {
+                    Some(result) => Ok(Value::Bool(result)),
+                    None => Err(ErrorType::IndeterminateComparison { lhs, rhs }),
+                },
+                None => Ok(Value::Bool(lhs.as_mean()? > rhs.as_mean()?)),
+            },
             "Greater than",
             ValueType::FloatType,
             false,
@@ -249,7 +369,13 @@ impl Value {
         convert_and_apply(
             &self,
             &rhs,
-            &mut |lhs, rhs| Ok(Value::Bool(lhs.as_float()? <= rhs.as_float()?)),
+            &mut |lhs, rhs| match interval::operands(&lhs, &rhs)? {
+                Some((a, b)) => match interval::less_or_equal_to(a, b) {
+                    Some(result) => Ok(Value::Bool(result)),
+                    None => Err(ErrorType::IndeterminateComparison { lhs, rhs }),
+                },
+                None => Ok(Value::Bool(lhs.as_mean()? <= rhs.as_mean()?)),
+            },
             "Less or equal to",
             ValueType::FloatType,
             false,
@@ -260,7 +386,13 @@ impl Value {
         convert_and_apply(
             &self,
             &rhs,
-            &mut |lhs, rhs| Ok(Value::Bool(lhs.as_float()? >= rhs.as_float()?)),
+            &mut |lhs, rhs| match interval::operands(&lhs, &rhs)? {
+                Some((a, b)) => match interval::less_or_equal_to(b, a) {
+                    Some(result) => Ok(Value::Bool(result)),
+                    None => Err(ErrorType::IndeterminateComparison { lhs, rhs }),
+                },
+                None => Ok(Value::Bool(lhs.as_mean()? >= rhs.as_mean()?)),
+            },
             "Greater or equal to",
             ValueType::FloatType,
             false,
@@ -317,7 +449,21 @@ impl Value {
         match convert_and_apply(
             &self,
             &rhs,
-            &mut |lhs, rhs| Ok(Value::Bool(lhs.as_complex()? == rhs.as_complex()?)),
+            &mut |lhs, rhs| match interval::operands(&lhs, &rhs)? {
+                Some((a, b)) => match interval::equal_to(a, b) {
+                    Some(result) => Ok(Value::Bool(result)),
+                    None => Err(ErrorType::IndeterminateComparison { lhs, rhs }),
+                },
+                None => match uncertain::operands(&lhs, &rhs)? {
+                    // Measurements are equal if they have the same value and deviation.
+                    Some((a, b)) => Ok(Value::Bool(a.value == b.value && a.sigma() == b.sigma())),
+                    // Dual numbers are compared by their real part, like the other comparisons.
+                    None if lhs.is_dual() || rhs.is_dual() => {
+                        Ok(Value::Bool(lhs.as_mean()? == rhs.as_mean()?))
+                    }
+                    None => Ok(Value::Bool(lhs.as_complex()? == rhs.as_complex()?)),
+                },
+            },
             "Equal to",
             ValueType::ComplexType,
             false,
@@ -341,4 +487,132 @@ impl Value {
             false,
         )
     }
+
+    pub fn bitwise_and(self, rhs: Self) -> EvalResult<Self> {
+        convert_and_apply(
+            &self,
+            &rhs,
+            &mut |lhs, rhs| Ok(Value::Int(lhs.as_int()? & rhs.as_int()?)),
+            "Bitwise AND",
+            ValueType::IntType,
+            false,
+        )
+    }
+
+    pub fn bitwise_or(self, rhs: Self) -> EvalResult<Self> {
+        convert_and_apply(
+            &self,
+            &rhs,
+            &mut |lhs, rhs| Ok(Value::Int(lhs.as_int()? | rhs.as_int()?)),
+            "Bitwise OR",
+            ValueType::IntType,
+            false,
+        )
+    }
+
+    pub fn bitwise_xor(self, rhs: Self) -> EvalResult<Self> {
+        convert_and_apply(
+            &self,
+            &rhs,
+            &mut |lhs, rhs| Ok(Value::Int(lhs.as_int()? ^ rhs.as_int()?)),
+            "Bitwise XOR",
+            ValueType::IntType,
+            false,
+        )
+    }
+
+    pub fn bitwise_not(self) -> EvalResult<Self> {
+        convert_and_apply(
+            &self,
+            &Value::Int(0),
+            &mut |lhs, _| Ok(Value::Int(!lhs.as_int()?)),
+            "Bitwise NOT",
+            ValueType::IntType,
+            false,
+        )
+    }
+
+    pub fn shift_left(self, rhs: Self) -> EvalResult<Self> {
+        self.shift(rhs, "<<", "Left shift", IntValue::checked_shl)
+    }
+
+    pub fn shift_right(self, rhs: Self) -> EvalResult<Self> {
+        self.shift(rhs, ">>", "Right shift", IntValue::checked_shr)
+    }
+
+    /// Shifts the bits of the value, failing for shifts not between 0 and 63.
+    fn shift(
+        self,
+        rhs: Self,
+        operator: &'static str,
+        operation_name: &'static str,
+        shift: fn(IntValue, u32) -> Option<IntValue>,
+    ) -> EvalResult<Self> {
+        convert_and_apply(
+            &self,
+            &rhs,
+            &mut |lhs, rhs| {
+                let value = lhs.as_int()?;
+                match u32::try_from(rhs.as_int()?)
+                    .ok()
+                    .and_then(|bits| shift(value, bits))
+                {
+                    Some(result) => Ok(Value::Int(result)),
+                    None => Err(ErrorType::OutOfDomain {
+                        func_name: operator.to_owned(),
+                        value: rhs,
+                    }),
+                }
+            },
+            operation_name,
+            ValueType::IntType,
+            false,
+        )
+    }
+
+    pub fn factorial(self) -> EvalResult<Self> {
+        self.stepped_factorial(1, "Factorial")
+    }
+
+    pub fn double_factorial(self) -> EvalResult<Self> {
+        self.stepped_factorial(2, "Double factorial")
+    }
+
+    /// Returns the product of the positive integers up to the value, decreasing by
+    /// `step`. Results too big for an int are floats.
+    fn stepped_factorial(self, step: usize, operation_name: &'static str) -> EvalResult<Self> {
+        convert_and_apply(
+            &self,
+            // A float, so that the results too big for an int are not converted back.
+            &Value::Float(0.0),
+            &mut |lhs, _| {
+                let n = lhs.as_int()?;
+                if n < 0 {
+                    return Err(ErrorType::OutOfDomain {
+                        func_name: if step == 1 { "!" } else { "!!" }.to_owned(),
+                        value: lhs,
+                    });
+                }
+                let factors = (1..=n).rev().step_by(step);
+                Ok(match factors.clone().try_fold(1, IntValue::checked_mul) {
+                    Some(product) => Value::Int(product),
+                    None => Value::Float(
+                        factors
+                            .map(|k| k as FloatValue)
+                            .try_fold(1.0, |product: FloatValue, k| {
+                                Some(product * k).filter(|product| product.is_finite())
+                            })
+                            .unwrap_or(FloatValue::INFINITY),
+                    ),
+                })
+            },
+            operation_name,
+            ValueType::IntType,
+            false,
+        )
+    }
+
+    pub fn percent(self) -> EvalResult<Self> {
+        self.div(Value::Int(100))
+    }
 }
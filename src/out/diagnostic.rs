@@ -0,0 +1,90 @@
+use super::{ErrorType, EvalResult, Warning, WarningKind};
+
+/// A problem found while parsing, with the part of the input it refers to.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Diagnostic {
+    /// The problem.
+    pub error: ErrorType,
+    /// The position of the first char of the problem inside the input, counted in chars.
+    pub position: usize,
+    /// The length of the problem in chars. It is zero for missing parts, like an
+    /// operand, which are found at `position`.
+    pub length: usize,
+}
+
+/// Collects the problems and the warnings found while parsing. By default the first
+/// problem is returned as an error, while a recovering collector records it and lets
+/// parsing continue.
+#[derive(Debug)]
+pub(crate) struct Diagnostics {
+    recovering: bool,
+    found: Vec<Diagnostic>,
+    warnings: Vec<Warning>,
+}
+
+impl Diagnostics {
+    /// Creates a collector failing on the first problem.
+    pub fn new() -> Self {
+        Self {
+            recovering: false,
+            found: vec![],
+            warnings: vec![],
+        }
+    }
+
+    /// Creates a collector recording every problem.
+    pub fn recovering() -> Self {
+        Self {
+            recovering: true,
+            ..Self::new()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.found.is_empty()
+    }
+
+    /// Records a problem and returns `fallback` to continue parsing with, or returns
+    /// the error if the collector is strict.
+    pub fn recover<T>(
+        &mut self,
+        error: ErrorType,
+        position: usize,
+        length: usize,
+        fallback: T,
+    ) -> EvalResult<T> {
+        if self.recovering {
+            self.found.push(Diagnostic {
+                error,
+                position,
+                length,
+            });
+            Ok(fallback)
+        } else {
+            Err(error)
+        }
+    }
+
+    /// Records a part of the input that was read implicitly.
+    pub fn warn(&mut self, kind: WarningKind, position: usize, length: usize) {
+        self.warnings.push(Warning {
+            kind,
+            position,
+            length,
+        });
+    }
+
+    /// Removes and returns the recorded warnings, sorted by position.
+    pub fn take_warnings(&mut self) -> Vec<Warning> {
+        let mut warnings = std::mem::take(&mut self.warnings);
+        warnings.sort_by_key(|warning| warning.position);
+        warnings
+    }
+
+    /// Returns the recorded problems, sorted by position.
+    pub fn into_vec(mut self) -> Vec<Diagnostic> {
+        self.found.sort_by_key(|diagnostic| diagnostic.position);
+        self.found
+    }
+}
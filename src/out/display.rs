@@ -1,97 +1,145 @@
-use super::ErrorType::{self, *};
+use super::{
+    locale::{self, MessageCatalog},
+    ErrorType::{self, *},
+};
 use std::fmt;
 
 impl fmt::Display for ErrorType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message(&locale::English))
+    }
+}
+
+impl ErrorType {
+    /// Returns the message of the error in the language of the catalog, falling back
+    /// to English for the errors the catalog does not translate.
+    ///
+    /// ```
+    /// use num_parser::*;
+    ///
+    /// let error = ErrorType::UnknownVar {
+    ///     var_name: "x".to_owned(),
+    ///     suggestions: vec![],
+    /// };
+    ///
+    /// assert_eq!(
+    ///     error.message(&locale::Italian),
+    ///     "ERRORE DI SINTASSI: variabile sconosciuta `x`."
+    /// );
+    /// ```
+    pub fn message(&self, catalog: &dyn MessageCatalog) -> String {
+        let template = catalog
+            .template(self.code())
+            .or_else(|| locale::English.template(self.code()))
+            .unwrap_or_default();
+
+        let mut message = locale::substitute(template, &self.fields(catalog));
+
+        if !self.suggestions().is_empty() {
+            let suggestions = self
+                .suggestions()
+                .iter()
+                .map(|suggestion| format!("`{}`", suggestion))
+                .collect::<Vec<String>>()
+                .join(", ");
+            let hint = catalog
+                .template(locale::SUGGESTIONS)
+                .or_else(|| locale::English.template(locale::SUGGESTIONS))
+                .unwrap_or_default();
+            message += " ";
+            message += &locale::substitute(hint, &[("suggestions", suggestions)]);
+        }
+
+        message
+    }
+
+    /// Returns the fields of the error, displayed, to be substituted in a template.
+    /// Wrapped errors are written with the same catalog.
+    fn fields(&self, catalog: &dyn MessageCatalog) -> Vec<(&'static str, String)> {
         match self {
-            TypeError { expected, given } => write!(
-                f,
-                "MATH ERROR: a function expected type {}, but a type {} was given.",
-                expected, given
-            ),
-            UnknownToken { token } => write!(
-                f,
-                "SYNTAX ERROR: an invalid token was provided: `{}`.",
-                token
-            ),
-            InvalidTokenPosition { token } => {
-                write!(f, "SYNTAX ERROR: invalid position for token `{}`.", token)
-            }
-            FailedCast { value, from, to } => write!(
-                f,
-                "MATH ERROR: could not cast value `{}` from type {} to type {}.",
-                value, from, to
-            ),
+            TypeError { expected, given } => vec![
+                ("expected", expected.to_string()),
+                ("given", given.to_string()),
+            ],
+            UnknownToken { token } => vec![("token", token.clone())],
+            InvalidTokenPosition { token }
+            | NotAnOperator { token }
+            | MissingOperatorArgument { token } => vec![("token", token.to_string())],
+            FailedCast { value, from, to } => vec![
+                ("value", value.to_string()),
+                ("from", from.to_string()),
+                ("to", to.to_string()),
+            ],
             MismatchedArrayLengths {
                 first,
                 second,
                 operation_name,
-            } => write!(
-                f,
-                "MATH ERROR: invalid vectors sizes {} and {} for operation `{}`.",
-                first, second, operation_name
-            ),
-            DivideByZero { numerator } => {
-                write!(f, "MATH ERROR: trying to divide {} by zero.", numerator)
-            }
-            NotAnOperator { token } => {
-                write!(f, "SYNTAX ERROR: `{}` is not a valid operator!", token)
-            }
-            InvalidClosingBracket => write!(f, "SYNTAX ERROR: invalid closing bracket."),
-            MissingClosingBracket => write!(f, "SYNTAX ERROR: missing closing bracket."),
-            MissingOperatorArgument { token } => {
-                write!(f, "SYNTAX ERROR: missing argument for operator `{}`", token)
-            }
-            FailedParse { value } => write!(f, "SYNTAX ERROR: could not parse value `{}`.", value),
-            EmptyBrackets => write!(f, "SYNTAX ERROR: invalid empty brackets."),
+            } => vec![
+                ("first", first.to_string()),
+                ("second", second.to_string()),
+                ("operation_name", operation_name.clone()),
+            ],
+            DivideByZero { numerator } => vec![("numerator", numerator.to_string())],
+            FailedParse { value } => vec![("value", value.clone())],
             WrongFunctionArgumentsAmount {
                 func_name,
                 expected,
                 given,
-            } => write!(
-                f,
-                "SYNTAX ERROR: function `{}` expected {} arguments, but got {}.",
-                func_name, expected, given
-            ),
-            MissingFunctionParameters { func_name } => write!(
-                f,
-                "SYNTAX ERROR: no arguments provided for function `{}`.",
-                func_name
-            ),
-            InvalidDeclaration => write!(f, "SYNTAX ERROR: invalid declaration."),
-            UnknownFunction { func_name } => {
-                write!(f, "SYNTAX ERROR: unknown function `{}`.", func_name)
+            } => vec![
+                ("func_name", func_name.clone()),
+                ("expected", expected.to_string()),
+                ("given", given.to_string()),
+            ],
+            MissingFunctionParameters { func_name }
+            | ExpectedFunctionArgument { func_name }
+            | UnknownFunction { func_name, .. }
+            | ReservedFunctionName { func_name } => vec![("func_name", func_name.clone())],
+            UnknownVar { var_name, .. } | ReservedVarName { var_name } => {
+                vec![("var_name", var_name.clone())]
             }
-            UnknownVar { var_name } => write!(f, "SYNTAX ERROR: unknown variable `{}`.", var_name),
-            ReservedVarName { var_name } => write!(
-                f,
-                "INTERNAL ERROR: `{}` is a keyword and cannot be used as a variable name.",
-                var_name
-            ),
-            ReservedFunctionName { func_name } => write!(
-                f,
-                "INTERNAL ERROR: `{}` is a keyword cannot be used as a function name.",
-                func_name
-            ),
-            EmptyUnion => write!(f, "SYNTAX ERROR: trying to aggregate an empty value."),
-            InvalidMutableContext { request } => write!(
-                f,
-                "INTERNAL ERROR: request `{}` is not valid for a static context",
-                request
-            ),
-            RecursionDepthLimitReached { limit } => {
-                write!(f, "INTERNAL ERROR: maximum depth reached: {}.", limit)
+            UnknownDeclaration { identifier } => vec![("identifier", identifier.clone())],
+            InvalidDirective { directive, line } => {
+                vec![("directive", directive.clone()), ("line", line.to_string())]
             }
-
+            InvalidCommand { command } => vec![("command", command.clone())],
+            InvalidMutableContext { request } => vec![("request", request.to_string())],
+            RecursionDepthLimitReached { limit } => vec![("limit", limit.to_string())],
+            InvalidInterval { lower, upper } => {
+                vec![("lower", lower.to_string()), ("upper", upper.to_string())]
+            }
+            OutOfDomain { func_name, value } => vec![
+                ("func_name", func_name.clone()),
+                ("value", value.to_string()),
+            ],
+            IndeterminateComparison { lhs, rhs } => {
+                vec![("lhs", lhs.to_string()), ("rhs", rhs.to_string())]
+            }
+            NotDifferentiable { value } => vec![("value", value.to_string())],
             ErrorDuring {
                 operation_name,
                 error,
-            } => write!(
-                f,
-                "An error occurred during operation `{}`: \n {}",
-                operation_name, *error
-            ),
-            InternalError { message } => write!(f, "INTERNAL ERROR: {}.", message),
+            } => vec![
+                ("operation_name", operation_name.clone()),
+                ("error", error.message(catalog)),
+            ],
+            ErrorInStatement {
+                statement,
+                line,
+                error,
+            } => vec![
+                ("statement", statement.to_string()),
+                ("line", line.to_string()),
+                ("error", error.message(catalog)),
+            ],
+            AmbiguousSyntax { warning } => vec![("warning", warning.message(catalog))],
+            AmbiguousComment { divisor } => vec![("divisor", divisor.clone())],
+            ExplicitSyntaxRequired { identifier } => vec![("identifier", identifier.clone())],
+            InternalError { message } => vec![("message", message.clone())],
+            InvalidClosingBracket
+            | MissingClosingBracket
+            | EmptyBrackets
+            | InvalidDeclaration
+            | EmptyUnion => vec![],
         }
     }
 }
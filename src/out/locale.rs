@@ -0,0 +1,188 @@
+//!
+//! Contains the catalogs used to write error messages in different languages.
+//!
+//! A catalog maps the [code](crate::ErrorType::code) of each error and warning to a
+//! message template, where their fields are written as `{name}`, like in
+//! `"trying to divide {numerator} by zero."`. Implement [`MessageCatalog`] to add a
+//! language, or use a `HashMap` from codes to templates. The key `suggestions` holds
+//! the hint added to errors suggesting other names, like `"Did you mean {suggestions}?"`.
+//!
+//! ```
+//! use num_parser::*;
+//! use std::collections::HashMap;
+//!
+//! let mut spanish = HashMap::new();
+//! spanish.insert(
+//!     "E0006".to_owned(),
+//!     "ERROR MATEMÁTICO: división de {numerator} por cero.".to_owned(),
+//! );
+//!
+//! let error = ErrorType::DivideByZero { numerator: Value::from(2) };
+//! assert_eq!(error.message(&spanish), "ERROR MATEMÁTICO: división de 2 por cero.");
+//! ```
+
+use std::collections::HashMap;
+
+/// The key of the template of the hint listing suggested names.
+pub const SUGGESTIONS: &str = "suggestions";
+
+/// A table of error message templates, keyed by error code.
+pub trait MessageCatalog {
+    /// Returns the template of the error with the provided code, or `None` if it is
+    /// not translated.
+    fn template(&self, code: &str) -> Option<&str>;
+}
+
+impl MessageCatalog for HashMap<String, String> {
+    fn template(&self, code: &str) -> Option<&str> {
+        self.get(code).map(|template| &template[..])
+    }
+}
+
+/// The English messages, used by default.
+#[derive(Clone, Copy, Debug)]
+pub struct English;
+
+impl MessageCatalog for English {
+    fn template(&self, code: &str) -> Option<&str> {
+        find(ENGLISH, code)
+    }
+}
+
+/// The Italian messages.
+#[derive(Clone, Copy, Debug)]
+pub struct Italian;
+
+impl MessageCatalog for Italian {
+    fn template(&self, code: &str) -> Option<&str> {
+        find(ITALIAN, code)
+    }
+}
+
+fn find(table: &'static [(&str, &str)], code: &str) -> Option<&'static str> {
+    table
+        .iter()
+        .find(|(template_code, _)| *template_code == code)
+        .map(|(_, template)| *template)
+}
+
+/// Replaces the `{name}` placeholders of a template with the value of the field with
+/// that name. Unknown placeholders are left as they are.
+pub(crate) fn substitute(template: &str, fields: &[(&str, String)]) -> String {
+    let mut message = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        message += &rest[..start];
+        let placeholder = &rest[start..];
+        let field = placeholder.find('}').and_then(|end| {
+            let name = &placeholder[1..end];
+            fields
+                .iter()
+                .find(|(field_name, _)| *field_name == name)
+                .map(|(_, value)| (value, end))
+        });
+
+        match field {
+            Some((value, end)) => {
+                message += value;
+                rest = &placeholder[end + 1..];
+            }
+            None => {
+                message.push('{');
+                rest = &placeholder[1..];
+            }
+        }
+    }
+
+    message + rest
+}
+
+// The tables are easier to read and compare with one message per line.
+#[rustfmt::skip]
+const ENGLISH: &[(&str, &str)] = &[
+    ("E0001", "MATH ERROR: a function expected type {expected}, but a type {given} was given."),
+    ("E0002", "SYNTAX ERROR: an invalid token was provided: `{token}`."),
+    ("E0003", "SYNTAX ERROR: invalid position for token `{token}`."),
+    ("E0004", "MATH ERROR: could not cast value `{value}` from type {from} to type {to}."),
+    ("E0005", "MATH ERROR: invalid vectors sizes {first} and {second} for operation `{operation_name}`."),
+    ("E0006", "MATH ERROR: trying to divide {numerator} by zero."),
+    ("E0007", "SYNTAX ERROR: `{token}` is not a valid operator!"),
+    ("E0008", "SYNTAX ERROR: invalid closing bracket."),
+    ("E0009", "SYNTAX ERROR: missing closing bracket."),
+    ("E0010", "SYNTAX ERROR: missing argument for operator `{token}`"),
+    ("E0011", "SYNTAX ERROR: could not parse value `{value}`."),
+    ("E0012", "SYNTAX ERROR: invalid empty brackets."),
+    ("E0013", "SYNTAX ERROR: function `{func_name}` expected {expected} arguments, but got {given}."),
+    ("E0014", "SYNTAX ERROR: no arguments provided for function `{func_name}`."),
+    ("E0015", "SYNTAX ERROR: function `{func_name}` expects a function as argument."),
+    ("E0016", "SYNTAX ERROR: invalid declaration."),
+    ("E0017", "SYNTAX ERROR: unknown function `{func_name}`."),
+    ("E0018", "SYNTAX ERROR: unknown variable `{var_name}`."),
+    ("E0019", "SYNTAX ERROR: `{identifier}` is not a declared variable or function."),
+    ("E0020", "SYNTAX ERROR: invalid directive `{directive}` at line {line}."),
+    ("E0021", "SYNTAX ERROR: invalid use of command `{command}`."),
+    ("E0022", "INTERNAL ERROR: `{var_name}` is a keyword and cannot be used as a variable name."),
+    ("E0023", "INTERNAL ERROR: `{func_name}` is a keyword cannot be used as a function name."),
+    ("E0024", "SYNTAX ERROR: trying to aggregate an empty value."),
+    ("E0025", "INTERNAL ERROR: request `{request}` is not valid for a static context"),
+    ("E0026", "INTERNAL ERROR: maximum depth reached: {limit}."),
+    ("E0027", "MATH ERROR: invalid interval bounds {lower} and {upper}."),
+    ("E0028", "MATH ERROR: value {value} is outside of the domain of `{func_name}`."),
+    ("E0029", "MATH ERROR: the comparison between {lhs} and {rhs} is indeterminate."),
+    ("E0030", "An error occurred during operation `{operation_name}`: \n {error}"),
+    ("E0031", "An error occurred in statement {statement} (line {line}): \n {error}"),
+    ("E0032", "INTERNAL ERROR: {message}."),
+    ("E0033", "SYNTAX ERROR: ambiguous input, {warning}"),
+    ("E0034", "MATH ERROR: cannot differentiate an operation on {value}."),
+    ("E0035", "SYNTAX ERROR: `// {divisor}` is an integer division, use `#` for comments or brackets around the divisor."),
+    ("E0036", "SYNTAX ERROR: `{identifier}` is longer than one letter, so it can only be declared with the explicit syntax."),
+    ("W0001", "identifier `{identifier}` is read as {parts}."),
+    ("W0002", "implicit multiplication at position {position}."),
+    ("W0003", "implicit brackets around the argument of `{func_name}`."),
+    ("suggestions", "Did you mean {suggestions}?"),
+];
+
+#[rustfmt::skip]
+const ITALIAN: &[(&str, &str)] = &[
+    ("E0001", "ERRORE MATEMATICO: una funzione si aspettava il tipo {expected}, ma è stato fornito il tipo {given}."),
+    ("E0002", "ERRORE DI SINTASSI: è stato fornito un simbolo non valido: `{token}`."),
+    ("E0003", "ERRORE DI SINTASSI: posizione non valida per il simbolo `{token}`."),
+    ("E0004", "ERRORE MATEMATICO: impossibile convertire il valore `{value}` dal tipo {from} al tipo {to}."),
+    ("E0005", "ERRORE MATEMATICO: dimensioni dei vettori {first} e {second} non valide per l'operazione `{operation_name}`."),
+    ("E0006", "ERRORE MATEMATICO: divisione di {numerator} per zero."),
+    ("E0007", "ERRORE DI SINTASSI: `{token}` non è un operatore valido!"),
+    ("E0008", "ERRORE DI SINTASSI: parentesi di chiusura non valida."),
+    ("E0009", "ERRORE DI SINTASSI: parentesi di chiusura mancante."),
+    ("E0010", "ERRORE DI SINTASSI: argomento mancante per l'operatore `{token}`."),
+    ("E0011", "ERRORE DI SINTASSI: impossibile interpretare il valore `{value}`."),
+    ("E0012", "ERRORE DI SINTASSI: parentesi vuote non valide."),
+    ("E0013", "ERRORE DI SINTASSI: la funzione `{func_name}` si aspettava {expected} argomenti, ma ne ha ricevuti {given}."),
+    ("E0014", "ERRORE DI SINTASSI: nessun argomento fornito per la funzione `{func_name}`."),
+    ("E0015", "ERRORE DI SINTASSI: la funzione `{func_name}` si aspetta una funzione come argomento."),
+    ("E0016", "ERRORE DI SINTASSI: dichiarazione non valida."),
+    ("E0017", "ERRORE DI SINTASSI: funzione sconosciuta `{func_name}`."),
+    ("E0018", "ERRORE DI SINTASSI: variabile sconosciuta `{var_name}`."),
+    ("E0019", "ERRORE DI SINTASSI: `{identifier}` non è una variabile o una funzione dichiarata."),
+    ("E0020", "ERRORE DI SINTASSI: direttiva `{directive}` non valida alla riga {line}."),
+    ("E0021", "ERRORE DI SINTASSI: uso non valido del comando `{command}`."),
+    ("E0022", "ERRORE INTERNO: `{var_name}` è una parola chiave e non può essere usata come nome di variabile."),
+    ("E0023", "ERRORE INTERNO: `{func_name}` è una parola chiave e non può essere usata come nome di funzione."),
+    ("E0024", "ERRORE DI SINTASSI: tentativo di aggregare un valore vuoto."),
+    ("E0025", "ERRORE INTERNO: la richiesta `{request}` non è valida per un contesto statico."),
+    ("E0026", "ERRORE INTERNO: raggiunta la profondità massima: {limit}."),
+    ("E0027", "ERRORE MATEMATICO: estremi dell'intervallo {lower} e {upper} non validi."),
+    ("E0028", "ERRORE MATEMATICO: il valore {value} è fuori dal dominio di `{func_name}`."),
+    ("E0029", "ERRORE MATEMATICO: il confronto tra {lhs} e {rhs} è indeterminato."),
+    ("E0030", "Si è verificato un errore durante l'operazione `{operation_name}`: \n {error}"),
+    ("E0031", "Si è verificato un errore nell'istruzione {statement} (riga {line}): \n {error}"),
+    ("E0032", "ERRORE INTERNO: {message}."),
+    ("E0033", "ERRORE DI SINTASSI: input ambiguo, {warning}"),
+    ("E0034", "ERRORE MATEMATICO: impossibile derivare un'operazione su {value}."),
+    ("E0035", "ERRORE DI SINTASSI: `// {divisor}` è una divisione intera, usa `#` per i commenti o le parentesi attorno al divisore."),
+    ("E0036", "ERRORE DI SINTASSI: `{identifier}` è più lungo di una lettera, quindi può essere dichiarato solo con la sintassi esplicita."),
+    ("W0001", "l'identificatore `{identifier}` è letto come {parts}."),
+    ("W0002", "moltiplicazione implicita alla posizione {position}."),
+    ("W0003", "parentesi implicite attorno all'argomento di `{func_name}`."),
+    ("suggestions", "Forse intendevi {suggestions}?"),
+];
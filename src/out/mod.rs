@@ -1,4 +1,13 @@
+mod diagnostic;
 mod display;
+pub mod locale;
+#[cfg(feature = "serde")]
+mod serialization;
+mod warning;
+
+pub use diagnostic::Diagnostic;
+pub(crate) use diagnostic::Diagnostics;
+pub use warning::{Warning, WarningKind};
 
 use crate::objects::Request;
 
@@ -21,76 +30,333 @@ pub type EvalResult<T> = Result<T, ErrorType>;
 ///
 /// assert_eq!(msg, "MATH ERROR: trying to divide 2 by zero.");
 /// ```
-#[derive(Debug)]
+///
+/// With the `serde` feature, errors are serialized as `{ code, message, data }`, where
+/// `code` is the one returned by [`code`](Self::code), `message` is the displayed error
+/// and `data` contains the fields of the variant, if any.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(remote = "Self", tag = "code", content = "data")
+)]
 pub enum ErrorType {
     /// A mismatched type.
+    #[cfg_attr(feature = "serde", serde(rename = "E0001"))]
     TypeError {
         expected: ValueType,
         given: ValueType,
     },
     /// An unknown token found while parsing the string.
+    #[cfg_attr(feature = "serde", serde(rename = "E0002"))]
     UnknownToken { token: String },
     /// A known token placed in an invalid position.
+    #[cfg_attr(feature = "serde", serde(rename = "E0003"))]
     InvalidTokenPosition { token: TokenType },
     /// A failed cast due to data loss.
+    #[cfg_attr(feature = "serde", serde(rename = "E0004"))]
     FailedCast {
         value: Value,
         from: ValueType,
         to: ValueType,
     },
     /// Two arrays with different lengths.
+    #[cfg_attr(feature = "serde", serde(rename = "E0005"))]
     MismatchedArrayLengths {
         first: usize,
         second: usize,
-        operation_name: &'static str,
+        operation_name: String,
     },
     /// Trying to divide by zero.
+    #[cfg_attr(feature = "serde", serde(rename = "E0006"))]
     DivideByZero { numerator: Value },
     /// A token which is not an operator being used as such.
+    #[cfg_attr(feature = "serde", serde(rename = "E0007"))]
     NotAnOperator { token: TokenType },
     /// An invalid closing bracket.
+    #[cfg_attr(feature = "serde", serde(rename = "E0008"))]
     InvalidClosingBracket,
     /// A missing closing bracket.
+    #[cfg_attr(feature = "serde", serde(rename = "E0009"))]
     MissingClosingBracket,
     /// A missing left argument for an operator.
+    #[cfg_attr(feature = "serde", serde(rename = "E0010"))]
     MissingOperatorArgument { token: TokenType },
     /// An error occurred while parsing a literal.
+    #[cfg_attr(feature = "serde", serde(rename = "E0011"))]
     FailedParse { value: String },
     /// Two brackets with nothing inside.
+    #[cfg_attr(feature = "serde", serde(rename = "E0012"))]
     EmptyBrackets,
     /// A function call with the wrong function arguments amount.
+    #[cfg_attr(feature = "serde", serde(rename = "E0013"))]
     WrongFunctionArgumentsAmount {
         func_name: String,
         expected: u8,
         given: u8,
     },
     /// A function with no parameters.
+    #[cfg_attr(feature = "serde", serde(rename = "E0014"))]
     MissingFunctionParameters { func_name: String },
+    /// A function expecting another function as an argument.
+    #[cfg_attr(feature = "serde", serde(rename = "E0015"))]
+    ExpectedFunctionArgument { func_name: String },
     /// An invalid declaration.
+    #[cfg_attr(feature = "serde", serde(rename = "E0016"))]
     InvalidDeclaration,
     /// An unknown function.
-    UnknownFunction { func_name: String },
+    #[cfg_attr(feature = "serde", serde(rename = "E0017"))]
+    UnknownFunction {
+        func_name: String,
+        /// The closest known functions, the most likely first.
+        #[cfg_attr(feature = "serde", serde(default))]
+        suggestions: Vec<String>,
+    },
     /// An unknown variable.
-    UnknownVar { var_name: String },
+    #[cfg_attr(feature = "serde", serde(rename = "E0018"))]
+    UnknownVar {
+        var_name: String,
+        /// The closest known variables, the most likely first.
+        #[cfg_attr(feature = "serde", serde(default))]
+        suggestions: Vec<String>,
+    },
+    /// An identifier which is neither a variable nor a function.
+    #[cfg_attr(feature = "serde", serde(rename = "E0019"))]
+    UnknownDeclaration { identifier: String },
+    /// A script directive with an unknown setting or an invalid value.
+    #[cfg_attr(feature = "serde", serde(rename = "E0020"))]
+    InvalidDirective { directive: String, line: usize },
+    /// A command used inside an expression or with invalid arguments.
+    #[cfg_attr(feature = "serde", serde(rename = "E0021"))]
+    InvalidCommand { command: String },
     /// A reserved variable name.
+    #[cfg_attr(feature = "serde", serde(rename = "E0022"))]
     ReservedVarName { var_name: String },
     /// A reserved function name.
+    #[cfg_attr(feature = "serde", serde(rename = "E0023"))]
     ReservedFunctionName { func_name: String },
     /// An empty union ,,
+    #[cfg_attr(feature = "serde", serde(rename = "E0024"))]
     EmptyUnion,
     /// Invalid request for a static context
+    #[cfg_attr(feature = "serde", serde(rename = "E0025"))]
     InvalidMutableContext { request: Request },
     /// Reached maximum recursion depth.
+    #[cfg_attr(feature = "serde", serde(rename = "E0026"))]
     RecursionDepthLimitReached { limit: u32 },
+    /// An interval with a lower bound greater than the upper one.
+    #[cfg_attr(feature = "serde", serde(rename = "E0027"))]
+    InvalidInterval { lower: Value, upper: Value },
+    /// A value outside of the domain of a function.
+    #[cfg_attr(feature = "serde", serde(rename = "E0028"))]
+    OutOfDomain { func_name: String, value: Value },
+    /// A comparison whose result depends on the values picked from the operands.
+    #[cfg_attr(feature = "serde", serde(rename = "E0029"))]
+    IndeterminateComparison { lhs: Value, rhs: Value },
+    /// An ambiguous input, found with the `Strict` syntax setting.
+    #[cfg_attr(feature = "serde", serde(rename = "E0033"))]
+    AmbiguousSyntax { warning: Warning },
+    /// A function differentiated through an operation that has no derivative.
+    #[cfg_attr(feature = "serde", serde(rename = "E0034"))]
+    NotDifferentiable { value: Value },
+    /// An integer division by a name in a script, which looks like a comment.
+    #[cfg_attr(feature = "serde", serde(rename = "E0035"))]
+    AmbiguousComment { divisor: String },
+    /// A new name longer than one letter, declared without the `Explicit` syntax setting.
+    #[cfg_attr(feature = "serde", serde(rename = "E0036"))]
+    ExplicitSyntaxRequired { identifier: String },
 
     /// An error wrapper to add additional information.
+    #[cfg_attr(feature = "serde", serde(rename = "E0030"))]
     ErrorDuring {
-        operation_name: &'static str,
+        operation_name: String,
+        error: Box<ErrorType>,
+    },
+    /// An error in a statement of a script.
+    #[cfg_attr(feature = "serde", serde(rename = "E0031"))]
+    ErrorInStatement {
+        /// The position of the statement in the script, starting from one.
+        statement: usize,
+        /// The line the statement is on, starting from one.
+        line: usize,
         error: Box<ErrorType>,
     },
     /// An error due to a missing implementation or a bug. This should
     /// never occur.
+    #[cfg_attr(feature = "serde", serde(rename = "E0032"))]
     InternalError { message: String },
 }
 
-impl std::error::Error for ErrorType {}
+/// The kind of an error, to handle errors without reading their message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ErrorCategory {
+    /// An invalid expression or declaration.
+    Syntax,
+    /// An operation that is not defined for its operands.
+    Math,
+    /// A value of the wrong type.
+    Type,
+    /// A limit set in the context was reached.
+    Limit,
+    /// A bug or a missing implementation.
+    Internal,
+}
+
+impl ErrorType {
+    /// Returns the code of the error, like `E0006`. Codes never change between versions,
+    /// and new errors get new codes.
+    ///
+    /// ```
+    /// use num_parser::*;
+    ///
+    /// let error = eval("1 + unknown").unwrap_err();
+    ///
+    /// assert_eq!(error.code(), "E0018");
+    /// assert_eq!(error.category(), ErrorCategory::Syntax);
+    /// ```
+    pub fn code(&self) -> &'static str {
+        use ErrorType::*;
+        match self {
+            TypeError { .. } => "E0001",
+            UnknownToken { .. } => "E0002",
+            InvalidTokenPosition { .. } => "E0003",
+            FailedCast { .. } => "E0004",
+            MismatchedArrayLengths { .. } => "E0005",
+            DivideByZero { .. } => "E0006",
+            NotAnOperator { .. } => "E0007",
+            InvalidClosingBracket => "E0008",
+            MissingClosingBracket => "E0009",
+            MissingOperatorArgument { .. } => "E0010",
+            FailedParse { .. } => "E0011",
+            EmptyBrackets => "E0012",
+            WrongFunctionArgumentsAmount { .. } => "E0013",
+            MissingFunctionParameters { .. } => "E0014",
+            ExpectedFunctionArgument { .. } => "E0015",
+            InvalidDeclaration => "E0016",
+            UnknownFunction { .. } => "E0017",
+            UnknownVar { .. } => "E0018",
+            UnknownDeclaration { .. } => "E0019",
+            InvalidDirective { .. } => "E0020",
+            InvalidCommand { .. } => "E0021",
+            ReservedVarName { .. } => "E0022",
+            ReservedFunctionName { .. } => "E0023",
+            EmptyUnion => "E0024",
+            InvalidMutableContext { .. } => "E0025",
+            RecursionDepthLimitReached { .. } => "E0026",
+            InvalidInterval { .. } => "E0027",
+            OutOfDomain { .. } => "E0028",
+            IndeterminateComparison { .. } => "E0029",
+            AmbiguousSyntax { .. } => "E0033",
+            NotDifferentiable { .. } => "E0034",
+            AmbiguousComment { .. } => "E0035",
+            ExplicitSyntaxRequired { .. } => "E0036",
+            ErrorDuring { .. } => "E0030",
+            ErrorInStatement { .. } => "E0031",
+            InternalError { .. } => "E0032",
+        }
+    }
+
+    /// Returns the category of the error. Wrappers like `ErrorDuring` have the
+    /// category of the error they contain.
+    pub fn category(&self) -> ErrorCategory {
+        use ErrorType::*;
+        match self {
+            TypeError { .. } | FailedCast { .. } | MismatchedArrayLengths { .. } => {
+                ErrorCategory::Type
+            }
+            DivideByZero { .. }
+            | InvalidInterval { .. }
+            | OutOfDomain { .. }
+            | IndeterminateComparison { .. }
+            | NotDifferentiable { .. } => ErrorCategory::Math,
+            RecursionDepthLimitReached { .. } => ErrorCategory::Limit,
+            InternalError { .. } => ErrorCategory::Internal,
+            ErrorDuring { error, .. } | ErrorInStatement { error, .. } => error.category(),
+            UnknownToken { .. }
+            | InvalidTokenPosition { .. }
+            | NotAnOperator { .. }
+            | InvalidClosingBracket
+            | MissingClosingBracket
+            | MissingOperatorArgument { .. }
+            | FailedParse { .. }
+            | EmptyBrackets
+            | WrongFunctionArgumentsAmount { .. }
+            | MissingFunctionParameters { .. }
+            | ExpectedFunctionArgument { .. }
+            | InvalidDeclaration
+            | UnknownFunction { .. }
+            | UnknownVar { .. }
+            | UnknownDeclaration { .. }
+            | InvalidDirective { .. }
+            | InvalidCommand { .. }
+            | ReservedVarName { .. }
+            | ReservedFunctionName { .. }
+            | EmptyUnion
+            | InvalidMutableContext { .. }
+            | AmbiguousSyntax { .. }
+            | AmbiguousComment { .. }
+            | ExplicitSyntaxRequired { .. } => ErrorCategory::Syntax,
+        }
+    }
+
+    /// Returns the names suggested in place of an unknown one, the most likely first.
+    ///
+    /// ```
+    /// use num_parser::*;
+    ///
+    /// let error = eval("sqr(4)").unwrap_err();
+    ///
+    /// assert_eq!(error.suggestions(), ["sqrt"]);
+    /// assert_eq!(
+    ///     error.to_string(),
+    ///     "SYNTAX ERROR: unknown function `sqr`. Did you mean `sqrt`?"
+    /// );
+    /// ```
+    pub fn suggestions(&self) -> &[String] {
+        match self {
+            ErrorType::UnknownFunction { suggestions, .. }
+            | ErrorType::UnknownVar { suggestions, .. } => suggestions,
+            _ => &[],
+        }
+    }
+
+    /// Returns the error wrapped by this one, if any.
+    pub fn cause(&self) -> Option<&ErrorType> {
+        match self {
+            ErrorType::ErrorDuring { error, .. } | ErrorType::ErrorInStatement { error, .. } => {
+                Some(error)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns an iterator over the chain of errors wrapped by this one, from the
+    /// outermost to the innermost.
+    ///
+    /// ```
+    /// use num_parser::*;
+    ///
+    /// let mut context = Context::default();
+    /// let error = eval_script("a = 1\nb = c", &mut context).pop().unwrap().unwrap_err();
+    ///
+    /// let codes: Vec<&str> = error.causes().map(|cause| cause.code()).collect();
+    /// assert_eq!(codes, vec!["E0018"]);
+    /// ```
+    pub fn causes(&self) -> impl Iterator<Item = &ErrorType> {
+        std::iter::successors(self.cause(), |error| error.cause())
+    }
+
+    /// Returns the innermost error of the chain, which is the error itself if it does
+    /// not wrap another one.
+    pub fn root_cause(&self) -> &ErrorType {
+        self.causes().last().unwrap_or(self)
+    }
+}
+
+impl std::error::Error for ErrorType {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.cause()
+            .map(|error| error as &(dyn std::error::Error + 'static))
+    }
+}
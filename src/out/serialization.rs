@@ -0,0 +1,38 @@
+//!
+//! Serializes errors as `{ code, message, data }`.
+//!
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::ErrorType;
+
+/// An error as it is serialized: the `{ code, data }` written by the derived
+/// implementation of `ErrorType`, together with the message.
+#[derive(Serialize)]
+struct WithMessage<'a> {
+    #[serde(flatten, serialize_with = "serialize_fields")]
+    error: &'a ErrorType,
+    message: String,
+}
+
+/// Writes the code and the data of an error with the derived implementation.
+fn serialize_fields<S: Serializer>(error: &&ErrorType, serializer: S) -> Result<S::Ok, S::Error> {
+    ErrorType::serialize(error, serializer)
+}
+
+impl Serialize for ErrorType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        WithMessage {
+            error: self,
+            message: self.to_string(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ErrorType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // The message is ignored, as it is generated from the data.
+        ErrorType::deserialize(deserializer)
+    }
+}
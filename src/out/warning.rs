@@ -0,0 +1,81 @@
+use std::fmt;
+
+use super::locale::{self, MessageCatalog};
+
+/// An ambiguous part of the input, which was read in one of the possible ways.
+///
+/// Warnings are returned by `eval_with_warnings`, and are errors when the `syntax`
+/// setting of the context is `Strict`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Warning {
+    /// What was read implicitly.
+    pub kind: WarningKind,
+    /// The position of the ambiguous part inside the input, counted in chars.
+    pub position: usize,
+    /// The length of the ambiguous part in chars. It is zero for added tokens, which
+    /// are placed before the char at `position`.
+    pub length: usize,
+}
+
+/// Every way the input can be read implicitly.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WarningKind {
+    /// An identifier read as several ones, like `pix` as `pi` and `x`.
+    SplitIdentifier {
+        identifier: String,
+        parts: Vec<String>,
+    },
+    /// A multiplication added between two operands, like in `2x`.
+    ImplicitMultiplication,
+    /// Brackets added around the argument of a function, like in `sin x`.
+    ImplicitBrackets { func_name: String },
+}
+
+impl Warning {
+    /// Returns the code of the warning, like `W0002`. Codes never change between
+    /// versions.
+    pub fn code(&self) -> &'static str {
+        match self.kind {
+            WarningKind::SplitIdentifier { .. } => "W0001",
+            WarningKind::ImplicitMultiplication => "W0002",
+            WarningKind::ImplicitBrackets { .. } => "W0003",
+        }
+    }
+
+    /// Returns the message of the warning in the language of the catalog, falling back
+    /// to English for the warnings the catalog does not translate.
+    pub fn message(&self, catalog: &dyn MessageCatalog) -> String {
+        let template = catalog
+            .template(self.code())
+            .or_else(|| locale::English.template(self.code()))
+            .unwrap_or_default();
+
+        let fields = match &self.kind {
+            WarningKind::SplitIdentifier { identifier, parts } => vec![
+                ("identifier", identifier.clone()),
+                (
+                    "parts",
+                    parts
+                        .iter()
+                        .map(|part| format!("`{}`", part))
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                ),
+            ],
+            WarningKind::ImplicitMultiplication => {
+                vec![("position", self.position.to_string())]
+            }
+            WarningKind::ImplicitBrackets { func_name } => vec![("func_name", func_name.clone())],
+        };
+
+        locale::substitute(template, &fields)
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "WARNING: {}", self.message(&locale::English))
+    }
+}
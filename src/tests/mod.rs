@@ -48,6 +48,10 @@ fn vector() {
             Value::Vector(vec![Value::from(7), Value::from(8), Value::from(9),]),
         ])
     );
+    assert_eq!(
+        eval("1, 2").unwrap(),
+        Value::Vector(vec![Value::from(1), Value::from(2)])
+    );
     assert_eq!(
         eval("(1,(2,(3,4)))").unwrap(),
         Value::Vector(vec![
@@ -283,250 +287,2262 @@ fn logical_or_panic() {
     eval("1.2||true").unwrap();
 }
 
-// VARS DECLARATIONS
-
 #[test]
-fn var() {
+fn short_circuit() {
     let mut context = Context::default();
-
-    eval_with_mutable_context("a = 2", &mut context).unwrap();
-
+    eval_with_mutable_context("x = 0", &mut context).unwrap();
     assert_eq!(
-        eval_with_static_context("a", &context).unwrap(),
-        Value::from(2)
+        eval_with_mutable_context("x != 0 && 1/x > 2", &mut context).unwrap(),
+        Some(Value::from(false))
+    );
+    assert_eq!(
+        eval_with_mutable_context("x == 0 || 1/x > 2", &mut context).unwrap(),
+        Some(Value::from(true))
+    );
+    // Vectors are still compared element by element
+    assert_eq!(
+        eval("(true, false) && (true, true)").unwrap(),
+        Value::from(vec![true, false])
     );
 }
 
 #[test]
-#[should_panic]
-fn invalid_var_panic() {
-    let mut context = Context::default();
-
-    eval_with_mutable_context("ab = 2", &mut context).unwrap();
+fn conditional() {
+    assert_eq!(eval("true ? 1 : 2").unwrap(), Value::from(1));
+    assert_eq!(eval("1 > 2 ? 1 : 2").unwrap(), Value::from(2));
+    assert_eq!(eval("false ? 1 : true ? 2 : 3").unwrap(), Value::from(2));
+    assert_eq!(eval("false ? 1 : false ? 2 : 3").unwrap(), Value::from(3));
+    assert_eq!(eval("1 + (true ? 2 : 3) * 2").unwrap(), Value::from(5));
+    assert_eq!(eval("1 ? 0 ? 5 : 6 : 7").unwrap(), Value::from(6));
+    assert_eq!(eval("1 ? 1 ? 5 : 6 : 7").unwrap(), Value::from(5));
+    assert_eq!(eval("0 ? 0 ? 5 : 6 : 7").unwrap(), Value::from(7));
+    assert_eq!(eval("1 ? 1 ? 0 ? 4 : 5 : 6 : 7").unwrap(), Value::from(5));
+    assert_eq!(eval("if(1 < 2, 1, 2)").unwrap(), Value::from(1));
+    eval("true ? 1").unwrap_err();
+    eval("1 : 2").unwrap_err();
 }
 
-// FUNCTIONS DECLARATIONS
-
 #[test]
-fn func() {
+fn lazy_conditional() {
     let mut context = Context::default();
-
-    eval_with_mutable_context("f(x) = xcos(x)", &mut context).unwrap();
-
+    eval_with_mutable_context("x = 0", &mut context).unwrap();
     assert_eq!(
-        eval_with_static_context("f(pi)", &context).unwrap(),
-        Value::from(-3.14159265)
+        eval_with_mutable_context("x == 0 ? 1 : 1/x > 2", &mut context).unwrap(),
+        Some(Value::from(1))
+    );
+    assert_eq!(
+        eval_with_mutable_context("if(x == 0, 1, 1/x > 2)", &mut context).unwrap(),
+        Some(Value::from(1))
     );
 }
 
 #[test]
-#[should_panic]
-fn unknown_func_panic() {
+fn piecewise() {
     let mut context = Context::default();
-
-    eval_with_mutable_context("f(x) = g(x)", &mut context).unwrap();
-
-    eval_with_static_context("f(1)", &context).unwrap();
+    eval_with_mutable_context("s(x) = piecewise(x < 0, -1, x > 0, 1, 0)", &mut context).unwrap();
+    assert_eq!(
+        eval_with_mutable_context("s(-3)", &mut context).unwrap(),
+        Some(Value::from(-1))
+    );
+    assert_eq!(
+        eval_with_mutable_context("s(3)", &mut context).unwrap(),
+        Some(Value::from(1))
+    );
+    assert_eq!(
+        eval_with_mutable_context("s(0)", &mut context).unwrap(),
+        Some(Value::from(0))
+    );
+    eval("piecewise(true, 1)").unwrap_err();
+}
+
+#[test]
+fn unicode_input() {
+    assert_eq!(eval("2 × 3 · 4").unwrap(), eval("2 * 3 * 4").unwrap());
+    assert_eq!(eval("6 ÷ 4 − 1").unwrap(), eval("6 / 4 - 1").unwrap());
+    assert_eq!(eval("1 ≤ 2").unwrap(), Value::from(true));
+    assert_eq!(eval("2 ≥ 3").unwrap(), Value::from(false));
+    assert_eq!(eval("1 ≠ 2").unwrap(), Value::from(true));
+    assert_eq!(eval("√16 + ∛27").unwrap(), Value::from(7));
+    assert_eq!(eval("2√(2)").unwrap(), eval("2*sqrt(2)").unwrap());
+    assert_eq!(eval("2π").unwrap(), eval("τ").unwrap());
+    assert_eq!(eval("φ").unwrap(), eval("phi").unwrap());
+    assert_eq!(eval("-∞ < 0").unwrap(), Value::from(true));
+    // Superscripts are exponents.
+    assert_eq!(eval("3² + 2¹⁰").unwrap(), eval("3^2 + 2^10").unwrap());
+    assert_eq!(eval("10⁻¹").unwrap(), Value::from(0.1));
+    // Angles in degrees are converted to the angle unit of the context.
+    assert_eq!(eval("sin 90°").unwrap(), Value::from(1));
+    assert_eq!(eval("(45 + 45)°").unwrap(), eval("pi/2").unwrap());
+    let context = Context::new(
+        settings::Rounding::default(),
+        settings::AngleUnit::Turn,
+        settings::DepthLimit::default(),
+    );
+    assert_eq!(
+        eval_with_static_context("180°", &context).unwrap(),
+        Value::from(0.5)
+    );
+    assert_eq!(
+        eval("°").unwrap_err(),
+        ErrorType::MissingFunctionParameters {
+            func_name: "deg".to_owned()
+        }
+    );
 }
 
 #[test]
-#[should_panic]
-fn invalid_func_panic() {
+fn absolute_value_bars() {
+    assert_eq!(eval("|-3|").unwrap(), Value::from(3));
+    assert_eq!(eval("|2 - 5| * 2").unwrap(), Value::from(6));
+    assert_eq!(eval("||-3| - |5||").unwrap(), Value::from(2));
+    assert_eq!(eval("|3 + 4i|").unwrap(), Value::from(5));
+    // Bars following an operand are still logical ORs.
+    assert_eq!(eval("false || true").unwrap(), Value::from(true));
+    assert_eq!(eval("false || |-1| > 0").unwrap(), Value::from(true));
+
+    // Bars are matched within the same brackets.
+    assert_eq!(eval("|(1|2)|").unwrap(), Value::from(3));
+    assert_eq!(eval("[|-1|, |2 - |-5||]").unwrap(), Value::from(vec![1, 3]));
+    // Bars with spaces on both sides are operators.
+    assert_eq!(eval("|1 | 2|").unwrap(), Value::from(3));
+    assert_eq!(eval("|2| | |4|").unwrap(), Value::from(6));
+    assert_eq!(eval("|-3| | 4").unwrap(), Value::from(7));
+    assert_eq!(eval("5 | -3").unwrap(), Value::from(-3));
+    // Adjacent absolute values are multiplied.
+    assert_eq!(eval("2|-3|").unwrap(), Value::from(6));
+    assert_eq!(eval("2 |-3|").unwrap(), Value::from(6));
+    assert_eq!(eval("|-1||-2|").unwrap(), Value::from(2));
+    assert_eq!(eval("|2|3").unwrap(), Value::from(6));
+    assert_eq!(eval("true || |-1||-2| > 1").unwrap(), Value::from(true));
+}
+
+#[test]
+fn floor_and_ceiling_brackets() {
+    assert_eq!(eval("⌊2.7⌋").unwrap(), Value::from(2));
+    assert_eq!(eval("⌈2.1⌉").unwrap(), Value::from(3));
+    assert_eq!(eval("2⌊-1.5⌋").unwrap(), Value::from(-4));
+    assert_eq!(eval("⌊2.7)").unwrap_err(), ErrorType::InvalidClosingBracket);
+}
+
+#[test]
+fn factorial() {
+    assert_eq!(eval("5!").unwrap(), Value::from(120));
+    assert_eq!(eval("0!").unwrap(), Value::from(1));
+    assert_eq!(eval("(1 + 2)!").unwrap(), Value::from(6));
+    assert_eq!(eval("7!!").unwrap(), Value::from(105));
+    assert_eq!(eval("8!!").unwrap(), Value::from(384));
+    assert_eq!(eval("(3, 4)!").unwrap(), Value::from(vec![6, 24]));
+    // Factorials bind tighter than any other operator.
+    assert_eq!(eval("2^3!").unwrap(), Value::from(64));
+    assert_eq!(eval("3!^2").unwrap(), Value::from(36));
+    assert_eq!(eval("-3!").unwrap(), Value::from(-6));
+    assert_eq!(eval("2 * 3!").unwrap(), Value::from(12));
+    // Results too big for an int are floats.
+    assert_eq!(eval("21!").unwrap(), Value::from(51090942171709440000.0));
+    // An exclamation point before an operand or an equal is still a NOT.
+    assert_eq!(eval("!false").unwrap(), Value::from(true));
+    assert_eq!(eval("5! != 120").unwrap(), Value::from(false));
+    assert_eq!(
+        eval("(-1)!").unwrap_err(),
+        ErrorType::OutOfDomain {
+            func_name: "!".to_owned(),
+            value: Value::from(-1)
+        }
+    );
+    eval("2.5!").unwrap_err();
+}
+
+#[test]
+fn percent() {
+    assert_eq!(eval("20%").unwrap(), Value::from(0.2));
+    assert_eq!(eval("50% * 4").unwrap(), Value::from(2));
+    assert_eq!(eval("(1, 2)%").unwrap(), Value::from(vec![0.01, 0.02]));
+    // A percentage followed by an operand is still a modulo.
+    assert_eq!(eval("7 % 3").unwrap(), Value::from(1));
+    assert_eq!(eval("7 % (1 + 2)").unwrap(), Value::from(1));
+}
+
+#[test]
+fn bitwise() {
+    assert_eq!(eval("12 & 10").unwrap(), Value::from(8));
+    assert_eq!(eval("12 | 10").unwrap(), Value::from(14));
+    assert_eq!(eval("12 xor 10").unwrap(), Value::from(6));
+    assert_eq!(eval("~5").unwrap(), Value::from(-6));
+    assert_eq!(eval("1 << 4").unwrap(), Value::from(16));
+    assert_eq!(eval("256 >> 2").unwrap(), Value::from(64));
+    // Shifts bind looser than sums, and `&`, `xor` and `|` looser than shifts.
+    assert_eq!(eval("1 + 2 << 1").unwrap(), Value::from(6));
+    assert_eq!(eval("5 xor 3 | 8").unwrap(), Value::from(14));
+    assert_eq!(eval("1 | 6 & 3").unwrap(), Value::from(3));
+    assert_eq!(eval("6 & 3 == 2").unwrap(), Value::from(true));
+    // Vectors are combined element by element.
+    assert_eq!(eval("(12, 5) & 6").unwrap(), Value::from(vec![4, 4]));
+    assert_eq!(
+        eval("(1, 2, 3) << (1, 2, 3)").unwrap(),
+        Value::from(vec![2, 8, 24])
+    );
+    // Next to absolute values, `|` is written with spaces around it.
+    assert_eq!(eval("|-3| | 4").unwrap(), Value::from(7));
+    assert_eq!(eval("(1|2) | |-4|").unwrap(), Value::from(7));
+    assert_eq!(eval("|-3| | |-4| | 8").unwrap(), Value::from(15));
+    assert_eq!(eval("|-3||-4|").unwrap(), Value::from(12));
+    assert_eq!(
+        eval("1 << 64").unwrap_err(),
+        ErrorType::OutOfDomain {
+            func_name: "<<".to_owned(),
+            value: Value::from(64)
+        }
+    );
+    eval("2.5 & 1").unwrap_err();
+    // Only whole identifiers are operators.
     let mut context = Context::default();
-
-    eval_with_mutable_context("f(x) g(x) = 2", &mut context).unwrap();
+    eval_with_mutable_context("x = 2", &mut context).unwrap();
+    eval_with_mutable_context("o = 3", &mut context).unwrap();
+    eval_with_mutable_context("r = 5", &mut context).unwrap();
+    assert_eq!(
+        eval_with_mutable_context("rxor", &mut context).unwrap(),
+        Some(Value::from(150))
+    );
 }
 
 #[test]
-fn recursion() {
+fn integer_division() {
+    assert_eq!(eval("7 // 2").unwrap(), Value::from(3));
+    assert_eq!(eval("(-7) // 2").unwrap(), Value::from(-4));
+    assert_eq!(eval("7 // (-2)").unwrap(), Value::from(-4));
+    assert_eq!(eval("(-7) // (-2)").unwrap(), Value::from(3));
+    assert_eq!(eval("7.5 // 2").unwrap(), Value::from(3));
+    assert_eq!(eval("(7, 9) // 4").unwrap(), Value::from(vec![1, 2]));
+    assert_eq!(
+        eval("7 // 0").unwrap_err(),
+        ErrorType::DivideByZero {
+            numerator: Value::from(7)
+        }
+    );
+    // In scripts, `//` is a comment only at the start of a line.
     let mut context = Context::default();
-
-    eval_with_mutable_context("f(x) = branch(x<=2, 1, f(x-1)+f(x-2))", &mut context).unwrap();
-
+    let results = eval_script("// Halve\na = 7 // 2 # Rounded down", &mut context);
+    assert_eq!(results.len(), 1);
+    assert_eq!(context.get_value("a"), Some(Value::from(3)));
+    // A trailing `//` followed by a name looks like a comment, and is rejected.
+    context.syntax = settings::Syntax::Explicit;
+    let results = eval_script("note = 2\nx = 1 // note", &mut context);
+    assert!(matches!(
+        &results[1],
+        Err(ErrorType::ErrorInStatement { line: 2, error, .. })
+            if **error == ErrorType::AmbiguousComment { divisor: "note".to_owned() }
+    ));
+    assert!(!context.is_var("x"));
+    let results = eval_script("x = 7 // (note); f(n) = x // n", &mut context);
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+    // Divisions by a name are written in brackets, so scripts can be loaded again.
+    eval_with_mutable_context("f(m, n) = m // n^2", &mut context).unwrap();
     assert_eq!(
-        eval_with_static_context("f(10)", &context).unwrap(),
-        Value::from(55)
+        context.function_declarations(),
+        vec!["f(m, n) = m // (n ^ 2)"]
     );
-    eval_with_static_context("f(20)", &context).unwrap_err();
+    assert!(Context::from_script(&context.to_script()).is_ok());
+}
+
+#[test]
+fn postfix_display() {
+    let context = Context::default();
+    for input in ["(1 + 2)!", "3!! * 2", "(-20)%", "20% + 1"] {
+        let (expression, diagnostics) = parse_with_diagnostics(input, &context);
+        assert!(diagnostics.is_empty());
+        assert_eq!(eval(&expression.to_string()).unwrap(), eval(input).unwrap());
+    }
+}
+
+#[test]
+fn chained_comparisons() {
+    let compare = |a: i64, operator: &str, b: i64| match operator {
+        "<" => a < b,
+        ">" => a > b,
+        "<=" => a <= b,
+        ">=" => a >= b,
+        "==" => a == b,
+        _ => a != b,
+    };
+    // Every pair of comparisons is chained.
+    let operators = ["<", ">", "<=", ">=", "==", "!="];
+    for first in operators {
+        for second in operators {
+            for (a, b, c) in [
+                (1, 2, 3),
+                (3, 2, 1),
+                (2, 2, 2),
+                (1, 1, 2),
+                (2, 1, 1),
+                (1, 3, 2),
+            ] {
+                let input = format!("{} {} {} {} {}", a, first, b, second, c);
+                assert_eq!(
+                    eval(&input).unwrap(),
+                    Value::from(compare(a, first, b) && compare(b, second, c)),
+                    "{}",
+                    input
+                );
+            }
+        }
+    }
+    assert_eq!(eval("0 < 5 <= 10").unwrap(), Value::from(true));
+    assert_eq!(eval("0 < 11 <= 10").unwrap(), Value::from(false));
+    assert_eq!(eval("1 < 2 < 3 < 4").unwrap(), Value::from(true));
+    assert_eq!(eval("1 < 2 < 3 + 1").unwrap(), Value::from(true));
+    assert_eq!(eval("1 < 2 == 3 < 4").unwrap(), Value::from(false));
+    assert_eq!(eval("1 < 3 == 3 < 4").unwrap(), Value::from(true));
+    assert_eq!(eval("(1 < 2) == true").unwrap(), Value::from(true));
+    assert_eq!(
+        eval("(1, 5, 3) < 4 <= 5").unwrap(),
+        Value::from(vec![true, false, true])
+    );
+    eval("1 < 2 <").unwrap_err();
+}
+
+#[test]
+fn chained_comparisons_evaluation() {
+    let context = Context::default();
+    // The middle operand appears once, so it is evaluated once.
+    let (expression, diagnostics) = parse_with_diagnostics("0 < x <= 10", &context);
+    assert!(diagnostics.is_empty());
+    match expression {
+        Expression::Comparison(first, chain) => {
+            assert_eq!(*first, Expression::Literal(Value::from(0)));
+            assert_eq!(chain.len(), 2);
+            assert_eq!(*chain[0].1, Expression::Var("x".to_owned()));
+        }
+        other => panic!("{:?} is not a chain of comparisons", other),
+    }
+    // The chain stops at the first false comparison.
+    let mut context = Context::default();
+    eval_with_mutable_context("x = 0", &mut context).unwrap();
+    assert_eq!(
+        eval_with_mutable_context("1 < x < 1/x", &mut context).unwrap(),
+        Some(Value::from(false))
+    );
+    // Bracketed comparisons, and their display, are not chained.
+    for input in [
+        "0 < 5 <= 10",
+        "(1 < 2) < 3",
+        "1 < (2 < 3)",
+        "1 < 2 < 3 == 3",
+        "(1 < 2) == true",
+        "(1 == 1) != (2 == 2)",
+    ] {
+        let (expression, diagnostics) = parse_with_diagnostics(input, &context);
+        assert!(diagnostics.is_empty());
+        assert_eq!(expression.to_string(), input);
+    }
+}
+
+// PRECEDENCE
+
+#[test]
+fn right_associative_exponentiation() {
+    assert_eq!(eval("2^3^2").unwrap(), Value::from(512));
+    assert_eq!(eval("(2^3)^2").unwrap(), Value::from(64));
+    assert_eq!(eval("2^-1").unwrap(), Value::from(0.5));
+    assert_eq!(eval("2^-1^2").unwrap(), Value::from(0.5));
+    assert_eq!(eval("2^3!").unwrap(), Value::from(64));
+}
+
+#[test]
+fn negation_precedence() {
+    assert_eq!(eval("-2^2").unwrap(), Value::from(-4));
+    assert_eq!(eval("(-2)^2").unwrap(), Value::from(4));
+    assert_eq!(eval("-2^-2").unwrap(), Value::from(-0.25));
+    assert_eq!(eval("2*-3").unwrap(), Value::from(-6));
+    assert_eq!(eval("2 - -3").unwrap(), Value::from(5));
+    assert_eq!(eval("-7 // 2").unwrap(), Value::from(-4));
+    assert_eq!(eval("-3!").unwrap(), Value::from(-6));
+    assert_eq!(eval("!!true").unwrap(), Value::from(true));
+    assert_eq!(eval("1 < -2").unwrap(), Value::from(false));
+}
+
+#[test]
+fn long_input() {
+    let input = vec!["2 * x"; 1000].join(" + ");
+    let (expression, diagnostics) = parse_with_diagnostics(&input, &Context::default());
+
+    assert!(diagnostics.is_empty());
+    assert_eq!(expression.to_string(), input);
+}
+
+#[test]
+fn operator_pairs() {
+    use crate::token::tokentype::{Associativity, TokenType, TokenType::*};
+
+    let binary = [
+        ("^", Caret),
+        ("*", Star),
+        ("/", Slash),
+        ("%", Percentage),
+        ("//", DoubleSlash),
+        ("±", PlusMinus),
+        ("+-", Uncertainty),
+        ("+", Plus),
+        ("-", Minus),
+        ("<<", DoubleLessThan),
+        (">>", DoubleGreaterThan),
+        ("&", And),
+        ("xor", Xor),
+        ("|", Or),
+        ("..", DoubleDot),
+        ("<", LessThan),
+        (">", GreaterThan),
+        ("<=", LessOrEqualTo),
+        (">=", GreaterOrEqualTo),
+        ("==", DoubleEqual),
+        ("!=", NotEqual),
+        ("&&", DoubleAnd),
+        ("||", DoubleOr),
+    ];
+    let prefix = [("-", Minus), ("!", Exclamation), ("~", Tilde)];
+    let postfix = [("!", Factorial), ("!!", DoubleFactorial), ("%", Percent)];
+
+    let context = Context::default();
+    let parse = |input: &str| {
+        let (expression, diagnostics) = parse_with_diagnostics(input, &context);
+        assert!(diagnostics.is_empty(), "{}: {:?}", input, diagnostics);
+        expression
+    };
+    let var = |name: &str| Box::new(Expression::Var(name.to_owned()));
+    let binary_node = |lhs, operator, rhs| Box::new(Expression::Binary(lhs, operator, rhs));
+    let unary_node = |operator, operand| Box::new(Expression::Unary(operator, operand));
+    let precedence = |operator: TokenType| operator.precedence().unwrap();
+
+    for &(first_symbol, first) in &binary {
+        for &(second_symbol, second) in &binary {
+            let input = format!("x {} y {} z", first_symbol, second_symbol);
+            let expected = if precedence(first) == precedence(second) {
+                // Operators with the same precedence are grouped in the same way
+                assert_eq!(first.associativity(), second.associativity(), "{}", input);
+                match first.associativity() {
+                    Associativity::Left => {
+                        binary_node(binary_node(var("x"), first, var("y")), second, var("z"))
+                    }
+                    Associativity::Right => {
+                        binary_node(var("x"), first, binary_node(var("y"), second, var("z")))
+                    }
+                    Associativity::Chain => Box::new(Expression::Comparison(
+                        var("x"),
+                        vec![(first, var("y")), (second, var("z"))],
+                    )),
+                }
+            } else if precedence(first) > precedence(second) {
+                binary_node(binary_node(var("x"), first, var("y")), second, var("z"))
+            } else {
+                binary_node(var("x"), first, binary_node(var("y"), second, var("z")))
+            };
+            assert_eq!(parse(&input), *expected, "{}", input);
+        }
+
+        for &(prefix_symbol, operator) in &prefix {
+            let input = format!("{}x {} y", prefix_symbol, first_symbol);
+            let expected = if operator.prefix_precedence().unwrap() > precedence(first) {
+                binary_node(unary_node(operator, var("x")), first, var("y"))
+            } else {
+                unary_node(operator, binary_node(var("x"), first, var("y")))
+            };
+            assert_eq!(parse(&input), *expected, "{}", input);
+
+            let input = format!("x {} {}y", first_symbol, prefix_symbol);
+            let expected = binary_node(var("x"), first, unary_node(operator, var("y")));
+            assert_eq!(parse(&input), *expected, "{}", input);
+        }
+
+        for &(postfix_symbol, operator) in &postfix {
+            // A `%` followed by an operand is a modulo
+            if operator != Percent || !prefix.iter().any(|(symbol, _)| *symbol == first_symbol) {
+                let input = format!("x{} {} y", postfix_symbol, first_symbol);
+                let expected = binary_node(unary_node(operator, var("x")), first, var("y"));
+                assert_eq!(parse(&input), *expected, "{}", input);
+            }
+
+            let input = format!("x {} y{}", first_symbol, postfix_symbol);
+            let expected = if precedence(operator) > precedence(first) {
+                binary_node(var("x"), first, unary_node(operator, var("y")))
+            } else {
+                unary_node(operator, binary_node(var("x"), first, var("y")))
+            };
+            assert_eq!(parse(&input), *expected, "{}", input);
+        }
+    }
+
+    for &(first_symbol, first) in &prefix {
+        for &(second_symbol, second) in &prefix {
+            let input = format!("{}{}x", first_symbol, second_symbol);
+            let expected = unary_node(first, unary_node(second, var("x")));
+            assert_eq!(parse(&input), *expected, "{}", input);
+        }
+        for &(postfix_symbol, operator) in &postfix {
+            let input = format!("{}x{}", first_symbol, postfix_symbol);
+            let expected = unary_node(first, unary_node(operator, var("x")));
+            assert_eq!(parse(&input), *expected, "{}", input);
+        }
+    }
+
+    for &(first_symbol, first) in &postfix {
+        for &(second_symbol, second) in &postfix {
+            let input = format!("x{} {}", first_symbol, second_symbol);
+            let expected = unary_node(second, unary_node(first, var("x")));
+            assert_eq!(parse(&input), *expected, "{}", input);
+        }
+    }
+}
+
+// INTERVALS
+
+#[test]
+fn interval() {
+    assert_eq!(eval("[1.9 .. 2.1]").unwrap(), Value::Interval(1.9, 2.1));
+    assert_eq!(eval("2 ± 0.1").unwrap(), Value::Interval(1.9, 2.1));
+    assert_eq!(eval("[1 .. 2] + 1").unwrap(), Value::Interval(2.0, 3.0));
+    assert_eq!(
+        eval("[1 .. 2] - [0 .. 1]").unwrap(),
+        Value::Interval(0.0, 2.0)
+    );
+    assert_eq!(
+        eval("[-1 .. 2] * [-3 .. 1]").unwrap(),
+        Value::Interval(-6.0, 3.0)
+    );
+    assert_eq!(eval("[-1 .. 2]^2").unwrap(), Value::Interval(0.0, 4.0));
+    assert_eq!(eval("[0 .. 4]^0.5").unwrap(), Value::Interval(0.0, 2.0));
+    assert_eq!(eval("[0 .. 4]^0").unwrap(), Value::Interval(1.0, 1.0));
+    assert_eq!(
+        eval("[0 .. 2]^-1").unwrap(),
+        Value::Interval(0.5, f64::INFINITY)
+    );
+    eval("[0 .. 4]^-0.5").unwrap_err();
 }
 
-// FUNCTIONS
+#[test]
+#[should_panic]
+fn interval_panic() {
+    eval("[2 .. 1]").unwrap();
+}
 
 #[test]
-fn min() {
+fn interval_division() {
+    assert_eq!(eval("1/[2 .. 4]").unwrap(), Value::Interval(0.25, 0.5));
     assert_eq!(
-        eval("min(4,-1.5,5.344,2.7,-6,9.2)").unwrap(),
-        Value::from(-6)
+        eval("1/[0 .. 2]").unwrap(),
+        Value::Interval(0.5, f64::INFINITY)
+    );
+    assert_eq!(
+        eval("1/[-1 .. 2]").unwrap(),
+        Value::Interval(f64::NEG_INFINITY, f64::INFINITY)
     );
+    eval("1/[0 .. 0]").unwrap_err();
 }
 
 #[test]
-fn max() {
-    assert_eq!(
-        eval("max(4,-1.5,5.344,2.7,-6,9.2)").unwrap(),
-        Value::from(9.2)
-    );
+fn interval_functions() {
+    assert_eq!(eval("sqrt([4 .. 9])").unwrap(), Value::Interval(2.0, 3.0));
+    assert_eq!(eval("exp([0 .. 1])").unwrap(), eval("[1 .. e]").unwrap());
+    assert_eq!(eval("ln([1 .. e])").unwrap(), Value::Interval(0.0, 1.0));
+    assert_eq!(eval("abs([-3 .. 2])").unwrap(), Value::Interval(0.0, 3.0));
+    eval("sqrt([-1 .. 1])").unwrap_err();
 }
 
 #[test]
-fn floor() {
-    assert_eq!(eval("floor(1.34)").unwrap(), Value::from(1));
+fn interval_trigonometry() {
+    assert_eq!(eval("sin([0 .. pi])").unwrap(), Value::Interval(0.0, 1.0));
+    assert_eq!(eval("sin([0 .. 10])").unwrap(), Value::Interval(-1.0, 1.0));
+    assert_eq!(eval("cos([0 .. pi])").unwrap(), Value::Interval(-1.0, 1.0));
+    eval("tan([1 .. 2])").unwrap_err();
+
+    let context = Context::new(
+        settings::Rounding::default(),
+        settings::AngleUnit::Degree,
+        settings::DepthLimit::default(),
+    );
+    assert_eq!(
+        eval_with_static_context("sin([30 .. 390])", &context).unwrap(),
+        Value::Interval(-1.0, 1.0)
+    );
+    assert_eq!(
+        eval_with_static_context("cos([30 .. 60])", &context).unwrap(),
+        Value::Interval(0.5, 0.8660254)
+    );
 }
 
 #[test]
-fn ceil() {
-    assert_eq!(eval("ceil(1.34)").unwrap(), Value::from(2));
+fn interval_comparison() {
+    assert_eq!(eval("[1 .. 2] < [3 .. 4]").unwrap(), Value::from(true));
+    assert_eq!(eval("[1 .. 2] > 3").unwrap(), Value::from(false));
+    assert_eq!(eval("[1 .. 2] <= 2").unwrap(), Value::from(true));
+    assert_eq!(eval("[1 .. 2] == [3 .. 4]").unwrap(), Value::from(false));
+    assert_eq!(eval("[1 .. 3] != 4").unwrap(), Value::from(true));
+    assert_eq!(eval("[2 .. 2] == 2").unwrap(), Value::from(true));
+    eval("[1 .. 3] < [2 .. 4]").unwrap_err();
+    assert!(matches!(
+        eval("[1 .. 3] == [1 .. 3]").unwrap_err(),
+        ErrorType::IndeterminateComparison { .. }
+    ));
+    eval("[1 .. 3] == 2").unwrap_err();
 }
 
+// UNCERTAINTY
+
 #[test]
-fn round() {
-    assert_eq!(eval("round(1.2)").unwrap(), Value::from(1));
-    assert_eq!(eval("round(1.5001)").unwrap(), Value::from(2));
-    assert_eq!(eval("round(1.5)").unwrap(), Value::from(2));
+fn uncertainty() {
+    assert_eq!(eval("9.81 +- 0.02").unwrap().to_string(), "9.81 ± 0.02");
+    assert_eq!(
+        eval("(1 +- 0.1) * (2 +- 0.2)").unwrap().to_string(),
+        "2 ± 0.28"
+    );
+    assert_eq!(eval("1234.5 +- 123").unwrap().to_string(), "1230 ± 120");
+    assert_eq!(eval("sqrt(4 +- 0.4)").unwrap().to_string(), "2 ± 0.1");
+    assert!(eval("1 +- (-1)").is_err());
 }
 
 #[test]
-fn abs() {
-    assert_eq!(eval("abs(1.5)").unwrap(), Value::from(1.5));
-    assert_eq!(eval("abs(-1.5)").unwrap(), Value::from(1.5));
-    assert_eq!(eval("abs(4+3i)").unwrap(), Value::from(5));
+fn uncertainty_rounding() {
+    let context = Context::new(
+        settings::Rounding::Round(1),
+        settings::AngleUnit::default(),
+        settings::DepthLimit::default(),
+    );
+    assert_eq!(
+        eval_with_static_context("9.81 +- 0.02", &context)
+            .unwrap()
+            .to_string(),
+        "9.81 ± 0.02"
+    );
+    // The deviation keeps at least one significant figure
+    assert_eq!(
+        eval_with_static_context("9.8123 +- 0.0234", &context)
+            .unwrap()
+            .to_string(),
+        "9.81 ± 0.02"
+    );
+    assert_eq!(
+        eval_with_static_context("9.8123 +- 0.234", &context)
+            .unwrap()
+            .to_string(),
+        "9.8 ± 0.2"
+    );
+    assert_eq!(
+        eval_with_static_context("1234.5 +- 123", &context)
+            .unwrap()
+            .to_string(),
+        "1230 ± 120"
+    );
+
+    let context = Context::new(
+        settings::Rounding::NoRounding,
+        settings::AngleUnit::default(),
+        settings::DepthLimit::default(),
+    );
+    assert_eq!(
+        eval_with_static_context("9.81 +- 0.02", &context)
+            .unwrap()
+            .to_string(),
+        "9.81 ± 0.02"
+    );
+    assert!(
+        eval_with_static_context("(1 +- 0.1) * (2 +- 0.2)", &context)
+            .unwrap()
+            .to_string()
+            .starts_with("2 ± 0.28284271")
+    );
 }
 
 #[test]
-fn sqrt() {
-    assert_eq!(eval("sqrt(2)").unwrap(), eval("2^(0.5)").unwrap());
-    assert_eq!(eval("sqrt(4+3i)").unwrap(), eval("(4+3i)^(0.5)").unwrap());
+fn uncertainty_needs_spaces() {
+    assert_eq!(eval("2+-1").unwrap(), Value::from(1));
+    assert_eq!(eval("2 +-1").unwrap(), Value::from(1));
+    assert_eq!(eval("2+- 1").unwrap(), Value::from(1));
+    assert_eq!(eval("2 + -1").unwrap(), Value::from(1));
+    assert_eq!(eval("2 +- 1").unwrap().to_string(), "2 ± 1");
 }
 
 #[test]
-fn ln() {
-    assert_eq!(eval("ln(e^3.5)").unwrap(), Value::from(3.5));
-    assert_eq!(eval("ln(e^(-1.5))").unwrap(), Value::from(-1.5));
+fn uncertainty_correlation() {
+    let mut context = Context::default();
+    eval_with_mutable_context("x = 9.81 +- 0.02", &mut context).unwrap();
     assert_eq!(
-        eval("ln(-1)").unwrap(),
-        Value::from(Complex64::new(0.0, 3.14159265))
+        eval_with_mutable_context("x - x", &mut context)
+            .unwrap()
+            .unwrap()
+            .to_string(),
+        "0 ± 0"
     );
     assert_eq!(
-        eval("ln(i)").unwrap(),
-        Value::from(Complex64::new(0.0, 1.57079633))
+        eval_with_mutable_context("x + x", &mut context)
+            .unwrap()
+            .unwrap()
+            .to_string(),
+        "19.62 ± 0.04"
+    );
+    // Independent measurements are added in quadrature
+    assert_eq!(
+        eval("(9.81 +- 0.02) + (9.81 +- 0.02)").unwrap().to_string(),
+        "19.62 ± 0.028"
     );
 }
 
 #[test]
-fn log() {
-    assert_eq!(eval("log(3, 9^2)").unwrap(), Value::from(4));
-}
+fn uncertainty_powers() {
+    assert_eq!(eval("(2 +- 0.1)^2").unwrap().to_string(), "4 ± 0.4");
+    assert_eq!(eval("(-2 +- 0.1)^2").unwrap().to_string(), "4 ± 0.4");
+    assert_eq!(eval("(0 +- 0.1)^2").unwrap().to_string(), "0 ± 0");
+    assert_eq!(eval("0^(2 +- 0.1)").unwrap().to_string(), "0 ± 0");
+    assert_eq!(eval("e^(1 +- 0.1)").unwrap().to_string(), "2.72 ± 0.27");
 
-#[test]
-fn exp() {
-    assert_eq!(eval("exp(ln(4))").unwrap(), Value::from(4));
+    assert!(matches!(
+        eval("(-2 +- 0.1)^0.5").unwrap_err(),
+        ErrorType::OutOfDomain { .. }
+    ));
+    assert!(matches!(
+        eval("(-2)^(2 +- 0.1)").unwrap_err(),
+        ErrorType::OutOfDomain { .. }
+    ));
+    assert!(matches!(
+        eval("0^(-1 +- 0.1)").unwrap_err(),
+        ErrorType::OutOfDomain { .. }
+    ));
 }
 
 #[test]
-fn branch() {
-    assert_eq!(eval("branch(true, 1, 2)").unwrap(), Value::from(1));
-    assert_eq!(eval("branch(false, 1, 2)").unwrap(), Value::from(2));
+fn uncertainty_comparison() {
+    assert_eq!(eval("(2 +- 1) > 1.5").unwrap(), Value::from(true));
+    assert_eq!(eval("(2 +- 1) == (2 +- 1)").unwrap(), Value::from(true));
+    assert_eq!(eval("(2 +- 1) == 2").unwrap(), Value::from(false));
 }
 
+// DIFFERENTIATION
+
 #[test]
-fn sin() {
-    let context = Context::new(
-        settings::Rounding::default(),
-        settings::AngleUnit::Degree,
-        settings::DepthLimit::default(),
+fn grad() {
+    let mut context = Context::default();
+    eval_with_mutable_context("f(x,y) = x^2*y + sin(x)", &mut context).unwrap();
+    eval_with_mutable_context("g(x) = exp(2x)", &mut context).unwrap();
+    assert_eq!(
+        eval_with_mutable_context("grad(f, (0, 2))", &mut context).unwrap(),
+        Some(Value::from(vec![1, 0]))
     );
     assert_eq!(
-        eval_with_static_context("sin(30)", &context).unwrap(),
-        Value::from(0.5)
+        eval_with_mutable_context("grad(g, 0)", &mut context).unwrap(),
+        Some(Value::from(2))
     );
     assert_eq!(
-        eval_with_static_context("sin(30)", &context).unwrap(),
-        Value::from(0.5)
+        eval_with_mutable_context("grad(cos, 0)", &mut context).unwrap(),
+        Some(Value::from(0))
     );
 }
 
 #[test]
-fn cos() {
-    let context = Context::new(
-        settings::Rounding::default(),
-        settings::AngleUnit::Degree,
-        settings::DepthLimit::default(),
-    );
+fn grad_recursion() {
+    let mut context = Context::default();
+    eval_with_mutable_context("p(n) = branch(n<=2, n^2, n*p(n-1))", &mut context).unwrap();
+    // p(n) = n(n-1)(n-2)^2 for n between 4 and 5
     assert_eq!(
-        eval_with_static_context("cos(60)", &context).unwrap(),
-        Value::from(0.5)
+        eval_with_mutable_context("grad(p, 4)", &mut context).unwrap(),
+        Some(Value::from(76))
     );
 }
 
 #[test]
-fn tan() {
-    let context = Context::new(
-        settings::Rounding::default(),
-        settings::AngleUnit::Degree,
-        settings::DepthLimit::default(),
+fn grad_comparisons() {
+    let mut context = Context::default();
+    // Comparisons only read the value of the variable.
+    eval_with_mutable_context("f(x) = branch(x == 1, 2x, 3x)", &mut context).unwrap();
+    eval_with_mutable_context("g(x) = piecewise(x < 0, -x, x != 1, x^2, 5x)", &mut context)
+        .unwrap();
+    assert_eq!(
+        eval_with_mutable_context("grad(f, 1)", &mut context).unwrap(),
+        Some(Value::from(2))
     );
     assert_eq!(
-        eval_with_static_context("tan(45)", &context).unwrap(),
-        Value::from(1)
+        eval_with_mutable_context("grad(f, 2)", &mut context).unwrap(),
+        Some(Value::from(3))
+    );
+    assert_eq!(
+        eval_with_mutable_context("grad(g, -1)", &mut context).unwrap(),
+        Some(Value::from(-1))
+    );
+    assert_eq!(
+        eval_with_mutable_context("grad(g, 1)", &mut context).unwrap(),
+        Some(Value::from(5))
+    );
+    assert_eq!(
+        eval_with_mutable_context("grad(g, 3)", &mut context).unwrap(),
+        Some(Value::from(6))
+    );
+
+    // q(n) = n(n-1)(n-2) for n between 2 and 3
+    eval_with_mutable_context("q(n) = branch(n == 0, 1, n*q(n-1))", &mut context).unwrap();
+    assert_eq!(
+        eval_with_mutable_context("grad(q, 3)", &mut context).unwrap(),
+        Some(Value::from(11))
     );
 }
 
 #[test]
-fn asin() {
-    let context = Context::new(
-        settings::Rounding::default(),
-        settings::AngleUnit::Degree,
-        settings::DepthLimit::default(),
+fn grad_min_max() {
+    let mut context = Context::default();
+    eval_with_mutable_context("f(x) = max(x, 1)", &mut context).unwrap();
+    eval_with_mutable_context("g(x) = min(x^2, 4, 3x)", &mut context).unwrap();
+    assert_eq!(
+        eval_with_mutable_context("grad(f, 2)", &mut context).unwrap(),
+        Some(Value::from(1))
     );
     assert_eq!(
-        eval_with_static_context("asin(0.5)", &context).unwrap(),
-        Value::from(30)
+        eval_with_mutable_context("grad(f, 0)", &mut context).unwrap(),
+        Some(Value::from(0))
+    );
+    assert_eq!(
+        eval_with_mutable_context("grad(g, 1)", &mut context).unwrap(),
+        Some(Value::from(2))
+    );
+    assert_eq!(
+        eval_with_mutable_context("grad(g, -1)", &mut context).unwrap(),
+        Some(Value::from(3))
+    );
+    assert_eq!(
+        eval_with_mutable_context("grad(g, 3)", &mut context).unwrap(),
+        Some(Value::from(0))
     );
 }
 
 #[test]
-fn acos() {
-    let context = Context::new(
-        settings::Rounding::default(),
-        settings::AngleUnit::Degree,
-        settings::DepthLimit::default(),
+fn grad_builtins() {
+    let mut context = Context::default();
+    eval_with_mutable_context("f(x) = log(e^2, x) + norm(x) + re(x)", &mut context).unwrap();
+    eval_with_mutable_context("g(x) = normalcdf(x, 0, 1)", &mut context).unwrap();
+    eval_with_mutable_context("h(x) = bitand(x, 1)", &mut context).unwrap();
+    assert_eq!(
+        eval_with_mutable_context("grad(f, 1)", &mut context).unwrap(),
+        Some(Value::from(2.5))
     );
     assert_eq!(
-        eval_with_static_context("acos(0.5)", &context).unwrap(),
-        Value::from(60)
+        eval_with_mutable_context("grad(g, 0)", &mut context).unwrap(),
+        eval("normalpdf(0, 0, 1)").ok()
+    );
+    assert_eq!(
+        eval_with_mutable_context("grad(h, 1)", &mut context).unwrap_err(),
+        ErrorType::NotDifferentiable {
+            value: Value::from(1.0)
+        }
     );
 }
 
 #[test]
-fn atan() {
-    let context = Context::new(
-        settings::Rounding::default(),
-        settings::AngleUnit::Degree,
-        settings::DepthLimit::default(),
+fn jacobian() {
+    let mut context = Context::default();
+    eval_with_mutable_context("h(x,y) = (x*y, x+y, x/y)", &mut context).unwrap();
+    assert_eq!(
+        eval_with_mutable_context("jacobian(h, (1, 2))", &mut context).unwrap(),
+        Some(Value::Vector(vec![
+            Value::from(vec![2.0, 1.0]),
+            Value::from(vec![1.0, 1.0]),
+            Value::from(vec![0.5, -0.25]),
+        ]))
     );
+    eval_with_mutable_context("grad(h, (1, 2))", &mut context).unwrap_err();
+    eval_with_mutable_context("grad(2, 1)", &mut context).unwrap_err();
+}
+
+// RANDOM
+
+#[test]
+fn seeded_random() {
+    let mut first = Context::default();
+    first.set_seed(settings::Seed::Fixed(7));
+    let mut second = Context::default();
+    second.set_seed(settings::Seed::Fixed(7));
+
+    for expr in [
+        "rand(0, 1, 5)",
+        "randint(1, 6)",
+        "randn(3)",
+        "normal(10, 2)",
+        "poisson(3, 4)",
+        "binomial(10, 0.5)",
+        "exponential(2)",
+        "choice((1, 2, 3), 2)",
+        "shuffle(1, 2, 3, 4)",
+    ] {
+        assert_eq!(
+            eval_with_mutable_context(expr, &mut first).unwrap(),
+            eval_with_mutable_context(expr, &mut second).unwrap()
+        );
+    }
+
+    // Setting the seed again restarts the sequence
+    first.set_seed(settings::Seed::Fixed(7));
+    second.set_seed(settings::Seed::Fixed(7));
+    let a = eval_with_mutable_context("rand(0, 1)", &mut first).unwrap();
+    eval_with_mutable_context("rand(0, 1)", &mut first).unwrap();
+    first.set_seed(settings::Seed::Fixed(7));
     assert_eq!(
-        eval_with_static_context("atan(1)", &context).unwrap(),
-        Value::from(45)
+        eval_with_mutable_context("rand(0, 1)", &mut first).unwrap(),
+        a
     );
 }
 
 #[test]
-fn sinh_asinh() {
-    assert_eq!(eval("asinh(sinh(1))").unwrap(), Value::from(1));
-    assert_eq!(eval("asinh(sinh(pi))").unwrap(), Value::from(3.14159265));
-}
-#[test]
-fn cosh_acosh() {
-    assert_eq!(eval("acosh(cosh(1))").unwrap(), Value::from(1));
-    assert_eq!(eval("acosh(cosh(pi))").unwrap(), Value::from(3.14159265));
+fn random_arguments() {
+    let mut context = Context::default();
+    assert!(eval_with_mutable_context("randn()", &mut context)
+        .unwrap()
+        .unwrap()
+        .is_float());
+    assert!(eval_with_mutable_context("randn(1, 2)", &mut context).is_err());
+
+    // The arguments of `choice` are evaluated once
+    let mut first = Context::default();
+    first.set_seed(settings::Seed::Fixed(7));
+    let mut second = Context::default();
+    second.set_seed(settings::Seed::Fixed(7));
+    eval_with_mutable_context("choice((rand(0, 1), rand(0, 1)), 0)", &mut first).unwrap();
+    eval_with_mutable_context("(rand(0, 1), rand(0, 1))", &mut second).unwrap();
+    assert_eq!(
+        eval_with_mutable_context("rand(0, 1)", &mut first).unwrap(),
+        eval_with_mutable_context("rand(0, 1)", &mut second).unwrap()
+    );
 }
+
 #[test]
-fn tanh_atanh() {
-    // Requires less precision, otherwise the test would fail
-    let context = Context::new(
-        settings::Rounding::Round(4),
-        settings::AngleUnit::default(),
-        settings::DepthLimit::default(),
+fn random_ranges() {
+    let mut context = Context::default();
+    context.set_seed(settings::Seed::Fixed(1));
+    let values = eval_with_mutable_context("randint(1, 6, 100)", &mut context)
+        .unwrap()
+        .unwrap()
+        .as_vector();
+    assert_eq!(values.len(), 100);
+    for value in values {
+        let value = value.as_int().unwrap();
+        assert!((1..=6).contains(&value));
+    }
+    let shuffled = eval_with_mutable_context("shuffle(1, 2, 3, 4)", &mut context)
+        .unwrap()
+        .unwrap()
+        .as_vector();
+    assert_eq!(shuffled.len(), 4);
+    for n in 1..=4i64 {
+        assert!(shuffled.contains(&Value::from(n)));
+    }
+
+    eval("normal(0, -1)").unwrap_err();
+    eval("rand(0, 1, -1)").unwrap_err();
+    eval("rand(1, 2, 3, 4)").unwrap_err();
+}
+
+#[test]
+fn distributions() {
+    assert_eq!(eval("normalpdf(0, 0, 1)").unwrap(), Value::from(0.39894228));
+    assert_eq!(
+        eval("normalcdf(1.96, 0, 1)").unwrap(),
+        Value::from(0.9750021)
+    );
+    assert_eq!(
+        eval("normalquantile(0.975, 0, 1)").unwrap(),
+        Value::from(1.95996398)
+    );
+    assert_eq!(eval("uniformcdf(0.25, 0, 1)").unwrap(), Value::from(0.25));
+    assert_eq!(
+        eval("exponentialquantile(0.5, 1)").unwrap(),
+        Value::from(0.69314718)
+    );
+    assert_eq!(eval("poissonpdf(2, 3)").unwrap(), Value::from(0.22404181));
+    assert_eq!(eval("poissonquantile(0.5, 3)").unwrap(), Value::from(3));
+    assert_eq!(
+        eval("binomialpdf(5, 10, 0.5)").unwrap(),
+        Value::from(0.24609375)
+    );
+    assert_eq!(
+        eval("binomialcdf(5, 10, 0.5)").unwrap(),
+        Value::from(0.62304688)
+    );
+    eval("normalquantile(2, 0, 1)").unwrap_err();
+}
+
+// VARS DECLARATIONS
+
+#[test]
+fn var() {
+    let mut context = Context::default();
+
+    eval_with_mutable_context("a = 2", &mut context).unwrap();
+
+    assert_eq!(
+        eval_with_static_context("a", &context).unwrap(),
+        Value::from(2)
+    );
+
+    eval_with_mutable_context("v = 1, 2", &mut context).unwrap();
+
+    assert_eq!(
+        eval_with_static_context("v", &context).unwrap(),
+        Value::from(vec![1, 2])
+    );
+}
+
+#[test]
+fn var_assignment() {
+    let mut context = Context::default();
+
+    eval_with_mutable_context("a = rand(0, 1)", &mut context).unwrap();
+    assert_eq!(
+        eval_with_static_context("a", &context).unwrap(),
+        eval_with_static_context("a", &context).unwrap()
+    );
+
+    eval_with_mutable_context("x = 1", &mut context).unwrap();
+    eval_with_mutable_context("x = x + 1", &mut context).unwrap();
+    assert_eq!(
+        eval_with_static_context("x", &context).unwrap(),
+        Value::from(2)
+    );
+    assert_eq!(context.get_value("x"), Some(Value::from(2)));
+}
+
+#[test]
+fn var_definition() {
+    let mut context = Context::default();
+
+    eval_with_mutable_context("b := 2a", &mut context).unwrap();
+    eval_with_mutable_context("a = 3", &mut context).unwrap();
+    assert_eq!(
+        eval_with_static_context("b", &context).unwrap(),
+        Value::from(6)
+    );
+    eval_with_mutable_context("a = 4", &mut context).unwrap();
+    assert_eq!(
+        eval_with_static_context("b", &context).unwrap(),
+        Value::from(8)
+    );
+
+    // A definition replaces the value with the same name, and vice versa.
+    eval_with_mutable_context("a := 5", &mut context).unwrap();
+    assert_eq!(context.get_value("a"), None);
+    assert!(context.get_var("a").is_some());
+
+    eval_with_mutable_context("x := x + 1", &mut context).unwrap();
+    eval_with_static_context("x", &context).unwrap_err();
+}
+
+#[test]
+fn context_copies() {
+    let mut context = Context::default();
+    eval_with_mutable_context("a = 2", &mut context).unwrap();
+
+    let mut copy = context.clone();
+    eval_with_mutable_context("a = 3", &mut copy).unwrap();
+    eval_with_mutable_context("f(x) = a*x", &mut copy).unwrap();
+
+    assert_eq!(
+        eval_with_static_context("f(2)", &copy).unwrap(),
+        Value::from(6)
+    );
+    assert_eq!(
+        eval_with_static_context("a", &context).unwrap(),
+        Value::from(2)
+    );
+    assert!(!context.is_function("f"));
+}
+
+#[test]
+#[should_panic]
+fn invalid_var_panic() {
+    let mut context = Context::default();
+
+    eval_with_mutable_context("ab = 2", &mut context).unwrap();
+}
+
+// FUNCTIONS DECLARATIONS
+
+#[test]
+fn func() {
+    let mut context = Context::default();
+
+    eval_with_mutable_context("f(x) = xcos(x)", &mut context).unwrap();
+
+    assert_eq!(
+        eval_with_static_context("f(pi)", &context).unwrap(),
+        Value::from(-3.14159265)
+    );
+}
+
+#[test]
+#[should_panic]
+fn unknown_func_panic() {
+    let mut context = Context::default();
+
+    eval_with_mutable_context("f(x) = g(x)", &mut context).unwrap();
+
+    eval_with_static_context("f(1)", &context).unwrap();
+}
+
+#[test]
+#[should_panic]
+fn invalid_func_panic() {
+    let mut context = Context::default();
+
+    eval_with_mutable_context("f(x) g(x) = 2", &mut context).unwrap();
+}
+
+#[test]
+fn recursion() {
+    let mut context = Context::default();
+
+    eval_with_mutable_context("f(x) = branch(x<=2, 1, f(x-1)+f(x-2))", &mut context).unwrap();
+
+    assert_eq!(
+        eval_with_static_context("f(10)", &context).unwrap(),
+        Value::from(55)
+    );
+    eval_with_static_context("f(20)", &context).unwrap_err();
+}
+
+// SCOPES
+
+#[test]
+fn parameters_shadow_globals() {
+    let mut context = Context::default();
+
+    eval_with_mutable_context("x = 10", &mut context).unwrap();
+    eval_with_mutable_context("f(x) = 2x", &mut context).unwrap();
+
+    assert_eq!(
+        eval_with_static_context("f(1) + x", &context).unwrap(),
+        Value::from(12)
+    );
+}
+
+#[test]
+fn caller_parameters_are_not_visible() {
+    let mut context = Context::default();
+
+    eval_with_mutable_context("g(y) = x + y", &mut context).unwrap();
+    eval_with_mutable_context("f(x) = g(1)", &mut context).unwrap();
+
+    eval_with_static_context("f(2)", &context).unwrap_err();
+}
+
+#[test]
+fn globals_are_evaluated_in_global_scope() {
+    let mut context = Context::default();
+
+    eval_with_mutable_context("b := 2x", &mut context).unwrap();
+    eval_with_mutable_context("f(x) = b", &mut context).unwrap();
+
+    eval_with_static_context("f(3)", &context).unwrap_err();
+
+    eval_with_mutable_context("x = 5", &mut context).unwrap();
+    assert_eq!(
+        eval_with_static_context("f(3)", &context).unwrap(),
+        Value::from(10)
+    );
+}
+
+#[test]
+fn values_are_captured_at_declaration() {
+    let mut context = Context::default();
+
+    eval_with_mutable_context("a = 2", &mut context).unwrap();
+    eval_with_mutable_context("f(x) = a*x", &mut context).unwrap();
+    eval_with_mutable_context("g(x) = ax", &mut context).unwrap();
+    eval_with_mutable_context("a = 3", &mut context).unwrap();
+
+    assert_eq!(
+        eval_with_static_context("f(2) + g(2)", &context).unwrap(),
+        Value::from(8)
+    );
+    // The captured values are kept when the context is written as a script
+    let loaded = Context::from_script(&context.to_script()).unwrap();
+    assert_eq!(
+        eval_with_static_context("f(2) + g(2) + a", &loaded).unwrap(),
+        Value::from(11)
+    );
+}
+
+#[test]
+fn variables_are_read_at_call_time() {
+    let mut context = Context::default();
+
+    eval_with_mutable_context("c := 2", &mut context).unwrap();
+    eval_with_mutable_context("f(x) = c*x", &mut context).unwrap();
+    eval_with_mutable_context("c := 3", &mut context).unwrap();
+
+    assert_eq!(
+        eval_with_static_context("f(2)", &context).unwrap(),
+        Value::from(6)
+    );
+}
+
+#[test]
+fn nested_calls() {
+    let mut context = Context::default();
+
+    eval_with_mutable_context("g(x) = x^2", &mut context).unwrap();
+    eval_with_mutable_context("f(x,y) = g(x+1)*x + max(x,y) + xy", &mut context).unwrap();
+
+    assert_eq!(
+        eval_with_static_context("f(2, 3)", &context).unwrap(),
+        Value::from(27)
+    );
+}
+
+#[test]
+fn conditional_recursion() {
+    let mut context = Context::default();
+
+    eval_with_mutable_context("f(n) = n <= 1 ? 1 : n*f(n-1)", &mut context).unwrap();
+
+    assert_eq!(
+        eval_with_static_context("f(5)", &context).unwrap(),
+        Value::from(120)
+    );
+}
+
+// SCRIPTS
+
+#[test]
+fn script() {
+    let mut context = Context::default();
+
+    let results = eval_script(
+        "a = 2; b = a + 1 # b is 3\n\n// A function\nf(x) = a*x\nf(b); a + b",
+        &mut context,
+    );
+
+    assert_eq!(results.len(), 5);
+    assert_eq!(results[2].as_ref().unwrap(), &None);
+    assert_eq!(results[3].as_ref().unwrap(), &Some(Value::from(6)));
+    assert_eq!(results[4].as_ref().unwrap(), &Some(Value::from(5)));
+    assert_eq!(
+        eval_with_static_context("f(1)", &context).unwrap(),
+        Value::from(2)
+    );
+}
+
+#[test]
+fn script_errors() {
+    let mut context = Context::default();
+
+    let results = eval_script("a = 1\na + c; a + 1", &mut context);
+
+    assert_eq!(results.len(), 2);
+    match &results[1] {
+        Err(ErrorType::ErrorInStatement {
+            statement, line, ..
+        }) => assert_eq!((*statement, *line), (2, 2)),
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    context.on_error = settings::OnError::Continue;
+    let results = eval_script("a + c; a + 1", &mut context);
+
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_err());
+    assert_eq!(results[1].as_ref().unwrap(), &Some(Value::from(2)));
+}
+
+#[test]
+fn context_scripts() {
+    let mut context = Context::new(
+        settings::Rounding::NoRounding,
+        settings::AngleUnit::Degree,
+        settings::DepthLimit::Limit(50),
+    );
+    context.on_error = settings::OnError::Continue;
+    eval_script(
+        "a = -1.5; v = [1, 2+3i]; m = 2 +- 0.5; b := 2a\nf(x, y) = x^(y-1); g(x) = -f(x, 2)",
+        &mut context,
+    );
+
+    let script = context.to_script();
+    assert_eq!(
+        script,
+        "#! rounding: none\n#! angle_unit: degree\n#! depth_limit: 50\n#! seed: entropy\n\
+        #! on_error: continue\n#! locale: english\n#! undo_limit: 100\n#! syntax: implicit\n\n\
+        a = -1.5\nb := 2 * a\nm = (2 +- 0.5)\nv = [1, (2+3i)]\n\
+        f(x, y) = x ^ (y - 1)\ng(x) = -f(x, 2)\n"
+    );
+
+    let loaded = Context::from_script(&script).unwrap();
+    assert_eq!(loaded.to_script(), script);
+    assert_eq!(
+        eval_with_static_context("g(3) + b + sin(90)", &loaded).unwrap(),
+        Value::from(-5)
+    );
+
+    assert!(matches!(
+        Context::from_script("#! rounding: 4\n#! angle: degree"),
+        Err(ErrorType::InvalidDirective { line: 2, .. })
+    ));
+    Context::from_script("a = 1\nb = c").unwrap_err();
+}
+
+// COMMANDS
+
+#[test]
+fn listing() {
+    let mut context = Context::default();
+
+    eval_with_mutable_context("b := 2a", &mut context).unwrap();
+    eval_with_mutable_context("a = -1.5", &mut context).unwrap();
+    eval_with_mutable_context("f(x, y) = x^(y - 1)", &mut context).unwrap();
+
+    assert_eq!(
+        context.variable_declarations(),
+        vec!["a = -1.5", "b := 2 * a"]
+    );
+    assert_eq!(
+        context.function_declarations(),
+        vec!["f(x, y) = x ^ (y - 1)"]
+    );
+    assert_eq!(
+        list_declarations("vars()", &context).unwrap(),
+        Some(context.variable_declarations())
+    );
+    assert_eq!(
+        list_declarations("funcs()", &context).unwrap(),
+        Some(context.function_declarations())
+    );
+    assert_eq!(list_declarations("f(a, 2)", &context).unwrap(), None);
+
+    // Listings have no value, and do not change the history.
+    assert_eq!(
+        eval_with_mutable_context("vars()", &mut context).unwrap(),
+        None
+    );
+    context.undo(1);
+    assert!(!context.is_function("f"));
+    context.redo(1);
+
+    let mut functions = context.iter_functions();
+    let (identifier, params, _) = functions.next().unwrap();
+    assert_eq!(
+        (identifier, params),
+        ("f", &["x".to_owned(), "y".to_owned()][..])
+    );
+    assert!(functions.next().is_none());
+}
+
+#[test]
+fn removal() {
+    let mut context = Context::default();
+
+    eval_with_mutable_context("a = 1", &mut context).unwrap();
+    eval_with_mutable_context("b := 2", &mut context).unwrap();
+    eval_with_mutable_context("f(x) = x", &mut context).unwrap();
+
+    eval_with_mutable_context("clear(a, f)", &mut context).unwrap();
+    assert!(!context.is_var("a"));
+    assert!(!context.is_function("f"));
+    assert!(context.is_var("b"));
+
+    // Nothing is removed if an identifier is unknown
+    eval_with_mutable_context("clear(b, a)", &mut context).unwrap_err();
+    assert!(context.is_var("b"));
+
+    eval_with_mutable_context("clear()", &mut context).unwrap();
+    assert_eq!(context.iter_variables().count(), 0);
+
+    context.add_value("c".to_owned(), Value::from(3));
+    assert!(context.remove_variable("c"));
+    assert!(!context.remove_variable("c"));
+}
+
+#[test]
+fn command_panic() {
+    let mut context = Context::default();
+
+    eval_with_mutable_context("1 + vars()", &mut context).unwrap_err();
+    eval_with_mutable_context("vars(1)", &mut context).unwrap_err();
+    list_declarations("funcs(f)", &context).unwrap_err();
+    eval_with_mutable_context("clear(1)", &mut context).unwrap_err();
+    eval_with_mutable_context("clear = 1", &mut context).unwrap_err();
+    eval_with_static_context("clear()", &context).unwrap_err();
+}
+
+// HISTORY
+
+#[test]
+fn snapshots() {
+    let mut context = Context::default();
+    eval_with_mutable_context("a = 1", &mut context).unwrap();
+    let snapshot = context.snapshot();
+
+    eval_with_mutable_context("a = 2", &mut context).unwrap();
+    eval_with_mutable_context("f(x) = ax", &mut context).unwrap();
+    context.restore(snapshot);
+
+    assert_eq!(context.get_value("a"), Some(Value::from(1)));
+    assert!(!context.is_function("f"));
+}
+
+#[test]
+fn transactions() {
+    let mut context = Context::default();
+    eval_with_mutable_context("a = 1", &mut context).unwrap();
+
+    context
+        .transaction(|context| {
+            eval_with_mutable_context("a = 2", context)?;
+            eval_with_mutable_context("b = a + c", context)
+        })
+        .unwrap_err();
+    assert_eq!(context.get_value("a"), Some(Value::from(1)));
+    assert!(!context.is_var("b"));
+    // The reverted changes cannot be undone.
+    assert_eq!(context.undo(5), 1);
+
+    context.on_error = settings::OnError::Rollback;
+    let results = eval_script("a = 3; f(x) = x; b = c; a = 4", &mut context);
+    assert_eq!(results.len(), 3);
+    assert!(!context.is_var("a"));
+    assert!(!context.is_function("f"));
+
+    let results = eval_script("a = 3; f(x) = x", &mut context);
+    assert!(results.iter().all(Result::is_ok));
+    assert_eq!(context.get_value("a"), Some(Value::from(3)));
+}
+
+#[test]
+fn undo_redo() {
+    let mut context = Context::default();
+    eval_with_mutable_context("a = 1", &mut context).unwrap();
+    eval_with_mutable_context("a = 2", &mut context).unwrap();
+    eval_with_mutable_context("a + 1", &mut context).unwrap();
+    eval_with_mutable_context("f(x) = ax", &mut context).unwrap();
+    eval_with_mutable_context("b = 1/c", &mut context).unwrap_err();
+    eval_with_mutable_context("clear(a)", &mut context).unwrap();
+
+    assert_eq!(context.undo(2), 2);
+    assert_eq!(context.get_value("a"), Some(Value::from(2)));
+    assert!(!context.is_function("f"));
+
+    assert_eq!(context.redo(1), 1);
+    assert!(context.is_function("f"));
+
+    // A new change discards the undone ones.
+    eval_with_mutable_context("b = 3", &mut context).unwrap();
+    assert_eq!(context.redo(1), 0);
+
+    assert_eq!(context.undo(10), 4);
+    assert!(!context.is_var("a"));
+
+    context.undo_limit = settings::UndoLimit::Limit(2);
+    for value in 0..5 {
+        eval_with_mutable_context(&format!("a = {}", value), &mut context).unwrap();
+    }
+    assert_eq!(context.undo(10), 2);
+    assert_eq!(context.get_value("a"), Some(Value::from(2)));
+}
+
+#[test]
+fn history_in_function_calls() {
+    let mut context = Context::default();
+    eval_with_mutable_context("f(n) = n <= 1 ? 1 : n*f(n-1)", &mut context).unwrap();
+    for value in 0..100 {
+        eval_with_mutable_context(&format!("a = {}", value), &mut context).unwrap();
+    }
+
+    // Evaluating function calls does not change the history
+    let copy = context.clone();
+    assert_eq!(
+        eval_with_mutable_context("f(10)", &mut context).unwrap(),
+        Some(Value::from(3628800))
+    );
+    assert_eq!(context.undo(1000), 100);
+    assert!(!context.is_var("a"));
+
+    // Copies keep their own history
+    let mut copy = copy;
+    assert_eq!(copy.undo(1), 1);
+    assert_eq!(copy.get_value("a"), Some(Value::from(98)));
+}
+
+// ERRORS
+
+#[test]
+fn error_codes() {
+    let errors = [
+        (eval("2 +"), "E0010", ErrorCategory::Syntax),
+        (eval("f(1)"), "E0017", ErrorCategory::Syntax),
+        (eval("[2 .. 1]"), "E0027", ErrorCategory::Math),
+        (eval("[1, 2] + [1, 2, 3]"), "E0005", ErrorCategory::Type),
+    ];
+    for (result, code, category) in errors {
+        let error = result.unwrap_err();
+        assert_eq!((error.code(), error.category()), (code, category));
+    }
+
+    let mut context = Context::default();
+    context.depth_limit = settings::DepthLimit::Limit(10);
+    eval_with_mutable_context("f(x) = f(x)", &mut context).unwrap();
+    let error = eval_with_static_context("f(1)", &context).unwrap_err();
+    assert_eq!(error.category(), ErrorCategory::Limit);
+
+    assert_eq!(error.clone(), error);
+    assert_ne!(error, ErrorType::EmptyBrackets);
+}
+
+#[test]
+fn error_causes() {
+    let mut context = Context::default();
+    let error = eval_script("a = 1\nb = c", &mut context)
+        .pop()
+        .unwrap()
+        .unwrap_err();
+    let inner = ErrorType::UnknownVar {
+        var_name: "c".to_owned(),
+        suggestions: vec![],
+    };
+
+    assert_eq!(error.code(), "E0031");
+    assert_eq!(error.category(), ErrorCategory::Syntax);
+    assert_eq!(error.causes().collect::<Vec<_>>(), vec![&inner]);
+    assert_eq!(error.root_cause(), &inner);
+    assert_eq!(inner.causes().count(), 0);
+    assert_eq!(inner.root_cause(), &inner);
+
+    let wrapped = ErrorType::ErrorDuring {
+        operation_name: "test".to_owned(),
+        error: Box::new(error.clone()),
+    };
+    assert_eq!(wrapped.causes().count(), 2);
+    assert_eq!(
+        std::error::Error::source(&wrapped).map(|source| source.to_string()),
+        Some(error.to_string())
+    );
+}
+
+#[test]
+fn suggestions() {
+    let mut context = Context::default();
+    eval_script("v = 1; g(x) = x", &mut context);
+
+    let suggestions = |input: &str| {
+        eval_with_static_context(input, &context)
+            .unwrap_err()
+            .suggestions()
+            .to_vec()
+    };
+    assert_eq!(suggestions("sqr(2)"), vec!["sqrt"]);
+    assert_eq!(suggestions("cso(2)"), vec!["cos"]);
+    assert_eq!(suggestions("Sin(1)")[0], "sin");
+    assert_eq!(suggestions("normalcd(1, 0, 1)")[0], "normalcdf");
+    assert_eq!(suggestions("tua"), vec!["tau"]);
+    // Single letters are too short to be misspelled.
+    assert_eq!(suggestions("h(1)"), Vec::<String>::new());
+    assert_eq!(suggestions("1 + y"), Vec::<String>::new());
+
+    let error = eval_with_static_context("cso(2)", &context).unwrap_err();
+    assert_eq!(
+        error.message(&locale::Italian),
+        "ERRORE DI SINTASSI: funzione sconosciuta `cso`. Forse intendevi `cos`?"
+    );
+}
+
+#[test]
+fn error_messages() {
+    let error = ErrorType::WrongFunctionArgumentsAmount {
+        func_name: "f".to_owned(),
+        expected: 2,
+        given: 1,
+    };
+    assert_eq!(
+        error.to_string(),
+        "SYNTAX ERROR: function `f` expected 2 arguments, but got 1."
+    );
+    assert_eq!(
+        error.message(&locale::Italian),
+        "ERRORE DI SINTASSI: la funzione `f` si aspettava 2 argomenti, ma ne ha ricevuti 1."
+    );
+
+    let mut context = Context::default();
+    context.locale = settings::Locale::Italian;
+    let error = eval_script("a = 1\nb = 1 / c", &mut context)
+        .pop()
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(
+        context.error_message(&error),
+        "Si è verificato un errore nell'istruzione 2 (riga 2): \n \
+        ERRORE DI SINTASSI: variabile sconosciuta `c`."
+    );
+
+    // Missing templates fall back to English, unknown fields are left as they are.
+    let mut catalog = std::collections::HashMap::new();
+    catalog.insert(
+        "E0018".to_owned(),
+        "FEHLER: unbekannte Variable `{var_name}` {other}".to_owned(),
+    );
+    assert_eq!(
+        error.message(&catalog),
+        "An error occurred in statement 2 (line 2): \n FEHLER: unbekannte Variable `c` {other}"
+    );
+}
+
+#[test]
+fn diagnostics() {
+    let context = Context::default();
+    let diagnose = |input: &str| {
+        let (expression, diagnostics) = parse_with_diagnostics(input, &context);
+        let found = diagnostics
+            .into_iter()
+            .map(|diagnostic| {
+                (
+                    diagnostic.error.code(),
+                    diagnostic.position,
+                    diagnostic.length,
+                )
+            })
+            .collect::<Vec<_>>();
+        (expression.to_string(), found)
+    };
+
+    assert_eq!(diagnose("2 + sin(x)"), ("2 + sin(x)".to_owned(), vec![]));
+    assert_eq!(diagnose("g(x) = 2x").1, vec![]);
+    // Every problem is found, in order.
+    assert_eq!(
+        diagnose("sin + 1.2.3 ] $"),
+        (
+            "sin + 1.23".to_owned(),
+            vec![
+                ("E0014", 0, 3),
+                ("E0003", 9, 1),
+                ("E0008", 12, 1),
+                ("E0002", 14, 1)
+            ]
+        )
+    );
+    assert_eq!(
+        diagnose("((1, ,2"),
+        (
+            "(1, _, 2)".to_owned(),
+            vec![("E0009", 0, 1), ("E0009", 1, 1), ("E0024", 5, 1)]
+        )
+    );
+    assert_eq!(
+        diagnose("max(1, 2 +) * (3 -"),
+        (
+            "max(1, 2 + _) * (3 - _)".to_owned(),
+            vec![("E0010", 9, 1), ("E0009", 14, 1), ("E0010", 17, 1)]
+        )
+    );
+    assert_eq!(diagnose("[1, 2)").1, vec![("E0008", 5, 1)]);
+    // Declarations are checked once the input is valid.
+    assert_eq!(diagnose("f(2) = x").1, vec![("E0016", 0, 8)]);
+
+    // The errors are the same ones returned by evaluation.
+    let (_, diagnostics) = parse_with_diagnostics("1 + 2 $", &context);
+    assert_eq!(diagnostics[0].error, eval("1 + 2 $").unwrap_err());
+    assert!(Expression::Invalid.eval(&context, 0).is_err());
+}
+
+#[test]
+fn warnings() {
+    let mut context = Context::default();
+    eval_script("x = 2; y = 3", &mut context);
+    let warn = |input: &str, context: &mut Context| {
+        let (_, warnings) = eval_with_warnings(input, context).unwrap();
+        warnings
+            .into_iter()
+            .map(|warning| (warning.code(), warning.position, warning.length))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(warn("2 * sin(x) + pi", &mut context), vec![]);
+    assert_eq!(warn("2 + 3i", &mut context), vec![]);
+    // `sin(x) * y`
+    assert_eq!(
+        warn("sinxy", &mut context),
+        vec![("W0001", 0, 5), ("W0003", 0, 3), ("W0002", 4, 0)]
+    );
+    assert_eq!(
+        warn("2(x)pi", &mut context),
+        vec![("W0002", 1, 0), ("W0002", 4, 0)]
+    );
+    assert_eq!(warn("f(t) = 2t", &mut context), vec![("W0002", 8, 0)]);
+    // Names separated by a space are not split, the brackets are added after `sin`
+    assert_eq!(warn("2 + sin  x", &mut context), vec![("W0003", 4, 3)]);
+
+    let (_, warnings) = eval_with_warnings("pix", &mut context).unwrap();
+    assert_eq!(
+        warnings[0].kind,
+        WarningKind::SplitIdentifier {
+            identifier: "pix".to_owned(),
+            parts: vec!["pi".to_owned(), "x".to_owned()],
+        }
+    );
+    assert_eq!(
+        warnings[0].to_string(),
+        "WARNING: identifier `pix` is read as `pi`, `x`."
+    );
+    assert_eq!(
+        warnings[0].message(&locale::Italian),
+        "l'identificatore `pix` è letto come `pi`, `x`."
+    );
+
+    // With the strict syntax warnings are errors.
+    context.syntax = settings::Syntax::Strict;
+    let error = eval_with_static_context("sin x", &context).unwrap_err();
+    assert_eq!(error.code(), "E0033");
+    assert_eq!(
+        error.to_string(),
+        "SYNTAX ERROR: ambiguous input, implicit brackets around the argument of `sin`."
+    );
+    match error {
+        ErrorType::AmbiguousSyntax { warning } => {
+            assert_eq!((warning.position, warning.length), (0, 3));
+        }
+        other => panic!("{:?} is not an ambiguous input", other),
+    }
+    assert!(eval_with_static_context("2pi", &context).is_err());
+    assert_eq!(
+        eval_with_static_context("2 * sin(x) + 3i", &context).unwrap(),
+        eval("2 * sin(2) + 3i").unwrap()
+    );
+    // Identifiers are not split when evaluated either.
+    eval_with_mutable_context("g(t) = pit", &mut context).unwrap();
+    assert_eq!(
+        eval_with_static_context("g(1)", &context)
+            .unwrap_err()
+            .code(),
+        "E0018"
+    );
+    let (_, diagnostics) = parse_with_diagnostics("2x + sin y", &context);
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!((diagnostics[1].position, diagnostics[1].length), (5, 3));
+}
+
+#[test]
+fn explicit_syntax() {
+    let mut context = Context::default();
+    context.syntax = settings::Syntax::Explicit;
+    eval_script(
+        "k_ab = 2; rate2 = 3; xy := k_ab * rate2; f_1(x_0, n) = x_0^n",
+        &mut context,
+    );
+
+    assert_eq!(
+        eval_with_static_context("xy + f_1(k_ab, rate2)", &context).unwrap(),
+        Value::from(14)
+    );
+    assert_eq!(
+        eval_with_static_context("2 * sin(pi / 2) + 3i", &context).unwrap(),
+        eval("2 + 3i").unwrap()
+    );
+    // Nothing is read implicitly.
+    for input in ["2k_ab", "sin k_ab", "k_ab rate2", "(1)(2)"] {
+        let error = eval_with_static_context(input, &context).unwrap_err();
+        assert_eq!(error.code(), "E0033");
+    }
+    let error = eval_with_static_context("pixy", &context).unwrap_err();
+    assert_eq!(error.code(), "E0018");
+    let error = eval_with_static_context("rate3", &context).unwrap_err();
+    assert_eq!(error.suggestions(), ["rate2"]);
+
+    // The same names are split with the implicit syntax.
+    let mut implicit = context.clone();
+    implicit.syntax = settings::Syntax::Implicit;
+    assert!(eval_with_static_context("k_ab", &implicit).is_err());
+    assert!(eval_with_mutable_context("ab = 2", &mut implicit).is_err());
+
+    // New names longer than one letter need the explicit syntax.
+    for (input, identifier) in [
+        ("foo(x) = 2x", "foo"),
+        ("rate(x) = x", "rate"),
+        ("xz := 3", "xz"),
+    ] {
+        let error = eval_with_mutable_context(input, &mut implicit).unwrap_err();
+        assert_eq!(
+            error,
+            ErrorType::ExplicitSyntaxRequired {
+                identifier: identifier.to_owned()
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "SYNTAX ERROR: `{}` is longer than one letter, so it can only be declared with the explicit syntax.",
+                identifier
+            )
+        );
+    }
+    assert!(matches!(
+        eval_with_static_context("foo(3)", &implicit).unwrap_err(),
+        ErrorType::UnknownFunction { .. }
+    ));
+
+    let loaded = Context::from_script(&context.to_script()).unwrap();
+    assert_eq!(loaded.syntax, settings::Syntax::Explicit);
+    assert_eq!(
+        eval_with_static_context("f_1(xy, 2)", &loaded).unwrap(),
+        Value::from(36)
+    );
+}
+
+#[test]
+fn non_ascii_identifiers() {
+    let mut context = Context::default();
+    eval_script("θ = 2; α(x) = 3x", &mut context);
+    assert_eq!(
+        eval_with_static_context("2θ + α(θ)", &context).unwrap(),
+        Value::from(10)
+    );
+    assert_eq!(
+        eval_with_static_context("θα(1)", &context).unwrap(),
+        Value::from(6)
+    );
+    let error = eval_with_static_context("µé", &context).unwrap_err();
+    assert_eq!(error.code(), "E0018");
+
+    context.syntax = settings::Syntax::Explicit;
+    eval_script("θ_é = 3; µ2 = 4", &mut context);
+    assert_eq!(
+        eval_with_static_context("θ_é * µ2 + α(θ)", &context).unwrap(),
+        Value::from(18)
+    );
+    let error = eval_with_static_context("θé", &context).unwrap_err();
+    assert_eq!(error.code(), "E0018");
+}
+
+// SERDE
+
+#[cfg(feature = "serde")]
+#[test]
+fn values_serde() {
+    let mut context = Context::default();
+    let values = eval_script(
+        "2; 1.5; 3-2i; true; [1 .. 2]; 9.81 +- 0.02; (1, [0 .. 1]); a = 1",
+        &mut context,
+    );
+
+    for value in values {
+        let value = value.unwrap().unwrap_or(Value::Bool(false));
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&json).unwrap(), value);
+
+        let value_type = value.to_type();
+        let json = serde_json::to_string(&value_type).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ValueType>(&json).unwrap(),
+            value_type
+        );
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn errors_serde() {
+    let error = ErrorType::DivideByZero {
+        numerator: Value::from(2),
+    };
+    let json = serde_json::to_value(&error).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "code": "E0006",
+            "message": "MATH ERROR: trying to divide 2 by zero.",
+            "data": { "numerator": { "Int": 2 } },
+        })
+    );
+    // Variants with no fields have no data
+    let error = ErrorType::EmptyBrackets;
+    assert_eq!(
+        serde_json::to_value(&error).unwrap(),
+        serde_json::json!({ "code": error.code(), "message": error.to_string() })
+    );
+
+    let mut context = Context::default();
+    let errors = vec![
+        ErrorType::EmptyBrackets,
+        eval_script("a = 1\nb = c", &mut context)
+            .pop()
+            .unwrap()
+            .unwrap_err(),
+        eval_with_static_context("f(x) = x", &context).unwrap_err(),
+        eval("[1, 2] + [1, 2, 3]").unwrap_err(),
+    ];
+    for error in errors {
+        let json = serde_json::to_string(&error).unwrap();
+        assert_eq!(serde_json::from_str::<ErrorType>(&json).unwrap(), error);
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn error_codes_serde() {
+    use crate::{objects::Request, token::tokentype::TokenType};
+
+    let value = || Value::from(1);
+    let name = || "f".to_owned();
+    let errors = vec![
+        ErrorType::TypeError {
+            expected: ValueType::IntType,
+            given: ValueType::BoolType,
+        },
+        ErrorType::UnknownToken { token: name() },
+        ErrorType::InvalidTokenPosition {
+            token: TokenType::Plus,
+        },
+        ErrorType::FailedCast {
+            value: value(),
+            from: ValueType::IntType,
+            to: ValueType::BoolType,
+        },
+        ErrorType::MismatchedArrayLengths {
+            first: 1,
+            second: 2,
+            operation_name: name(),
+        },
+        ErrorType::DivideByZero { numerator: value() },
+        ErrorType::NotAnOperator {
+            token: TokenType::Comma,
+        },
+        ErrorType::InvalidClosingBracket,
+        ErrorType::MissingClosingBracket,
+        ErrorType::MissingOperatorArgument {
+            token: TokenType::Plus,
+        },
+        ErrorType::FailedParse { value: name() },
+        ErrorType::EmptyBrackets,
+        ErrorType::WrongFunctionArgumentsAmount {
+            func_name: name(),
+            expected: 1,
+            given: 2,
+        },
+        ErrorType::MissingFunctionParameters { func_name: name() },
+        ErrorType::ExpectedFunctionArgument { func_name: name() },
+        ErrorType::InvalidDeclaration,
+        ErrorType::UnknownFunction {
+            func_name: name(),
+            suggestions: vec![],
+        },
+        ErrorType::UnknownVar {
+            var_name: name(),
+            suggestions: vec![],
+        },
+        ErrorType::UnknownDeclaration { identifier: name() },
+        ErrorType::InvalidDirective {
+            directive: name(),
+            line: 1,
+        },
+        ErrorType::InvalidCommand { command: name() },
+        ErrorType::ReservedVarName { var_name: name() },
+        ErrorType::ReservedFunctionName { func_name: name() },
+        ErrorType::EmptyUnion,
+        ErrorType::InvalidMutableContext {
+            request: Request::Removal(vec![]),
+        },
+        ErrorType::RecursionDepthLimitReached { limit: 1 },
+        ErrorType::InvalidInterval {
+            lower: value(),
+            upper: value(),
+        },
+        ErrorType::OutOfDomain {
+            func_name: name(),
+            value: value(),
+        },
+        ErrorType::IndeterminateComparison {
+            lhs: value(),
+            rhs: value(),
+        },
+        ErrorType::AmbiguousSyntax {
+            warning: Warning {
+                kind: WarningKind::ImplicitMultiplication,
+                position: 0,
+                length: 0,
+            },
+        },
+        ErrorType::NotDifferentiable { value: value() },
+        ErrorType::AmbiguousComment { divisor: name() },
+        ErrorType::ExplicitSyntaxRequired { identifier: name() },
+        ErrorType::ErrorDuring {
+            operation_name: name(),
+            error: Box::new(ErrorType::EmptyUnion),
+        },
+        ErrorType::ErrorInStatement {
+            statement: 1,
+            line: 1,
+            error: Box::new(ErrorType::EmptyUnion),
+        },
+        ErrorType::InternalError { message: name() },
+    ];
+
+    // Every variant has its own code, which is the tag it is serialized with
+    let mut codes: Vec<&str> = errors.iter().map(ErrorType::code).collect();
+    codes.sort();
+    codes.dedup();
+    assert_eq!(codes.len(), 36);
+    for error in errors {
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["code"], error.code());
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn context_serde() {
+    let mut context = Context::new(
+        settings::Rounding::Round(2),
+        settings::AngleUnit::Turn,
+        settings::DepthLimit::NoLimit,
+    );
+    eval_script("a = 0.25; b := 2a; f(x) = sin(x) + b", &mut context);
+
+    let json = serde_json::to_value(&context).unwrap();
+    assert_eq!(json["version"], 1);
+
+    let loaded: Context = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(
+        eval_with_static_context("f(a)", &loaded).unwrap(),
+        Value::from(1.5)
+    );
+    assert_eq!(loaded.to_script(), context.to_script());
+
+    // Measurements made after loading are independent of the stored ones
+    let mut context = Context::default();
+    eval_script("x = 1 +- 0.3", &mut context);
+    let mut loaded: Context =
+        serde_json::from_value(serde_json::to_value(&context).unwrap()).unwrap();
+    eval_script("y = 1 +- 0.4", &mut loaded);
+    assert_eq!(
+        eval_with_static_context("x + y", &loaded)
+            .unwrap()
+            .to_string(),
+        "2 ± 0.5"
+    );
+
+    let mut future = json;
+    future["version"] = serde_json::json!(2);
+    serde_json::from_value::<Context>(future).unwrap_err();
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn functions_serde() {
+    let function = function::builtin::get_built_in_function("sin").unwrap();
+    let json = serde_json::to_string(&function).unwrap();
+    assert_eq!(json, "\"sin\"");
+
+    let loaded: function::Function = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.func_identifier, "sin");
+    assert!(serde_json::from_str::<function::Function>("\"unknown\"").is_err());
+}
+
+// FUNCTIONS
+
+#[test]
+fn min() {
+    assert_eq!(
+        eval("min(4,-1.5,5.344,2.7,-6,9.2)").unwrap(),
+        Value::from(-6)
+    );
+}
+
+#[test]
+fn max() {
+    assert_eq!(
+        eval("max(4,-1.5,5.344,2.7,-6,9.2)").unwrap(),
+        Value::from(9.2)
+    );
+}
+
+#[test]
+fn dynamic_arguments() {
+    // Functions with a dynamic amount of arguments take at least one.
+    assert_eq!(eval("max(5)").unwrap(), Value::from(5));
+    assert_eq!(eval("min(-2)").unwrap(), Value::from(-2));
+    assert_eq!(
+        eval("max()").unwrap_err(),
+        ErrorType::MissingFunctionParameters {
+            func_name: "max".to_owned()
+        }
+    );
+}
+
+#[test]
+fn floor() {
+    assert_eq!(eval("floor(1.34)").unwrap(), Value::from(1));
+}
+
+#[test]
+fn ceil() {
+    assert_eq!(eval("ceil(1.34)").unwrap(), Value::from(2));
+}
+
+#[test]
+fn round() {
+    assert_eq!(eval("round(1.2)").unwrap(), Value::from(1));
+    assert_eq!(eval("round(1.5001)").unwrap(), Value::from(2));
+    assert_eq!(eval("round(1.5)").unwrap(), Value::from(2));
+}
+
+#[test]
+fn abs() {
+    assert_eq!(eval("abs(1.5)").unwrap(), Value::from(1.5));
+    assert_eq!(eval("abs(-1.5)").unwrap(), Value::from(1.5));
+    assert_eq!(eval("abs(4+3i)").unwrap(), Value::from(5));
+}
+
+#[test]
+fn sqrt() {
+    assert_eq!(eval("sqrt(2)").unwrap(), eval("2^(0.5)").unwrap());
+    assert_eq!(eval("sqrt(4+3i)").unwrap(), eval("(4+3i)^(0.5)").unwrap());
+}
+
+#[test]
+fn cbrt() {
+    assert_eq!(eval("cbrt(27)").unwrap(), Value::from(3));
+    assert_eq!(eval("cbrt(-8)").unwrap(), Value::from(-2));
+    assert_eq!(eval("cbrt(8i)").unwrap(), eval("(8i)^(1/3)").unwrap());
+}
+
+#[test]
+fn popcount() {
+    assert_eq!(eval("popcount(255)").unwrap(), Value::from(8));
+    assert_eq!(eval("popcount(0)").unwrap(), Value::from(0));
+}
+
+#[test]
+fn bitand_bitor() {
+    assert_eq!(eval("bitand(12, 10)").unwrap(), Value::from(8));
+    assert_eq!(eval("bitor(12, 10)").unwrap(), Value::from(14));
+    assert_eq!(eval("bitor((1, 2), 4)").unwrap(), Value::from(vec![5, 6]));
+}
+
+#[test]
+fn ln() {
+    assert_eq!(eval("ln(e^3.5)").unwrap(), Value::from(3.5));
+    assert_eq!(eval("ln(e^(-1.5))").unwrap(), Value::from(-1.5));
+    assert_eq!(
+        eval("ln(-1)").unwrap(),
+        Value::from(Complex64::new(0.0, 3.14159265))
+    );
+    assert_eq!(
+        eval("ln(i)").unwrap(),
+        Value::from(Complex64::new(0.0, 1.57079633))
+    );
+}
+
+#[test]
+fn log() {
+    assert_eq!(eval("log(3, 9^2)").unwrap(), Value::from(4));
+}
+
+#[test]
+fn exp() {
+    assert_eq!(eval("exp(ln(4))").unwrap(), Value::from(4));
+}
+
+#[test]
+fn branch() {
+    assert_eq!(eval("branch(true, 1, 2)").unwrap(), Value::from(1));
+    assert_eq!(eval("branch(false, 1, 2)").unwrap(), Value::from(2));
+}
+
+#[test]
+fn sin() {
+    let context = Context::new(
+        settings::Rounding::default(),
+        settings::AngleUnit::Degree,
+        settings::DepthLimit::default(),
+    );
+    assert_eq!(
+        eval_with_static_context("sin(30)", &context).unwrap(),
+        Value::from(0.5)
+    );
+    assert_eq!(
+        eval_with_static_context("sin(30)", &context).unwrap(),
+        Value::from(0.5)
+    );
+}
+
+#[test]
+fn cos() {
+    let context = Context::new(
+        settings::Rounding::default(),
+        settings::AngleUnit::Degree,
+        settings::DepthLimit::default(),
+    );
+    assert_eq!(
+        eval_with_static_context("cos(60)", &context).unwrap(),
+        Value::from(0.5)
+    );
+}
+
+#[test]
+fn tan() {
+    let context = Context::new(
+        settings::Rounding::default(),
+        settings::AngleUnit::Degree,
+        settings::DepthLimit::default(),
+    );
+    assert_eq!(
+        eval_with_static_context("tan(45)", &context).unwrap(),
+        Value::from(1)
+    );
+}
+
+#[test]
+fn asin() {
+    let context = Context::new(
+        settings::Rounding::default(),
+        settings::AngleUnit::Degree,
+        settings::DepthLimit::default(),
+    );
+    assert_eq!(
+        eval_with_static_context("asin(0.5)", &context).unwrap(),
+        Value::from(30)
+    );
+}
+
+#[test]
+fn acos() {
+    let context = Context::new(
+        settings::Rounding::default(),
+        settings::AngleUnit::Degree,
+        settings::DepthLimit::default(),
+    );
+    assert_eq!(
+        eval_with_static_context("acos(0.5)", &context).unwrap(),
+        Value::from(60)
+    );
+}
+
+#[test]
+fn atan() {
+    let context = Context::new(
+        settings::Rounding::default(),
+        settings::AngleUnit::Degree,
+        settings::DepthLimit::default(),
+    );
+    assert_eq!(
+        eval_with_static_context("atan(1)", &context).unwrap(),
+        Value::from(45)
+    );
+}
+
+#[test]
+fn sinh_asinh() {
+    assert_eq!(eval("asinh(sinh(1))").unwrap(), Value::from(1));
+    assert_eq!(eval("asinh(sinh(pi))").unwrap(), Value::from(3.14159265));
+}
+#[test]
+fn cosh_acosh() {
+    assert_eq!(eval("acosh(cosh(1))").unwrap(), Value::from(1));
+    assert_eq!(eval("acosh(cosh(pi))").unwrap(), Value::from(3.14159265));
+}
+#[test]
+fn tanh_atanh() {
+    // Requires less precision, otherwise the test would fail
+    let context = Context::new(
+        settings::Rounding::Round(4),
+        settings::AngleUnit::default(),
+        settings::DepthLimit::default(),
     );
     assert_eq!(
         eval_with_static_context("atanh(tanh(1))", &context).unwrap(),
@@ -538,6 +2554,20 @@ fn tanh_atanh() {
     );
 }
 
+#[test]
+fn deg() {
+    assert_eq!(eval("deg(180)").unwrap(), eval("pi").unwrap());
+    let context = Context::new(
+        settings::Rounding::default(),
+        settings::AngleUnit::Degree,
+        settings::DepthLimit::default(),
+    );
+    assert_eq!(
+        eval_with_static_context("deg(30)", &context).unwrap(),
+        Value::from(30)
+    );
+}
+
 #[test]
 fn re() {
     assert_eq!(eval("re(1)").unwrap(), Value::from(1));
@@ -10,7 +10,10 @@ impl fmt::Display for Token {
         match self.r#type {
             Plus | Minus | Star | Slash | Dot | Comma | OpeningBracket | ClosingBracket | Equal
             | Caret | Percentage | LessThan | GreaterThan | LessOrEqualTo | GreaterOrEqualTo
-            | DoubleEqual | DoubleAnd | DoubleOr | NotEqual | Exclamation | And | Or => {
+            | DoubleEqual | DoubleAnd | DoubleOr | NotEqual | Exclamation | And | Or | PlusMinus
+            | DoubleDot | Uncertainty | Question | Colon | ColonEqual | OpeningSquareBracket
+            | ClosingSquareBracket | Factorial | DoubleFactorial | Percent | Tilde | DoubleSlash
+            | DoubleLessThan | DoubleGreaterThan | Xor => {
                 write!(f, "{}", self.r#type)
             }
 
@@ -34,6 +37,10 @@ impl fmt::Display for TokenType {
             GreaterThan => write!(f, ">"),
             And => write!(f, "&"),
             Or => write!(f, "|"),
+            PlusMinus => write!(f, "±"),
+            Question => write!(f, "?"),
+            Colon => write!(f, ":"),
+            Tilde => write!(f, "~"),
 
             LessOrEqualTo => write!(f, "<="),
             GreaterOrEqualTo => write!(f, ">="),
@@ -41,11 +48,24 @@ impl fmt::Display for TokenType {
             DoubleAnd => write!(f, "&&"),
             DoubleOr => write!(f, "||"),
             NotEqual => write!(f, "!="),
+            DoubleDot => write!(f, ".."),
+            Uncertainty => write!(f, "+-"),
+            ColonEqual => write!(f, ":="),
+            DoubleSlash => write!(f, "//"),
+            DoubleLessThan => write!(f, "<<"),
+            DoubleGreaterThan => write!(f, ">>"),
+            Xor => write!(f, "xor"),
 
             Exclamation => write!(f, "!"),
 
+            Factorial => write!(f, "!"),
+            DoubleFactorial => write!(f, "!!"),
+            Percent => write!(f, "%"),
+
             OpeningBracket => write!(f, "("),
             ClosingBracket => write!(f, ")"),
+            OpeningSquareBracket => write!(f, "["),
+            ClosingSquareBracket => write!(f, "]"),
 
             Dot => write!(f, "."),
             Literal => write!(f, "<literal>"),
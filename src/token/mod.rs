@@ -2,19 +2,21 @@ mod display;
 pub mod tokentype;
 
 use crate::{
-    context::Context,
+    context::{settings, Context},
     function::builtin,
-    out::{ErrorType, EvalResult},
+    out::{Diagnostics, ErrorType, EvalResult, WarningKind},
     token::tokentype::TokenType,
 };
 
 use self::tokentype::IdentifierType;
-use itertools::Itertools;
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Token {
     /// The generic token type.
     pub r#type: TokenType,
+    /// The position of the token inside the original input, in chars.
+    pub position: usize,
     /// The token size inside the original input.
     pub length: usize,
     /// The token content. Only useful for literals and identifiers.
@@ -27,11 +29,18 @@ impl Token {
     fn new(r#type: TokenType, length: usize, value: &str) -> Self {
         Self {
             r#type,
+            position: 0,
             length,
             value: String::from(value),
         }
     }
 
+    /// Places the token at the provided position of the input.
+    fn at(mut self, position: usize) -> Self {
+        self.position = position;
+        self
+    }
+
     fn join_with(&mut self, token: &Token, r#type: TokenType) {
         self.r#type = r#type;
         self.value.extend(token.value.chars());
@@ -39,407 +48,1050 @@ impl Token {
     }
 }
 
-/// Builds a stream of tokens.
-pub fn build_stream(mut source: String, context: &Context) -> EvalResult<TokenStream> {
-    source = remove_whitespaces(&source);
-
-    let mut stream: TokenStream = vec![];
-    let mut content_iter = source.chars();
-
-    while let Some(c) = content_iter.next() {
-        stream.push(tokenize(&c)?);
+/// Builds a stream of tokens, reporting the problems found to `diagnostics`.
+pub fn build_stream(
+    source: String,
+    context: &Context,
+    diagnostics: &mut Diagnostics,
+) -> EvalResult<TokenStream> {
+    let mut lexer = Lexer::new(context);
+    lexer.read_chars(&source);
+    let stream = lexer.finish(diagnostics)?;
+
+    if context.syntax != settings::Syntax::Implicit {
+        // Nothing is read implicitly with the strict and explicit syntaxes.
+        for warning in diagnostics.take_warnings() {
+            let (position, length) = (warning.position, warning.length);
+            diagnostics.recover(ErrorType::AmbiguousSyntax { warning }, position, length, ())?;
+        }
     }
 
-    stream = join_operators(&stream);
-    stream = join_identifiers(&stream)?;
-    stream = join_literals(&stream)?;
-    stream = format_identifiers(&stream, context);
-    stream = predict_unknown_identifiers(&stream);
-    stream = add_implicit_brackets(&stream)?;
-    stream = add_implicit_multiplications(&stream);
-
     Ok(stream)
 }
 
-fn join_operators(stream: &TokenStream) -> TokenStream {
-    fn find_and_join(
-        stream: &TokenStream,
-        pattern: Vec<TokenType>,
-        replacement: TokenType,
-    ) -> TokenStream {
-        let mut out_v = vec![];
+/// The steps of reading finding problems, in the order their problems are reported: an
+/// unknown char is reported before a misplaced bracket, wherever the two are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Step {
+    Chars,
+    Brackets,
+    Numbers,
+    Identifiers,
+    ImplicitBrackets,
+    ImplicitMultiplications,
+}
 
-        let mut start_index = 0;
-        let mut end_index = pattern.len();
+/// What the tokens around an operator tell about it.
+struct Surroundings {
+    /// Whether an exclamation point is followed by the `=` of `!=`.
+    not_equal: bool,
+    /// Whether a percentage is followed by an operand.
+    before_operand: bool,
+    /// Whether there is a space before the token.
+    spaced_before: bool,
+    /// The next token read.
+    next: Option<Token>,
+}
 
-        let mut stream_as_iter = stream.iter();
+#[derive(Debug)]
+enum Problem {
+    Error(ErrorType),
+    Warning(WarningKind),
+}
 
-        while end_index <= stream.len() {
-            let slice = &stream[start_index..end_index]
-                .iter()
-                .map(|token| token.r#type)
-                .collect::<Vec<TokenType>>();
+/// Reads the input in a single pass. Chars are read into tokens, which are handed to
+/// the following steps as soon as no later char can change them, like a `°` wrapping
+/// the operand before it. Each step keeps the few tokens it needs to decide, and hands
+/// the others to the next one, down to the stream.
+struct Lexer<'a> {
+    context: &'a Context,
+    explicit: bool,
+    /// The known identifiers, found on the first identifier read.
+    names: Option<IdentifierNames<'a>>,
+    /// The problems found, reported once the whole input is read.
+    problems: Vec<(Step, Problem, usize, usize)>,
+
+    /// The tokens read from the chars.
+    read: TokenStream,
+    /// The index of the first token of `read` not handed to the steps yet.
+    next: usize,
+    /// The indices of the brackets read and not closed yet.
+    open_brackets: Vec<usize>,
+    /// The index of the first token of the operand ending with the last token read.
+    operand_start: Option<usize>,
+    /// Whether the previous char was a superscript, whose exponent is still open.
+    in_exponent: bool,
+
+    /// The number of bars opening an absolute value and not closed yet, for each
+    /// bracket open, starting from the outermost level.
+    open_bars: Vec<usize>,
+    /// The last two tokens with their bars and postfix operators read.
+    before_last: Option<Token>,
+    last: Option<Token>,
+    /// The operators written without spaces between them, joined as they are read.
+    operators: TokenStream,
+    /// Whether there is a space before the operators being joined.
+    operators_spaced: bool,
+    /// Where the last token read for joining operators ends.
+    operators_end: usize,
+    /// Whether each open bracket is a square one.
+    open_square_brackets: Vec<bool>,
+    /// The identifier being joined.
+    identifier: Option<Token>,
+    /// The number being joined, and whether it already has a decimal point.
+    literal: Option<Token>,
+    dot_found: bool,
+    /// The unknown identifier waiting for the next token to tell if it is a function.
+    unknown: Option<Token>,
+    /// The token waiting for the next one to tell if it is a function called without
+    /// brackets.
+    previous: Option<Token>,
+
+    stream: TokenStream,
+}
 
-            if *slice == pattern {
-                out_v.push(Token::new(replacement, pattern.len(), ""));
+impl<'a> Lexer<'a> {
+    fn new(context: &'a Context) -> Self {
+        Self {
+            context,
+            explicit: context.syntax == settings::Syntax::Explicit,
+            names: None,
+            problems: vec![],
+            read: vec![],
+            next: 0,
+            open_brackets: vec![],
+            operand_start: None,
+            in_exponent: false,
+            open_bars: vec![0],
+            before_last: None,
+            last: None,
+            operators: vec![],
+            operators_spaced: false,
+            operators_end: 0,
+            open_square_brackets: vec![],
+            identifier: None,
+            literal: None,
+            dot_found: false,
+            unknown: None,
+            previous: None,
+            stream: vec![],
+        }
+    }
 
-                for _ in 0..pattern.len() {
-                    // Advance the iterator, consuming the replaced tokens.
-                    stream_as_iter.next();
-                }
+    /// Records a problem, to be reported once the whole input is read.
+    fn report(&mut self, step: Step, problem: Problem, position: usize, length: usize) {
+        self.problems.push((step, problem, position, length));
+    }
 
-                start_index += pattern.len();
-                end_index += pattern.len();
-            } else {
-                out_v.push(stream[start_index].clone());
-                stream_as_iter.next();
+    /// Reads the chars into tokens.
+    fn read_chars(&mut self, source: &str) {
+        for (position, c) in source.chars().enumerate() {
+            self.read_char(position, c);
+            self.hand_over(false);
+        }
+        if self.in_exponent {
+            let end = source.chars().count();
+            self.push_read(Token::new(TokenType::ClosingBracket, 0, "").at(end));
+        }
+    }
 
-                start_index += 1;
-                end_index += 1;
+    fn read_char(&mut self, position: usize, c: char) {
+        let superscript = from_superscript(c);
+        if self.in_exponent && superscript.is_none() {
+            self.push_read(Token::new(TokenType::ClosingBracket, 0, "").at(position));
+            self.in_exponent = false;
+        }
+        // Whitespaces are skipped, but still count in the positions of the tokens.
+        if c == ' ' {
+            return;
+        }
+        // Superscripts are read as an exponent in brackets: `x²` is `x^(2)`.
+        if let Some(c) = superscript {
+            if !self.in_exponent {
+                self.push_read(Token::new(TokenType::Caret, 0, "").at(position));
+                self.push_read(Token::new(TokenType::OpeningBracket, 0, "").at(position));
+                self.in_exponent = true;
+            }
+            if let Ok(token) = tokenize(&c) {
+                self.push_read(token.at(position));
             }
+            return;
         }
+        if c == '°' {
+            self.read_degrees(position);
+            return;
+        }
+        // Floor and ceiling brackets are read as calls: `⌊x⌋` is `floor[x]`.
+        if c == '⌊' || c == '⌈' {
+            let func_name = if c == '⌊' { "floor" } else { "ceil" };
+            self.push_read(
+                Token::new(
+                    TokenType::Identifier(IdentifierType::Function),
+                    1,
+                    func_name,
+                )
+                .at(position),
+            );
+            self.push_read(Token::new(TokenType::OpeningSquareBracket, 0, "").at(position));
+            return;
+        }
+        // With the explicit syntax, identifiers can contain underscores, and digits
+        // after their first char.
+        let continues_identifier = self.read.last().is_some_and(|last| {
+            last.r#type == TokenType::Identifier(IdentifierType::Unknown)
+                && last.position + last.length == position
+        });
+        if self.explicit && (c == '_' || (c.is_numeric() && continues_identifier)) {
+            self.push_read(
+                Token::new(
+                    TokenType::Identifier(IdentifierType::Unknown),
+                    1,
+                    &c.to_string(),
+                )
+                .at(position),
+            );
+            return;
+        }
+        match tokenize(&c) {
+            Ok(token) => self.push_read(token.at(position)),
+            // Unknown chars are skipped when recovering.
+            Err(error) => self.report(Step::Chars, Problem::Error(error), position, 1),
+        }
+    }
+
+    /// Adds a token read from the chars, keeping track of where the operand it ends
+    /// starts.
+    fn push_read(&mut self, token: Token) {
+        use TokenType::*;
 
-        // Add the remaining data
-        for token in stream_as_iter {
-            out_v.push(token.clone());
+        let last_type = self.read.last().map(|last| last.r#type);
+        if last_type == Some(Identifier(IdentifierType::Unknown))
+            && token.r#type != Identifier(IdentifierType::Unknown)
+        {
+            self.read_keyword_operators();
         }
 
-        out_v
+        let index = self.read.len();
+        self.operand_start = match token.r#type {
+            // Numbers and identifiers are still split in chars.
+            Literal | Dot if matches!(last_type, Some(Literal | Dot)) => self.operand_start,
+            Identifier(IdentifierType::Unknown)
+                if last_type == Some(Identifier(IdentifierType::Unknown)) =>
+            {
+                self.operand_start
+            }
+            Literal | Dot | Identifier(_) => Some(index),
+            OpeningBracket | OpeningSquareBracket => {
+                self.open_brackets.push(index);
+                None
+            }
+            ClosingBracket | ClosingSquareBracket => self.open_brackets.pop(),
+            _ => None,
+        };
+        self.read.push(token);
     }
 
-    use TokenType::*;
+    /// Wraps the operand read last in a call to `deg`, which converts it from degrees.
+    /// The call is in brackets, so `sin 90°` is `sin((deg(90)))`.
+    fn read_degrees(&mut self, position: usize) {
+        let deg = Token::new(TokenType::Identifier(IdentifierType::Function), 1, "deg");
+        match self.operand_start {
+            Some(start) => {
+                if self.read.last().map(|last| last.r#type)
+                    == Some(TokenType::Identifier(IdentifierType::Unknown))
+                {
+                    self.read_keyword_operators();
+                }
+                let start_position = self.read[start].position;
+                let opening = Token::new(TokenType::OpeningBracket, 0, "").at(start_position);
+                self.read
+                    .splice(start..start, [opening.clone(), deg.at(position), opening]);
+                let closing = Token::new(TokenType::ClosingBracket, 0, "").at(position);
+                self.read.extend([closing.clone(), closing]);
+            }
+            // Without an operand, `deg` is left without arguments and reported as such.
+            None => self.push_read(deg.at(position)),
+        }
+    }
 
-    let mut new_stream = stream.clone();
-    new_stream = find_and_join(&new_stream, vec![LessThan, Equal], LessOrEqualTo);
-    new_stream = find_and_join(&new_stream, vec![GreaterThan, Equal], GreaterOrEqualTo);
-    new_stream = find_and_join(&new_stream, vec![Equal, Equal], DoubleEqual);
-    new_stream = find_and_join(&new_stream, vec![Exclamation, Equal], NotEqual);
-    new_stream = find_and_join(&new_stream, vec![And, And], DoubleAnd);
-    new_stream = find_and_join(&new_stream, vec![Or, Or], DoubleOr);
+    /// Reads the identifiers spelling an operator, like `xor`, as that operator. Only
+    /// whole identifiers are read, so `a xor b` is an operator while `axorb` is not.
+    fn read_keyword_operators(&mut self) {
+        let is_unknown =
+            |token: &Token| token.r#type == TokenType::Identifier(IdentifierType::Unknown);
+        let count = self
+            .read
+            .iter()
+            .rev()
+            .take_while(|token| is_unknown(token))
+            .count();
+        let chars = self.read.split_off(self.read.len() - count);
+
+        // A whole identifier is a run of contiguous chars.
+        let mut start = 0;
+        while start < chars.len() {
+            let mut end = start + 1;
+            while end < chars.len() && is_joined(&chars[end - 1], &chars[end]) {
+                end += 1;
+            }
+            let word: String = chars[start..end]
+                .iter()
+                .map(|token| &token.value[..])
+                .collect();
+            if word == "xor" {
+                self.read
+                    .push(Token::new(TokenType::Xor, 3, "").at(chars[start].position));
+            } else {
+                self.read.extend_from_slice(&chars[start..end]);
+            }
+            start = end;
+        }
+    }
 
-    new_stream
-}
+    /// Hands the tokens read to the following steps, up to the ones that later chars
+    /// could still change, or all of them at the end of the input.
+    fn hand_over(&mut self, at_end: bool) {
+        let end = match (self.open_brackets.first(), self.operand_start) {
+            _ if at_end => self.read.len(),
+            (Some(&bracket), Some(operand)) => bracket.min(operand),
+            (Some(&index), None) | (None, Some(index)) => index,
+            (None, None) => self.read.len(),
+        };
 
-fn predict_unknown_identifiers(stream: &TokenStream) -> TokenStream {
-    // Return if empty.
-    if stream.len() == 0 {
-        return stream.clone();
-    }
+        while self.next < end {
+            let token = self.read[self.next].clone();
+            let rest = &self.read[self.next + 1..];
+            // Operators are read looking at the tokens following them, which must not
+            // change anymore.
+            let visible = &rest[..end - self.next - 1];
+            let is_known = match token.r#type {
+                // A bar looks at the next token.
+                TokenType::Or => !visible.is_empty(),
+                // An exclamation point looks for the `=` of `!=`, not followed by another.
+                TokenType::Exclamation => visible.len() >= 2,
+                // A percentage looks at the prefix operators up to the next operand, or
+                // at the tokens of `..` and `!=`.
+                TokenType::Percentage => {
+                    visible.len() >= 3
+                        && visible
+                            .iter()
+                            .any(|token| !token.r#type.is_unary_operator())
+                }
+                _ => true,
+            };
+            if !at_end && !is_known {
+                break;
+            }
 
-    let mut out_stream = vec![];
+            let not_equal = is_not_equal(&self.read[self.next..]);
+            let before_operand = token.r#type == TokenType::Percentage && starts_with_operand(rest);
+            let spaced_before = self.next > 0 && !is_joined(&self.read[self.next - 1], &token);
+            let next = rest.first().cloned();
+            self.next += 1;
+            let surroundings = Surroundings {
+                not_equal,
+                before_operand,
+                spaced_before,
+                next,
+            };
+            self.read_bars_and_postfix_operators(token, surroundings);
+        }
+    }
 
-    let stream_as_iter = stream.iter();
+    /// Reads the bars around absolute values as calls to `abs`, so `|x|` is `abs(x)`, and
+    /// finds the postfix operators, which follow an operand.
+    ///
+    /// A bar not following an operand opens an absolute value. Following an operand, it
+    /// is an `|` operator if written with spaces around it, or as part of `||`, and opens
+    /// an absolute value if only spaced before it, like in `2 |x|`. Otherwise, it closes
+    /// the last absolute value open within the same brackets, or opens one if followed
+    /// by a prefix operator, like in `2|-3|`.
+    fn read_bars_and_postfix_operators(&mut self, token: Token, surroundings: Surroundings) {
+        use TokenType::*;
 
-    let mut convert = |prev: &Token, next: Option<&Token>| {
-        let next_is_bracket = match next {
-            Some(token) => token.r#type == TokenType::OpeningBracket,
-            None => false,
+        // The `..` operator is not joined yet, so its tokens do not end an operand.
+        let after_operand = match (&self.before_last, &self.last) {
+            (Some(previous), Some(last)) if previous.r#type == Dot && last.r#type == Dot => false,
+            (_, Some(last)) => ends_operand(last),
+            (_, None) => false,
         };
 
-        if prev.r#type == TokenType::Identifier(IdentifierType::Unknown) {
-            if next_is_bracket {
-                // Categorize as function
-                out_stream.push(Token::new(
-                    TokenType::Identifier(IdentifierType::Function),
-                    prev.value.len(),
-                    &prev.value[..],
-                ));
-            } else {
-                // Categorize as var
-                out_stream.push(Token::new(
-                    TokenType::Identifier(IdentifierType::Var),
-                    prev.value.len(),
-                    &prev.value[..],
-                ));
+        match token.r#type {
+            OpeningBracket | OpeningSquareBracket => {
+                self.open_bars.push(0);
+                self.push_operator(token);
             }
-        } else {
-            out_stream.push(prev.clone());
+            ClosingBracket | ClosingSquareBracket => {
+                if self.open_bars.len() > 1 {
+                    self.open_bars.pop();
+                }
+                self.push_operator(token);
+            }
+            Or => {
+                // The second bar of an `||` following an operand.
+                let continues_or = match (&self.before_last, &self.last) {
+                    (Some(before), Some(last)) => {
+                        last.r#type == Or && is_joined(last, &token) && ends_operand(before)
+                    }
+                    _ => false,
+                };
+                let open_bars = *self.open_bars.last().unwrap();
+                let (spaced_after, next_type) = match &surroundings.next {
+                    Some(next) => (!is_joined(&token, next), Some(next.r#type)),
+                    None => (false, None),
+                };
+
+                let is_operator = continues_or
+                    || (after_operand
+                        && ((surroundings.spaced_before && spaced_after)
+                            || (open_bars == 0 && !spaced_after && next_type == Some(Or))));
+                let before_prefix_operator =
+                    !spaced_after && next_type.is_some_and(|t| t.is_unary_operator());
+                let is_opening = !after_operand
+                    || (open_bars == 0 && (surroundings.spaced_before || before_prefix_operator));
+
+                if is_operator {
+                    self.push_operator(token);
+                } else if is_opening {
+                    *self.open_bars.last_mut().unwrap() += 1;
+                    self.push_operator(
+                        Token::new(Identifier(IdentifierType::Function), 1, "abs")
+                            .at(token.position),
+                    );
+                    self.push_operator(Token::new(OpeningBracket, 0, "").at(token.position));
+                } else if open_bars > 0 {
+                    *self.open_bars.last_mut().unwrap() -= 1;
+                    self.push_operator(Token::new(ClosingBracket, 1, "").at(token.position));
+                } else {
+                    self.push_operator(token);
+                }
+            }
+            // An exclamation point followed by `=` is part of `!=`, unless the `=` is
+            // part of `==`.
+            Exclamation if after_operand && !surroundings.not_equal => {
+                let last = self.last.as_mut().unwrap();
+                if last.r#type == Factorial && last.position + last.length == token.position {
+                    last.join_with(&token, DoubleFactorial);
+                } else {
+                    self.push_operator(Token::new(Factorial, 1, "").at(token.position));
+                }
+            }
+            // A percentage followed by an operand is a modulo.
+            Percentage if after_operand && !surroundings.before_operand => {
+                self.push_operator(Token::new(Percent, 1, "").at(token.position));
+            }
+            _ => self.push_operator(token),
         }
-    };
+    }
 
-    // Convert unknown identifiers to function if they are followed by a parenthesis
-    for (prev, next) in stream_as_iter.tuple_windows() {
-        convert(prev, Some(next));
+    /// Keeps the last two tokens with their bars and postfix operators read, handing
+    /// the one before to the next step.
+    fn push_operator(&mut self, token: Token) {
+        self.before_last = self.last.replace(token);
+        if let Some(before_last) = self.before_last.clone() {
+            self.join_operators(before_last);
+        }
     }
 
-    // Add last token, not included in the previous identifier
-    let last_token = stream.iter().last().unwrap();
-    convert(last_token, None);
+    /// Joins the operators written with more than one char, like `<=`, as they are
+    /// read.
+    fn join_operators(&mut self, token: Token) {
+        use TokenType::*;
 
-    out_stream
-}
+        let is_joinable = matches!(
+            token.r#type,
+            LessThan
+                | GreaterThan
+                | Slash
+                | Equal
+                | Exclamation
+                | And
+                | Or
+                | Dot
+                | Plus
+                | Minus
+                | Colon
+        );
+        let spaced = token.position > self.operators_end;
+        self.operators_end = token.position + token.length;
+        // Operators are joined only if written without spaces, so `2 + -3` is a sum.
+        if is_joinable {
+            if let Some(last) = self
+                .operators
+                .last_mut()
+                .filter(|last| is_joined(last, &token))
+            {
+                match joined_parts(last.r#type) {
+                    // The second char of a joined operator is joined to the next one
+                    // instead if they make an operator listed first, like in `!==`.
+                    Some((index, first, second)) => match joined_operator(second, token.r#type) {
+                        Some((other, joined)) if other < index => {
+                            let position = last.position;
+                            *last = Token::new(first, 1, "").at(position);
+                            self.operators
+                                .push(Token::new(joined, 2, "").at(position + 1));
+                        }
+                        _ => self.operators.push(token),
+                    },
+                    None => match joined_operator(last.r#type, token.r#type) {
+                        Some((_, joined)) => last.join_with(&token, joined),
+                        None => self.operators.push(token),
+                    },
+                }
+                return;
+            }
+        }
+        self.flush_operators(spaced);
+        if is_joinable {
+            self.operators_spaced = spaced;
+            self.operators.push(token);
+        } else {
+            self.convert_square_brackets(token);
+        }
+    }
 
-fn add_implicit_brackets(stream: &TokenStream) -> EvalResult<TokenStream> {
-    // Return if empty.
-    if stream.len() == 0 {
-        return Ok(stream.clone());
+    /// Hands over the operators joined. `spaced_after` tells if they are followed by a
+    /// space.
+    fn flush_operators(&mut self, spaced_after: bool) {
+        let mut operators = std::mem::take(&mut self.operators);
+        // A measurement is written with spaces around `+-`, so that `2+-1` is a sum.
+        if let [plus, minus] = &operators[..] {
+            if plus.r#type == TokenType::Plus
+                && minus.r#type == TokenType::Minus
+                && self.operators_spaced
+                && spaced_after
+            {
+                operators = vec![Token::new(TokenType::Uncertainty, 2, "").at(plus.position)];
+            }
+        }
+        for token in operators {
+            self.convert_square_brackets(token);
+        }
     }
 
-    let mut out_stream = vec![];
+    /// Checks that square brackets are correctly paired and converts them to round ones.
+    /// When recovering, mismatched brackets close the open one and brackets closing
+    /// nothing are skipped.
+    fn convert_square_brackets(&mut self, token: Token) {
+        match token.r#type {
+            TokenType::OpeningBracket => self.open_square_brackets.push(false),
+            TokenType::OpeningSquareBracket => self.open_square_brackets.push(true),
+            TokenType::ClosingBracket | TokenType::ClosingSquareBracket => {
+                let is_square = token.r#type == TokenType::ClosingSquareBracket;
+                let error = Problem::Error(ErrorType::InvalidClosingBracket);
+                match self.open_square_brackets.pop() {
+                    Some(was_square) if was_square == is_square => (),
+                    Some(_) => self.report(Step::Brackets, error, token.position, token.length),
+                    None => {
+                        self.report(Step::Brackets, error, token.position, token.length);
+                        return;
+                    }
+                }
+            }
+            _ => (),
+        }
 
-    let mut skip_iteration = false;
+        self.join_identifiers(match token.r#type {
+            TokenType::OpeningSquareBracket => {
+                Token::new(TokenType::OpeningBracket, 1, "").at(token.position)
+            }
+            TokenType::ClosingSquareBracket => {
+                Token::new(TokenType::ClosingBracket, 1, "").at(token.position)
+            }
+            _ => token,
+        });
+    }
 
-    let stream_as_iter = stream.iter();
-    // Add implicit brackets for all functions not followed by an opening bracket
-    for (prev, next) in stream_as_iter.tuple_windows() {
-        if skip_iteration {
-            skip_iteration = false;
-            continue;
+    /// Joins the chars of identifiers. Identifiers separated by whitespaces are kept
+    /// apart, so that `sin x` is not read as `sinx`.
+    fn join_identifiers(&mut self, token: Token) {
+        let unknown = TokenType::Identifier(IdentifierType::Unknown);
+        if token.r#type != unknown {
+            if let Some(identifier) = self.identifier.take() {
+                self.join_literals(identifier);
+            }
+            return self.join_literals(token);
         }
 
-        if prev.r#type == TokenType::Identifier(IdentifierType::Function)
-            && next.r#type != TokenType::OpeningBracket
-        {
-            if next.r#type == TokenType::Literal
-                || next.r#type == TokenType::Identifier(IdentifierType::Var)
-            {
-                out_stream.push(prev.clone());
-                out_stream.push(Token::new(TokenType::OpeningBracket, 1, ""));
-                out_stream.push(next.clone());
-                out_stream.push(Token::new(TokenType::ClosingBracket, 1, ""));
+        match &mut self.identifier {
+            Some(identifier) if is_joined(identifier, &token) => {
+                identifier.join_with(&token, unknown);
+            }
+            _ => {
+                if let Some(identifier) = self.identifier.replace(token) {
+                    self.join_literals(identifier);
+                }
+            }
+        }
+    }
 
-                skip_iteration = true;
-            } else {
-                return Err(ErrorType::MissingFunctionParameters {
-                    func_name: prev.value.clone(),
-                });
+    /// Joins the digits and the decimal point of numbers.
+    fn join_literals(&mut self, token: Token) {
+        let is_dot = token.r#type == TokenType::Dot;
+        if token.r#type != TokenType::Literal && !is_dot {
+            self.dot_found = false;
+            if let Some(literal) = self.literal.take() {
+                self.format_identifiers(literal);
             }
-        } else {
-            out_stream.push(prev.clone());
+            return self.format_identifiers(token);
+        }
+
+        if is_dot {
+            // Allow only one decimal point. When recovering, the extra one is skipped.
+            if self.dot_found {
+                let error = ErrorType::InvalidTokenPosition {
+                    token: token.r#type,
+                };
+                let (position, length) = (token.position, token.length);
+                return self.report(Step::Numbers, Problem::Error(error), position, length);
+            }
+            self.dot_found = true;
+        }
+        match &mut self.literal {
+            Some(literal) => literal.join_with(&token, TokenType::Literal),
+            None => self.literal = Some(token),
         }
     }
 
-    if !skip_iteration {
-        // Push last item if it was not included during the previous iteration.
-        out_stream.push(stream.iter().last().unwrap().clone());
+    /// Splits the identifiers into the known names they are made of, like `xy` into `x`
+    /// and `y`.
+    fn format_identifiers(&mut self, token: Token) {
+        if token.r#type != TokenType::Identifier(IdentifierType::Unknown) {
+            return self.predict_unknown_identifiers(token);
+        }
+
+        let context = self.context;
+        let names = self
+            .names
+            .get_or_insert_with(|| IdentifierNames::new(context));
+        let content = token.value.clone();
+        let splitted = match context.syntax {
+            // Identifiers are read as a whole with the explicit syntax.
+            settings::Syntax::Explicit => {
+                let i_type = names.identifier_type(&content);
+                vec![(content, i_type)]
+            }
+            _ => names.split(content),
+        };
+        // Keep the identifier whole if some part of it is still unknown: it may be
+        // split when evaluated, once the parameters of functions are known, and
+        // otherwise it is reported as a whole.
+        if splitted.len() > 1
+            && splitted
+                .iter()
+                .any(|(_, i_type)| *i_type == IdentifierType::Unknown)
+        {
+            return self.predict_unknown_identifiers(token);
+        }
+        if splitted.len() > 1 {
+            let warning = WarningKind::SplitIdentifier {
+                identifier: token.value.clone(),
+                parts: splitted.iter().map(|(i, _)| i.clone()).collect(),
+            };
+            let (position, length) = (token.position, token.length);
+            self.report(
+                Step::Identifiers,
+                Problem::Warning(warning),
+                position,
+                length,
+            );
+        }
+        let mut position = token.position;
+        for (i, i_type) in splitted {
+            let length = i.chars().count();
+            self.predict_unknown_identifiers(
+                Token::new(TokenType::Identifier(i_type), length, &i[..]).at(position),
+            );
+            position += length;
+        }
     }
-    Ok(out_stream)
-}
 
-fn format_identifiers(stream: &TokenStream, context: &Context) -> TokenStream {
-    let mut out_stream = vec![];
+    /// Reads the unknown identifiers as functions if followed by a bracket, and as
+    /// variables otherwise.
+    fn predict_unknown_identifiers(&mut self, token: Token) {
+        if let Some(unknown) = self.unknown.take() {
+            let i_type = if token.r#type == TokenType::OpeningBracket {
+                IdentifierType::Function
+            } else {
+                IdentifierType::Var
+            };
+            self.add_implicit_brackets(
+                Token::new(
+                    TokenType::Identifier(i_type),
+                    unknown.value.len(),
+                    &unknown.value[..],
+                )
+                .at(unknown.position),
+            );
+        }
 
-    let mut stream_as_iter = stream.iter();
-    while let Some(token) = stream_as_iter.next() {
         if token.r#type == TokenType::Identifier(IdentifierType::Unknown) {
-            let content = token.value.clone();
-            let splitted = split_into_identifiers(content, context);
-            for (i, i_type) in splitted {
-                out_stream.push(Token::new(TokenType::Identifier(i_type), i.len(), &i[..]));
-            }
+            self.unknown = Some(token);
         } else {
-            out_stream.push(token.clone());
+            self.add_implicit_brackets(token);
         }
     }
-    out_stream
-}
 
-/// Given a string, returns a vector with all identified vars and function.
-pub fn split_into_identifiers(input: String, context: &Context) -> Vec<(String, IdentifierType)> {
-    let mut out = vec![];
-
-    /// Given a string tries to find a match from all possible categories.
-    fn try_to_categorize(
-        sorted_patterns: &Vec<(IdentifierType, Vec<&str>)>,
-        candidate: &String,
-    ) -> Option<IdentifierType> {
-        let mut patterns_as_iter = sorted_patterns.iter();
-        while let Some((i_type, patterns)) = patterns_as_iter.next() {
-            for pattern in patterns {
-                if &&candidate[..] == pattern {
-                    return Some(*i_type);
-                }
+    /// Adds the brackets around the argument of a function called without them, like
+    /// `sin 2`.
+    fn add_implicit_brackets(&mut self, next: Token) {
+        let prev = match self.previous.take() {
+            Some(prev) => prev,
+            None => {
+                self.previous = Some(next);
+                return;
             }
+        };
+
+        if prev.r#type == TokenType::Identifier(IdentifierType::Function)
+            && next.r#type != TokenType::OpeningBracket
+        {
+            if next.r#type == TokenType::Literal
+                || next.r#type == TokenType::Identifier(IdentifierType::Var)
+            {
+                let warning = WarningKind::ImplicitBrackets {
+                    func_name: prev.value.clone(),
+                };
+                let (position, length) = (prev.position, prev.length);
+                self.report(
+                    Step::ImplicitBrackets,
+                    Problem::Warning(warning),
+                    position,
+                    length,
+                );
+                let position = next.position;
+                self.add_implicit_multiplications(prev);
+                self.add_implicit_multiplications(
+                    Token::new(TokenType::OpeningBracket, 1, "").at(position),
+                );
+                self.add_implicit_multiplications(next);
+                self.add_implicit_multiplications(
+                    Token::new(TokenType::ClosingBracket, 1, "").at(position),
+                );
+                return;
+            }
+            if next.r#type != TokenType::Comma && next.r#type != TokenType::ClosingBracket {
+                // When recovering, the function is read as a reference.
+                let error = ErrorType::MissingFunctionParameters {
+                    func_name: prev.value.clone(),
+                };
+                let (position, length) = (prev.position, prev.length);
+                self.report(
+                    Step::ImplicitBrackets,
+                    Problem::Error(error),
+                    position,
+                    length,
+                );
+            }
+            // A function passed as an argument to another one is referenced by name.
+            self.add_implicit_multiplications(
+                Token::new(
+                    TokenType::Identifier(IdentifierType::Var),
+                    prev.length,
+                    &prev.value,
+                )
+                .at(prev.position),
+            );
+        } else {
+            self.add_implicit_multiplications(prev);
         }
-        None
+        self.previous = Some(next);
     }
 
-    // In order of priority:
-    // Built-in functions
-    // Built-in consts
-    // User-defined functions
-    // User-defined vars
-    let patterns = vec![
-        (
-            IdentifierType::Function,
-            builtin::get_built_in_functions_vec()
-                .iter()
-                .map(|x| x.func_identifier)
-                .collect::<Vec<&str>>(),
-        ),
-        (
-            IdentifierType::Var,
-            builtin::get_built_in_consts_map()
-                .iter()
-                .map(|x| x.0)
-                .collect::<Vec<&str>>(),
-        ),
-        (
-            IdentifierType::Function,
-            context
-                .functions
-                .iter()
-                .map(|x| &x.0[..])
-                .collect::<Vec<&str>>(),
-        ),
-        (
-            IdentifierType::Var,
-            context
-                .variables
-                .iter()
-                .map(|x| &x.0[..])
-                .collect::<Vec<&str>>(),
-        ),
-    ];
-
-    let mut to_identify = input.to_owned();
-    let mut current;
-    while to_identify.len() > 0 {
-        current = to_identify.clone();
-        to_identify = "".to_owned();
-
-        if current.is_empty() {
-            break;
-        }
-
-        loop {
-            let categorized = try_to_categorize(&patterns, &current);
-            match categorized {
-                Some(identifier) => {
-                    out.push((current, identifier));
-                    break;
-                }
-                None => {
-                    if current.len() == 1 {
-                        out.push((current.clone(), IdentifierType::Unknown));
-                        break;
-                    } else {
-                        // Remove one char from current and push it to to_identify
-                        to_identify.insert(0, current.chars().last().unwrap());
-                        current = current[0..current.len() - 1].to_owned();
-                    }
+    /// Adds the multiplications written implicitly, like in `2x`.
+    fn add_implicit_multiplications(&mut self, token: Token) {
+        use IdentifierType::*;
+        use TokenType::*;
+
+        if let Some(previous) = self.stream.last() {
+            let previous_token_type = previous.r#type;
+            let current_type = token.r#type;
+            // Add token '*' between:
+            // literal-bracket: 2(4) or (4)2
+            if previous_token_type == Literal               && current_type == OpeningBracket
+                || previous_token_type == ClosingBracket        && current_type == Literal
+                // literal-literal
+                || previous_token_type == Literal               && current_type == Literal
+                // bracket-bracket: (2)(4)
+                || previous_token_type == ClosingBracket        && current_type == OpeningBracket
+                // bracket-any identifier: (2)pi
+                || previous_token_type == ClosingBracket        && matches!(current_type, Identifier(_))
+                // postfix-operand: 3!2 or n!(n-1)
+                || previous_token_type.is_postfix_operator()   && matches!(current_type, Literal | OpeningBracket | Identifier(_))
+                // var-bracket
+                || previous_token_type == Identifier(Var)       && current_type == OpeningBracket
+                // literal-any identifier: 2pi
+                || previous_token_type == Literal               && matches!(current_type, Identifier(_))
+                // any identifier-literal
+                || matches!(previous_token_type, Identifier(_)) && current_type == Literal
+                // any identifier-any identifier
+                || matches!(previous_token_type, Identifier(_)) && matches!(current_type, Identifier(_))
+            {
+                // A number followed by `i` is an imaginary number, not an ambiguous product.
+                if previous_token_type != Literal || token.value != "i" {
+                    let warning = Problem::Warning(WarningKind::ImplicitMultiplication);
+                    self.report(Step::ImplicitMultiplications, warning, token.position, 0);
                 }
+                self.stream.push(Token::new(Star, 1, "").at(token.position));
             }
         }
-    }
-
-    out
-}
 
-fn add_implicit_multiplications(stream: &TokenStream) -> TokenStream {
-    // Return if empty.
-    if stream.len() == 0 {
-        return stream.clone();
+        self.stream.push(token);
     }
 
-    let mut out_stream: TokenStream = vec![stream[0].clone()];
+    /// Hands the tokens left to the steps, and reports the problems found.
+    fn finish(mut self, diagnostics: &mut Diagnostics) -> EvalResult<TokenStream> {
+        if self.read.last().map(|last| last.r#type)
+            == Some(TokenType::Identifier(IdentifierType::Unknown))
+        {
+            self.read_keyword_operators();
+        }
+        self.hand_over(true);
+        if let Some(last) = self.last.take() {
+            self.join_operators(last);
+        }
+        self.flush_operators(true);
+        if let Some(identifier) = self.identifier.take() {
+            self.join_literals(identifier);
+        }
+        if let Some(literal) = self.literal.take() {
+            self.format_identifiers(literal);
+        }
+        if let Some(unknown) = self.unknown.take() {
+            // The last unknown identifier is a variable.
+            self.add_implicit_brackets(
+                Token::new(
+                    TokenType::Identifier(IdentifierType::Var),
+                    unknown.value.len(),
+                    &unknown.value[..],
+                )
+                .at(unknown.position),
+            );
+        }
+        if let Some(last) = self.previous.take() {
+            self.add_implicit_multiplications(last);
+        }
 
-    let mut previous_token_type = stream[0].r#type;
-    let mut index = 1;
+        self.problems.sort_by_key(|(step, ..)| *step);
+        for (_, problem, position, length) in self.problems {
+            match problem {
+                Problem::Error(error) => diagnostics.recover(error, position, length, ())?,
+                Problem::Warning(kind) => diagnostics.warn(kind, position, length),
+            }
+        }
 
-    while index < stream.len() {
-        let current_type = stream[index].r#type;
+        Ok(self.stream)
+    }
+}
 
-        use IdentifierType::*;
-        use TokenType::*;
-        // Add token '*' between:
-        // literal-bracket: 2(4) or (4)2
-        if previous_token_type == Literal               && current_type == OpeningBracket
-            || previous_token_type == ClosingBracket        && current_type == Literal
-            // literal-literal
-            || previous_token_type == Literal               && current_type == Literal
-            // bracket-bracket: (2)(4)
-            || previous_token_type == ClosingBracket        && current_type == OpeningBracket
-            // bracket-any identifier: (2)pi
-            || previous_token_type == ClosingBracket        && matches!(current_type, Identifier(_))
-            // var-bracket
-            || previous_token_type == Identifier(Var)       && current_type == OpeningBracket
-            // literal-any identifier: 2pi
-            || previous_token_type == Literal               && matches!(current_type, Identifier(_))
-            // any identifier-literal
-            || matches!(previous_token_type, Identifier(_)) && current_type == Literal
-            // any identifier-any identifier
-            || matches!(previous_token_type, Identifier(_)) && matches!(current_type, Identifier(_))
+/// Returns true if the tokens following a percentage start with an operand, so that the
+/// percentage is a modulo.
+fn starts_with_operand(rest: &[Token]) -> bool {
+    match rest {
+        // The `..` and `!=` operators are not joined yet, so their tokens do not start
+        // an operand.
+        [next, following, ..]
+            if next.r#type == TokenType::Dot && following.r#type == TokenType::Dot =>
         {
-            out_stream.push(Token::new(TokenType::Star, 1, ""));
+            false
         }
-
-        out_stream.push(stream[index].clone());
-        previous_token_type = current_type;
-        index += 1;
+        next if is_not_equal(next) => false,
+        // Prefix operators at the end are not followed by an operand.
+        rest if rest.iter().all(|token| token.r#type.is_unary_operator()) => false,
+        [next, ..] => starts_operand(next),
+        [] => false,
     }
+}
 
-    out_stream
+/// Returns the char written by a superscript, like `2` for `²`.
+fn from_superscript(character: char) -> Option<char> {
+    Some(match character {
+        '⁰' => '0',
+        '¹' => '1',
+        '²' => '2',
+        '³' => '3',
+        '⁴' => '4',
+        '⁵' => '5',
+        '⁶' => '6',
+        '⁷' => '7',
+        '⁸' => '8',
+        '⁹' => '9',
+        '⁻' => '-',
+        _ => return None,
+    })
 }
 
-fn remove_whitespaces(string: &String) -> String {
-    string.replace(" ", "")
+/// Returns true if the token can be the last one of an operand.
+pub(crate) fn ends_operand(token: &Token) -> bool {
+    use TokenType::*;
+    matches!(
+        token.r#type,
+        Literal
+            | Dot
+            | Identifier(IdentifierType::Var | IdentifierType::Unknown)
+            | ClosingBracket
+            | ClosingSquareBracket
+            | Factorial
+            | DoubleFactorial
+            | Percent
+    )
 }
 
-/// Joins all identifiers.
-fn join_identifiers(stream: &TokenStream) -> EvalResult<TokenStream> {
-    let mut joined_stream: TokenStream = vec![];
+/// Returns true if the token can be the first one of an operand.
+fn starts_operand(token: &Token) -> bool {
+    use TokenType::*;
+    matches!(
+        token.r#type,
+        Literal
+            | Dot
+            | Identifier(_)
+            | OpeningBracket
+            | OpeningSquareBracket
+            | Minus
+            | Exclamation
+            | Tilde
+    )
+}
 
-    let mut is_previous_identifier: bool = false;
-    // Iterate over the stream and join any literal
-    for token in stream {
-        let is_identifier = token.r#type == TokenType::Identifier(IdentifierType::Unknown);
+/// Returns true if the tokens start with the `!` and `=` of a `!=`, written without
+/// spaces.
+fn is_not_equal(tokens: &[Token]) -> bool {
+    let joined_equal = |index: usize| {
+        tokens.get(index).is_some_and(|token| {
+            token.r#type == TokenType::Equal && is_joined(&tokens[index - 1], token)
+        })
+    };
+    tokens.first().map(|token| token.r#type) == Some(TokenType::Exclamation)
+        && joined_equal(1)
+        && !joined_equal(2)
+}
 
-        if is_identifier && is_previous_identifier {
-            // Join with the previous token and avoid pushing the current one.
-            let previous_token = joined_stream.last_mut().unwrap();
-            previous_token.join_with(token, TokenType::Identifier(IdentifierType::Unknown));
-        } else {
-            joined_stream.push(token.clone());
-        }
+/// Returns true if there is no space between the tokens.
+fn is_joined(first: &Token, second: &Token) -> bool {
+    first.position + first.length == second.position
+}
 
-        is_previous_identifier = is_identifier;
-    }
+/// The operators written with two chars, and the chars they are made of. Where two
+/// of them overlap, the one listed first is joined.
+const JOINED_OPERATORS: [(TokenType, TokenType, TokenType); 11] = {
+    use TokenType::*;
+    [
+        (DoubleLessThan, LessThan, LessThan),
+        (DoubleGreaterThan, GreaterThan, GreaterThan),
+        (DoubleSlash, Slash, Slash),
+        (LessOrEqualTo, LessThan, Equal),
+        (GreaterOrEqualTo, GreaterThan, Equal),
+        (DoubleEqual, Equal, Equal),
+        (NotEqual, Exclamation, Equal),
+        (DoubleAnd, And, And),
+        (DoubleOr, Or, Or),
+        (DoubleDot, Dot, Dot),
+        (ColonEqual, Colon, Equal),
+    ]
+};
 
-    Ok(joined_stream)
+/// Returns the operator made of two chars, with its index in `JOINED_OPERATORS`.
+fn joined_operator(first: TokenType, second: TokenType) -> Option<(usize, TokenType)> {
+    JOINED_OPERATORS
+        .iter()
+        .position(|&(_, a, b)| (a, b) == (first, second))
+        .map(|index| (index, JOINED_OPERATORS[index].0))
 }
 
-/// Join numbers handling commas.
-fn join_literals(stream: &TokenStream) -> EvalResult<TokenStream> {
-    let mut joined_stream: TokenStream = vec![];
-
-    let mut is_previous_literal: bool = false;
-    // This is reset for every number
-    let mut comma_found = false;
-    // Iterate over the stream and join any literal
-    for token in stream {
-        let is_comma = token.r#type == TokenType::Dot;
-        let is_literal = token.r#type == TokenType::Literal || is_comma;
-
-        if is_literal || is_comma {
-            if is_comma {
-                // Allow only one comma
-                if comma_found {
-                    return Err(ErrorType::InvalidTokenPosition {
-                        token: token.r#type,
-                    });
-                }
-                comma_found = true;
-            }
+/// Returns the index in `JOINED_OPERATORS` of an operator made of two chars, with the
+/// chars it is made of.
+fn joined_parts(joined: TokenType) -> Option<(usize, TokenType, TokenType)> {
+    JOINED_OPERATORS
+        .iter()
+        .position(|&(operator, _, _)| operator == joined)
+        .map(|index| (index, JOINED_OPERATORS[index].1, JOINED_OPERATORS[index].2))
+}
 
-            if is_previous_literal {
-                // Join with the previous token and avoid pushing the current one.
-                let previous_token = joined_stream.last_mut().unwrap();
-                previous_token.join_with(token, TokenType::Literal);
-            } else {
-                joined_stream.push(token.clone());
+/// The known identifiers, with their type.
+struct IdentifierNames<'a>(HashMap<&'a str, IdentifierType>);
+
+impl<'a> IdentifierNames<'a> {
+    fn new(context: &'a Context) -> Self {
+        let mut names = HashMap::new();
+        let mut add = |identifiers: Vec<&'a str>, i_type: IdentifierType| {
+            for identifier in identifiers {
+                names.entry(identifier).or_insert(i_type);
             }
-        } else {
-            // Reset temp vars if it is not a literal
-            comma_found = false;
+        };
 
-            joined_stream.push(token.clone());
+        // In order of priority:
+        // Built-in functions
+        // Commands
+        // Built-in consts
+        // User-defined functions
+        // User-defined vars
+        // Parameters of the function being evaluated
+        add(
+            builtin::get_built_in_functions_vec()
+                .iter()
+                .map(|x| x.func_identifier)
+                .collect(),
+            IdentifierType::Function,
+        );
+        add(builtin::COMMANDS.to_vec(), IdentifierType::Function);
+        add(
+            builtin::get_built_in_consts_map()
+                .iter()
+                .map(|x| x.0)
+                .collect(),
+            IdentifierType::Var,
+        );
+        add(
+            context.functions.iter().map(|x| &x.0[..]).collect(),
+            IdentifierType::Function,
+        );
+        add(
+            context
+                .variables
+                .keys()
+                .chain(context.values.keys())
+                .map(|x| &x[..])
+                .collect(),
+            IdentifierType::Var,
+        );
+        add(context.local_identifiers(), IdentifierType::Var);
+
+        Self(names)
+    }
+
+    /// Returns the type of an identifier read as a whole, without splitting it.
+    fn identifier_type(&self, identifier: &str) -> IdentifierType {
+        match &self.split(String::from(identifier))[..] {
+            [(name, i_type)] if name == identifier => *i_type,
+            _ => IdentifierType::Unknown,
+        }
+    }
+
+    /// Given a string, returns a vector with all identified vars and function.
+    fn split(&self, input: String) -> Vec<(String, IdentifierType)> {
+        let mut out = vec![];
+
+        let mut to_identify = &input[..];
+        while let Some(first) = to_identify.chars().next() {
+            // Take the longest known name, or else a single unknown char.
+            let (current, identifier) = to_identify
+                .char_indices()
+                .map(|(index, c)| &to_identify[..index + c.len_utf8()])
+                .rev()
+                .find_map(|current| self.0.get(current).map(|identifier| (current, *identifier)))
+                .unwrap_or((&to_identify[..first.len_utf8()], IdentifierType::Unknown));
+
+            out.push((current.to_owned(), identifier));
+            to_identify = &to_identify[current.len()..];
         }
 
-        is_previous_literal = is_literal;
+        out
     }
+}
 
-    Ok(joined_stream)
+/// Given a string, returns a vector with all identified vars and function.
+pub fn split_into_identifiers(input: String, context: &Context) -> Vec<(String, IdentifierType)> {
+    IdentifierNames::new(context).split(input)
 }
 
 /// Returns the char corresponding token.
@@ -458,11 +1110,30 @@ fn tokenize(character: &char) -> EvalResult<Token> {
         '&' => Token::new(TokenType::And, 1, ""),
         '|' => Token::new(TokenType::Or, 1, ""),
         '!' => Token::new(TokenType::Exclamation, 1, ""),
+        '±' => Token::new(TokenType::PlusMinus, 1, ""),
+        '×' | '·' => Token::new(TokenType::Star, 1, ""),
+        '÷' => Token::new(TokenType::Slash, 1, ""),
+        '−' => Token::new(TokenType::Minus, 1, ""),
+        '≤' => Token::new(TokenType::LessOrEqualTo, 1, ""),
+        '≥' => Token::new(TokenType::GreaterOrEqualTo, 1, ""),
+        '≠' => Token::new(TokenType::NotEqual, 1, ""),
+        '?' => Token::new(TokenType::Question, 1, ""),
+        ':' => Token::new(TokenType::Colon, 1, ""),
+        '~' => Token::new(TokenType::Tilde, 1, ""),
 
         '.' => Token::new(TokenType::Dot, 1, "."),
 
         '(' => Token::new(TokenType::OpeningBracket, 1, ""),
         ')' => Token::new(TokenType::ClosingBracket, 1, ""),
+        '[' => Token::new(TokenType::OpeningSquareBracket, 1, ""),
+        ']' | '⌋' | '⌉' => Token::new(TokenType::ClosingSquareBracket, 1, ""),
+
+        '√' => Token::new(TokenType::Identifier(IdentifierType::Function), 1, "sqrt"),
+        '∛' => Token::new(TokenType::Identifier(IdentifierType::Function), 1, "cbrt"),
+        'π' => Token::new(TokenType::Identifier(IdentifierType::Var), 1, "pi"),
+        'τ' => Token::new(TokenType::Identifier(IdentifierType::Var), 1, "tau"),
+        'φ' => Token::new(TokenType::Identifier(IdentifierType::Var), 1, "phi"),
+        '∞' => Token::new(TokenType::Identifier(IdentifierType::Var), 1, "inf"),
         other => {
             let as_string = format!("{}", other);
             if other.is_numeric() {
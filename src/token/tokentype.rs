@@ -10,6 +10,17 @@ pub enum IdentifierType {
     Unknown,
 }
 
+/// How operators with the same precedence are grouped.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Associativity {
+    /// `a - b - c` is `(a - b) - c`.
+    Left,
+    /// `a ^ b ^ c` is `a ^ (b ^ c)`.
+    Right,
+    /// `a < b <= c` is `a < b && b <= c`.
+    Chain,
+}
+
 /// Contains all the possible input tokens type.
 #[derive(Debug, PartialEq, Clone, Copy)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -40,6 +51,14 @@ pub enum TokenType {
     And,
     /// An or '|' character.
     Or,
+    /// A plus-minus sign '±' character.
+    PlusMinus,
+    /// A question mark '?' character.
+    Question,
+    /// A colon ':' character.
+    Colon,
+    /// A tilde '~' character.
+    Tilde,
 
     // TO BE ASSEMBLED
     /// A less-than sign followed by an equal character.
@@ -54,14 +73,40 @@ pub enum TokenType {
     DoubleOr,
     /// An exclamation point followed by an equal character.
     NotEqual,
+    /// Two dot '.' characters.
+    DoubleDot,
+    /// A plus '+' character followed by a minus '-' character.
+    Uncertainty,
+    /// A colon ':' followed by an equal '=' character.
+    ColonEqual,
+    /// Two slash '/' characters.
+    DoubleSlash,
+    /// Two less-than signs '<' characters.
+    DoubleLessThan,
+    /// Two greater-than signs '>' characters.
+    DoubleGreaterThan,
+    /// The `xor` keyword.
+    Xor,
 
     /// An exclamation point '!' character.
     Exclamation,
 
+    // POSTFIX
+    /// An exclamation point '!' character following an operand.
+    Factorial,
+    /// Two exclamation point '!' characters following an operand.
+    DoubleFactorial,
+    /// A percentage '%' character following an operand and not followed by another one.
+    Percent,
+
     /// An opening bracket '(' character.
     OpeningBracket,
     /// A closing bracket ')' character.
     ClosingBracket,
+    /// An opening square bracket '[' character.
+    OpeningSquareBracket,
+    /// A closing square bracket ']' character.
+    ClosingSquareBracket,
 
     /// A dot '.' character.
     Dot,
@@ -78,26 +123,38 @@ impl TokenType {
         *self == TokenType::Literal ||  // A number
         self.is_binary_operator() || // An operator
         self.is_unary_operator() ||
+        self.is_postfix_operator() ||
         self.is_union_operator()
     }
 
     pub fn is_binary_operator(&self) -> bool {
         match self {
             Plus | Minus | Star | Slash | Equal | Caret | Percentage | LessThan | GreaterThan
-            | LessOrEqualTo | GreaterOrEqualTo | DoubleAnd | DoubleOr | DoubleEqual | NotEqual => {
-                true
-            }
+            | LessOrEqualTo | GreaterOrEqualTo | DoubleAnd | DoubleOr | DoubleEqual | NotEqual
+            | PlusMinus | DoubleDot | Uncertainty | Question | Colon | ColonEqual | And | Or
+            | Xor | DoubleSlash | DoubleLessThan | DoubleGreaterThan => true,
             _ => false,
         }
     }
 
     pub fn is_unary_operator(&self) -> bool {
         match self {
-            Minus | Exclamation => true,
+            Minus | Exclamation | Tilde => true,
             _ => false,
         }
     }
 
+    pub fn is_postfix_operator(&self) -> bool {
+        matches!(self, Factorial | DoubleFactorial | Percent)
+    }
+
+    pub fn is_comparison_operator(&self) -> bool {
+        matches!(
+            self,
+            LessThan | GreaterThan | LessOrEqualTo | GreaterOrEqualTo | DoubleEqual | NotEqual
+        )
+    }
+
     pub fn is_union_operator(&self) -> bool {
         match self {
             Comma => true,
@@ -109,18 +166,48 @@ impl TokenType {
         Ok(match self {
             Literal => 300,
             Identifier(_) => 200,
+            Factorial | DoubleFactorial | Percent => 95,
             Caret => 90,
-            Exclamation => 80,
-            Star | Slash | Percentage => 70,
+            Exclamation | Tilde => 80,
+            Star | Slash | Percentage | DoubleSlash => 70,
+            PlusMinus | Uncertainty => 65,
             Plus | Minus => 60,
-            LessThan | LessOrEqualTo | GreaterThan | GreaterOrEqualTo => 50,
-            DoubleEqual | NotEqual => 40,
+            DoubleLessThan | DoubleGreaterThan => 59,
+            And => 58,
+            Xor => 57,
+            Or => 56,
+            DoubleDot => 55,
+            LessThan | LessOrEqualTo | GreaterThan | GreaterOrEqualTo | DoubleEqual | NotEqual => {
+                50
+            }
             DoubleAnd => 30,
             DoubleOr => 20,
+            Question | Colon => 15,
             Comma => 10,
-            Equal => 0,
+            Equal | ColonEqual => 0,
 
             _ => return Err(ErrorType::NotAnOperator { token: *self }),
         })
     }
+
+    /// How the operator is grouped with operators with the same precedence.
+    pub fn associativity(&self) -> Associativity {
+        match self {
+            Caret | Question | Colon => Associativity::Right,
+            LessThan | LessOrEqualTo | GreaterThan | GreaterOrEqualTo | DoubleEqual | NotEqual => {
+                Associativity::Chain
+            }
+            _ => Associativity::Left,
+        }
+    }
+
+    /// The precedence of an operator applied to the operand on its right only, like
+    /// `-` in `-2`. Negation binds tighter than `*` and looser than `^`, so `-2^2` is
+    /// `-(2^2)` and `-7 // 2` is `(-7) // 2`.
+    pub fn prefix_precedence(&self) -> EvalResult<u16> {
+        Ok(match self {
+            Minus => 75,
+            _ => self.precedence()?,
+        })
+    }
 }
@@ -1,10 +1,10 @@
-use itertools::Itertools;
-
 use crate::{
+    context::Context,
     objects::Expression,
-    out::{ErrorType, EvalResult},
+    out::{Diagnostics, ErrorType, EvalResult},
     token::{
-        tokentype::{IdentifierType, TokenType},
+        ends_operand,
+        tokentype::{Associativity, IdentifierType, TokenType},
         Token, TokenStream,
     },
     value::Value,
@@ -17,405 +17,448 @@ pub type Node = Expression;
 #[derive(Debug)]
 pub struct Tree(pub Node);
 
-/// Builds an expression tree, effectively parsing the token stream.
-pub fn build_tree(stream: TokenStream) -> EvalResult<Tree> {
-    check_brackets(&stream)?;
-    // Sort by precedence
-    let mut sorted_node_tokens = sort_node_tokens(&stream)?;
-
-    Ok(Tree(create_node(
-        &mut sorted_node_tokens,
-        &stream,
-        None,
-        (0, stream.len()),
-    )?))
+/// Builds an expression tree, effectively parsing the token stream. Problems are
+/// reported to `diagnostics`, and replaced by `Node::Invalid` when recovering.
+pub fn build_tree(
+    stream: TokenStream,
+    context: &Context,
+    diagnostics: &mut Diagnostics,
+) -> EvalResult<Tree> {
+    let stream = check_brackets(stream, diagnostics)?;
+    let mut parser = Parser::new(stream, context, diagnostics);
+    let root = parser.parse_expression(None)?;
+    // Tokens left out of the tree are misplaced operators
+    parser.skip_misplaced()?;
+    // Empty input
+    Ok(Tree(root.unwrap_or(Node::Literal(Value::Int(0)))))
 }
 
-fn check_brackets(stream: &TokenStream) -> EvalResult<()> {
-    let mut depth = 0;
+/// Checks that brackets are balanced. When recovering, brackets closing nothing are
+/// removed and the missing ones are added at the end of the stream.
+fn check_brackets(stream: TokenStream, diagnostics: &mut Diagnostics) -> EvalResult<TokenStream> {
+    let mut checked = vec![];
+    let mut open_brackets = vec![];
     for token in stream {
         match token.r#type {
-            TokenType::OpeningBracket => depth += 1,
-            TokenType::ClosingBracket => {
-                depth -= 1;
-                if depth < 0 {
-                    // Also check for invalid brackets
-                    return Err(ErrorType::InvalidClosingBracket);
+            TokenType::OpeningBracket => open_brackets.push(token.clone()),
+            TokenType::ClosingBracket => match open_brackets.pop() {
+                Some(_) => (),
+                // Also check for invalid brackets
+                None => {
+                    diagnostics.recover(
+                        ErrorType::InvalidClosingBracket,
+                        token.position,
+                        token.length,
+                        (),
+                    )?;
+                    continue;
                 }
-            }
+            },
             _ => (),
         }
+        checked.push(token);
     }
-    match depth {
-        0 => Ok(()),
-        _ => return Err(ErrorType::MissingClosingBracket),
+
+    for bracket in open_brackets {
+        diagnostics.recover(
+            ErrorType::MissingClosingBracket,
+            bracket.position,
+            bracket.length,
+            (),
+        )?;
+        checked.push(Token {
+            r#type: TokenType::ClosingBracket,
+            length: 0,
+            value: String::new(),
+            ..bracket
+        });
     }
+
+    Ok(checked)
 }
 
-/// Contains the token info inside the token stream.
-#[derive(Clone, PartialEq, Debug)]
-struct TokenInfo {
-    /// The contained token.
-    pub token: Token,
-    /// The token position inside the stream.
-    pub position: usize,
-    /// The brackets depth at the token position.
-    pub depth: u16,
-    /// The token precedence.
-    pub precedence: u16,
+/// The operator an expression is read for: its precedence and how it is grouped with the
+/// operators with the same precedence. `None` for expressions in brackets.
+type Parent = Option<(u16, Associativity)>;
+
+/// Reads the tokens left to right, building every operand before looking at the operator
+/// that follows it, so that the tree is built in a single pass over the stream.
+struct Parser<'a> {
+    /// The tokens creating nodes and the brackets.
+    tokens: TokenStream,
+    /// Whether each token follows the end of an operand in the stream, which makes
+    /// operators like `-` binary even after empty brackets.
+    after_operand: Vec<bool>,
+    /// The position of the next token to read.
+    index: usize,
+    /// The context measurements written as literals get their source from.
+    context: &'a Context,
+    diagnostics: &'a mut Diagnostics,
 }
 
-/// Sorts all possible tokens that create nodes.
-fn sort_node_tokens(stream: &TokenStream) -> EvalResult<Vec<TokenInfo>> {
-    let mut sorted = vec![];
-    let mut depth = 0;
-    for (position, token) in stream.iter().enumerate() {
-        if token.r#type == TokenType::OpeningBracket {
-            depth += 1;
-        } else if token.r#type == TokenType::ClosingBracket {
-            depth -= 1;
-        } else if token.r#type.is_expression() {
-            let precedence = token.r#type.precedence()?;
-            sorted.push(TokenInfo {
-                token: token.clone(),
-                position,
-                depth,
-                precedence,
-            });
+impl<'a> Parser<'a> {
+    fn new(stream: TokenStream, context: &'a Context, diagnostics: &'a mut Diagnostics) -> Self {
+        let mut tokens: TokenStream = vec![];
+        let mut after_operand = vec![];
+        let mut previous_ends_operand = false;
+        for token in stream {
+            let ends_operand = ends_operand(&token);
+            let r#type = token.r#type;
+            let mut last_types = tokens.iter().rev().map(|x| x.r#type);
+            // Empty brackets are skipped, unless holding the parameters of a function
+            let closes_empty_brackets = r#type == TokenType::ClosingBracket
+                && last_types.next() == Some(TokenType::OpeningBracket)
+                && last_types.next() != Some(TokenType::Identifier(IdentifierType::Function));
+            if closes_empty_brackets {
+                tokens.pop();
+                after_operand.pop();
+            } else if r#type.is_expression()
+                || r#type == TokenType::OpeningBracket
+                || r#type == TokenType::ClosingBracket
+            {
+                after_operand.push(previous_ends_operand);
+                tokens.push(token);
+            }
+            previous_ends_operand = ends_operand;
+        }
+        Self {
+            tokens,
+            after_operand,
+            index: 0,
+            context,
+            diagnostics,
         }
     }
-    sorted.sort_by_key(|v| (v.depth, v.precedence, -(v.position as i16)));
 
-    Ok(sorted)
-}
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index)
+    }
 
-/// Consumes the token with the provided stream position and builds a node. It finds
-/// eventual required node parameters inside the same vectors within the specified range.
-fn create_node(
-    sorted_node_tokens: &mut Vec<TokenInfo>,
-    stream: &TokenStream,
-    position: Option<usize>,
-    range: (usize, usize),
-) -> EvalResult<Node> {
-    let index = match position {
-        Some(value) => {
-            // Find the node in the sorted ones with the corresponding index
-            match sorted_node_tokens.iter().position(|x| x.position == value) {
-                Some(position) => position,
-                None => {
-                    return Err(ErrorType::InternalError {
-                        message: String::from("trying to remove non-existing token"),
-                    });
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.index).cloned();
+        self.index += 1;
+        token
+    }
+
+    /// Reports a problem found at `token`, returning `fallback` to continue with when
+    /// recovering.
+    fn recover<T>(&mut self, error: ErrorType, token: &Token, fallback: T) -> EvalResult<T> {
+        self.diagnostics
+            .recover(error, token.position, token.length, fallback)
+    }
+
+    /// Reads an expression made of the operators binding tighter than `parent`, so that
+    /// the following operator is applied to the whole expression. Returns `None` if no
+    /// operand starts here.
+    fn parse_expression(&mut self, parent: Parent) -> EvalResult<Option<Node>> {
+        let mut node = match self.parse_operand()? {
+            Some(node) => node,
+            None => match self.peek().cloned() {
+                // An operator missing its left operand, like `*` in `2 + * 3`
+                Some(token)
+                    if (is_infix(token.r#type) || token.r#type.is_postfix_operator())
+                        && binds(token.r#type, parent)? =>
+                {
+                    if token.r#type.is_union_operator() {
+                        let at = self.last_union_operator(&token);
+                        self.recover(ErrorType::EmptyUnion, &at, Node::Invalid)?
+                    } else {
+                        let error = ErrorType::MissingOperatorArgument {
+                            token: token.r#type,
+                        };
+                        self.recover(error, &token, Node::Invalid)?
+                    }
                 }
+                _ => return Ok(None),
+            },
+        };
+
+        while let Some(token) = self.peek().cloned() {
+            let r#type = token.r#type;
+            if !(is_infix(r#type) || r#type.is_postfix_operator()) || !binds(r#type, parent)? {
+                break;
             }
+            self.index += 1;
+            node = if r#type.is_postfix_operator() {
+                Node::Unary(r#type, Box::new(node))
+            } else if r#type.is_union_operator() {
+                self.parse_union(node, token)?
+            } else if r#type == TokenType::Question {
+                self.parse_conditional(node, token)?
+            } else if r#type.associativity() == Associativity::Chain {
+                self.parse_comparison(node, token)?
+            } else {
+                let rhs =
+                    self.parse_expression(Some((r#type.precedence()?, r#type.associativity())))?;
+                let rhs = self.expect_operand(rhs, &token)?;
+                build_binary_operator(node, r#type, rhs, self.context)?
+            };
         }
-        None => 0,
-    };
-
-    if sorted_node_tokens.len() == 0 {
-        if position == None {
-            // First iteration, so input is empty
-            return Ok(Node::Literal(Value::Int(0)));
-        } else {
-            // This is an error
-            return Err(ErrorType::InternalError {
-                message: String::from("trying to remove non-existing token"),
-            });
-        }
+
+        Ok(Some(node))
     }
 
-    let token_info = sorted_node_tokens.remove(index);
-
-    // Get the node type.
-    if token_info.token.r#type.is_binary_operator() && token_info.token.r#type.is_unary_operator() {
-        // In this case we need to check for both unary and binary
-        match build_binary_operator(sorted_node_tokens, stream, &token_info, range) {
-            Ok(node) => return Ok(node),
-            Err(_) => {
-                return Ok(build_unary_operator(
-                    sorted_node_tokens,
-                    stream,
-                    &token_info,
-                    range,
-                )?)
+    /// Reads a literal, an identifier, an expression in brackets or a prefix operator
+    /// with its operand. Returns `None` if the next token starts none of them.
+    fn parse_operand(&mut self) -> EvalResult<Option<Node>> {
+        let token = match self.peek() {
+            Some(token) => token.clone(),
+            None => return Ok(None),
+        };
+        Ok(Some(match token.r#type {
+            TokenType::OpeningBracket => {
+                self.index += 1;
+                return self.parse_brackets();
             }
-        }
-    } else if token_info.token.r#type.is_binary_operator() {
-        // Try just binary
-        return Ok(build_binary_operator(
-            sorted_node_tokens,
-            stream,
-            &token_info,
-            range,
-        )?);
-    } else if token_info.token.r#type.is_unary_operator() {
-        // Try just unary
-        return Ok(build_unary_operator(
-            sorted_node_tokens,
-            stream,
-            &token_info,
-            range,
-        )?);
-    } else if token_info.token.r#type.is_union_operator() {
-        return Ok(build_union_operator(
-            sorted_node_tokens,
-            stream,
-            &token_info,
-            range,
-        )?);
-    } else {
-        // Match for literals, constants, functions and variables.
-        match token_info.token.r#type {
             TokenType::Literal => {
-                return Ok(Node::Literal(Value::from_string(token_info.token.value)?))
+                self.index += 1;
+                match Value::from_string(token.value.clone()) {
+                    Ok(value) => Node::Literal(value),
+                    Err(error) => self.recover(error, &token, Node::Invalid)?,
+                }
             }
             TokenType::Identifier(i_type) => {
-                let val = &token_info.token.value;
+                self.index += 1;
                 match i_type {
-                    IdentifierType::Var => Ok(Node::Var(val.clone())),
-                    IdentifierType::Function => Ok(Node::Func(
-                        val.clone(),
-                        get_function_parameters(sorted_node_tokens, stream, &token_info)?,
-                    )),
-                    IdentifierType::Unknown => Err(ErrorType::UnknownToken { token: val.clone() }),
+                    IdentifierType::Var => Node::Var(token.value),
+                    IdentifierType::Function => {
+                        let parameters = self.parse_function_parameters(&token)?;
+                        Node::Func(token.value, parameters)
+                    }
+                    IdentifierType::Unknown => self.recover(
+                        ErrorType::UnknownToken {
+                            token: token.value.clone(),
+                        },
+                        &token,
+                        Node::Invalid,
+                    )?,
                 }
             }
-            _ => Err(ErrorType::InternalError {
-                message: format!("token `{}` is not a valid node", token_info.token),
-            }),
-        }
+            r#type
+                if r#type.is_unary_operator()
+                    && !(r#type.is_binary_operator() && self.after_operand[self.index]) =>
+            {
+                self.index += 1;
+                let parent = Some((r#type.prefix_precedence()?, Associativity::Left));
+                let operand = self.parse_expression(parent)?;
+                Node::Unary(r#type, Box::new(self.expect_operand(operand, &token)?))
+            }
+            _ => return Ok(None),
+        }))
     }
-}
 
-/// Builds a unary operator from the provided data.
-fn build_unary_operator(
-    sorted_node_tokens: &mut Vec<TokenInfo>,
-    stream: &TokenStream,
-    token_info: &TokenInfo,
-    range: (usize, usize),
-) -> EvalResult<Node> {
-    Ok(Node::Unary(
-        token_info.token.r#type,
-        Box::new(
-            match get_lowest_precedence_node_in_range(
-                sorted_node_tokens,
-                stream,
-                (token_info.position + 1, range.1),
-            )? {
-                Some(next_node) => next_node,
-                None => {
-                    return Err(ErrorType::MissingOperatorArgument {
-                        token: token_info.token.r#type,
-                    })
-                }
-            },
-        ),
-    ))
-}
+    /// Reads the expression inside brackets, after the opening one.
+    fn parse_brackets(&mut self) -> EvalResult<Option<Node>> {
+        let node = self.parse_expression(None)?;
+        self.skip_misplaced()?;
+        // Closing bracket, added if missing when checking brackets
+        self.next();
+        Ok(node)
+    }
 
-/// Builds a binary operator with the provided data.
-fn build_binary_operator(
-    sorted_node_tokens: &mut Vec<TokenInfo>,
-    stream: &TokenStream,
-    token_info: &TokenInfo,
-    range: (usize, usize),
-) -> EvalResult<Node> {
-    Ok(Node::Binary(
-        Box::new(
-            // Previous node
-            match get_lowest_precedence_node_in_range(
-                sorted_node_tokens,
-                stream,
-                (range.0, token_info.position),
-            )? {
-                Some(previous_node) => previous_node,
-                None => {
-                    return Err(ErrorType::MissingOperatorArgument {
-                        token: token_info.token.r#type,
-                    })
+    /// Reports the tokens left until the end of the current brackets, which are not part
+    /// of the expression, like `~` in `2~`.
+    fn skip_misplaced(&mut self) -> EvalResult<()> {
+        let mut depth = 0;
+        while let Some(token) = self.peek().cloned() {
+            match token.r#type {
+                TokenType::ClosingBracket if depth == 0 => break,
+                TokenType::ClosingBracket => depth -= 1,
+                TokenType::OpeningBracket => depth += 1,
+                r#type => {
+                    let error = ErrorType::InvalidTokenPosition { token: r#type };
+                    self.recover(error, &token, ())?;
                 }
-            },
-        ),
-        token_info.token.r#type,
-        Box::new(
-            // Successive node
-            match get_lowest_precedence_node_in_range(
-                sorted_node_tokens,
-                stream,
-                (token_info.position + 1, range.1),
-            )? {
-                Some(next_node) => next_node,
-                None => {
-                    return Err(ErrorType::MissingOperatorArgument {
-                        token: token_info.token.r#type,
-                    })
-                }
-            },
-        ),
-    ))
-}
+            }
+            self.index += 1;
+        }
+        Ok(())
+    }
 
-/// Get the lowest precedence node in the range. The range is start-inclusive, end-exclusive.
-fn get_lowest_precedence_node_in_range(
-    sorted_node_tokens: &mut Vec<TokenInfo>,
-    stream: &TokenStream,
-    range: (usize, usize),
-) -> EvalResult<Option<Node>> {
-    let candidates: Vec<TokenInfo> = sorted_node_tokens
-        .iter()
-        .filter(|&x| x.position >= range.0 && x.position < range.1)
-        .cloned()
-        .collect();
-
-    if candidates.len() == 0 {
-        Ok(None)
-    } else {
-        Ok(
-            match candidates
-                .iter()
-                .min_by_key(|&x| (x.depth, x.precedence, -(x.position as i16)))
-            {
-                Some(value) => {
-                    // Create the node
-                    Some(create_node(
-                        sorted_node_tokens,
-                        stream,
-                        Some(value.position),
-                        range,
-                    )?)
-                }
-                None => return Err(ErrorType::EmptyBrackets),
-            },
-        )
+    /// Returns the parameters inside the brackets following the function identifier.
+    fn parse_function_parameters(&mut self, func_token: &Token) -> EvalResult<Vec<Box<Node>>> {
+        // Brackets should have all been added during "tokenization" phase.
+        if self.peek().map(|x| x.r#type) != Some(TokenType::OpeningBracket) {
+            return self.recover(
+                ErrorType::MissingFunctionParameters {
+                    func_name: func_token.value.clone(),
+                },
+                func_token,
+                vec![],
+            );
+        }
+        self.index += 1;
+
+        Ok(match self.parse_brackets()? {
+            Some(Node::Union(nodes)) => nodes,
+            Some(node) => vec![Box::new(node)],
+            // A call without arguments, such as `vars()`.
+            None => vec![],
+        })
     }
-}
 
-/// Returns the node contained inside the function brackets.
-fn get_function_parameters(
-    sorted_node_tokens: &mut Vec<TokenInfo>,
-    stream: &TokenStream,
-    func_token: &TokenInfo,
-) -> EvalResult<Vec<Box<Node>>> {
-    let func_pos = func_token.position;
-    // Check if in range
-    let content_node = {
-        if func_pos + 1 < stream.len() {
-            // Check for bracket
-            // Brackets should have all been added during "tokenization" phase.
-            if stream[func_pos + 1].r#type == TokenType::OpeningBracket {
-                // Builds the node inside the brackets
-                match get_lowest_precedence_node_in_range(
-                    sorted_node_tokens,
-                    stream,
-                    (
-                        func_pos + 1,
-                        get_corresponding_closing_bracket(stream, func_pos + 1)?,
-                    ),
-                )? {
-                    Some(node) => Ok(node),
-                    None => {
-                        return Err(ErrorType::MissingFunctionParameters {
-                            func_name: func_token.token.value.clone(),
-                        })
-                    }
+    /// Reads the elements separated by commas following `first`, after the first comma.
+    fn parse_union(&mut self, first: Node, comma: Token) -> EvalResult<Node> {
+        let parent = Some((comma.r#type.precedence()?, Associativity::Left));
+        let mut nodes = vec![Box::new(first)];
+        let mut comma = comma;
+        // Where empty elements are reported, found at the first one
+        let mut last_comma = None;
+        loop {
+            let node = match self.parse_expression(parent)? {
+                Some(node) => node,
+                None => {
+                    let at = last_comma
+                        .get_or_insert_with(|| self.last_union_operator(&comma))
+                        .clone();
+                    self.recover(ErrorType::EmptyUnion, &at, Node::Invalid)?
                 }
-            } else {
-                // No available token
-                return Err(ErrorType::MissingFunctionParameters {
-                    func_name: func_token.token.value.clone(),
-                });
+            };
+            nodes.push(Box::new(node));
+
+            match self.peek() {
+                Some(token) if token.r#type.is_union_operator() => comma = token.clone(),
+                _ => break,
             }
-        } else {
-            return Err(ErrorType::MissingFunctionParameters {
-                func_name: func_token.token.value.clone(),
-            });
+            self.index += 1;
         }
-    }?;
 
-    match content_node {
-        Expression::Union(nodes) => Ok(nodes),
-        other => Ok(vec![Box::new(other)]),
+        Ok(Node::Union(nodes))
     }
-}
 
-fn get_corresponding_closing_bracket(
-    stream: &TokenStream,
-    opening_bracket_pos: usize,
-) -> EvalResult<usize> {
-    let mut index = opening_bracket_pos + 1;
-    let mut current_depth = 0;
-    while index < stream.len() {
-        let token: &Token = &stream[index];
-
-        if token.r#type == TokenType::ClosingBracket {
-            if current_depth == 0 {
-                return Ok(index.try_into().unwrap());
+    /// Returns the last comma of the union `comma` is part of, where its empty elements
+    /// are reported.
+    fn last_union_operator(&self, comma: &Token) -> Token {
+        let mut last = comma;
+        let mut depth = 0;
+        for token in &self.tokens[self.index..] {
+            match token.r#type {
+                TokenType::OpeningBracket => depth += 1,
+                TokenType::ClosingBracket if depth == 0 => break,
+                TokenType::ClosingBracket => depth -= 1,
+                // Assignments are the only operators containing unions
+                TokenType::Equal | TokenType::ColonEqual if depth == 0 => break,
+                r#type if r#type.is_union_operator() && depth == 0 => last = token,
+                _ => (),
             }
-            current_depth -= 1;
-        } else if token.r#type == TokenType::OpeningBracket {
-            current_depth += 1;
         }
-        index += 1;
+        last.clone()
     }
-    Err(ErrorType::MissingClosingBracket)
-}
 
-fn build_union_operator(
-    sorted_node_tokens: &mut Vec<TokenInfo>,
-    stream: &TokenStream,
-    token_info: &TokenInfo,
-    range: (usize, usize),
-) -> EvalResult<Node> {
-    // Check for every other union operators (commas) at the same depth and
-    // in the same range.
-    let mut union_operators = [vec![token_info.clone()], {
-        let vec = sorted_node_tokens
-            .iter()
-            .filter(|x| {
-                x.token.r#type == token_info.token.r#type
-                    && x.depth == token_info.depth
-                    && x.position >= range.0
-                    && x.position < range.1
-            })
-            .cloned()
-            .collect_vec();
-
-        // Remove the found item from the sorted node tokens
-        let mut index = 0;
-        while index < vec.len() {
-            let elem = &vec[index];
-            sorted_node_tokens.remove(sorted_node_tokens.iter().position(|x| *x == *elem).unwrap());
-            index += 1;
+    /// Reads a comparison, chaining it with the following comparisons, so that
+    /// `a < b == c` is read as `a < b && b == c`.
+    fn parse_comparison(&mut self, first: Node, operator: Token) -> EvalResult<Node> {
+        let parent = Some((operator.r#type.precedence()?, Associativity::Chain));
+        let is_chained = |token: &Token| token.r#type.associativity() == Associativity::Chain;
+
+        let mut chain = vec![];
+        let mut operator = operator;
+        loop {
+            let node = match self.parse_expression(parent)? {
+                Some(node) => node,
+                None => {
+                    // Reported at the comparison after the missing operand, if any
+                    let at = match self.peek() {
+                        Some(token) if is_chained(token) => token.clone(),
+                        _ => operator.clone(),
+                    };
+                    let error = ErrorType::MissingOperatorArgument { token: at.r#type };
+                    self.recover(error, &at, Node::Invalid)?
+                }
+            };
+            chain.push((operator.r#type, Box::new(node)));
+
+            match self.peek() {
+                Some(token) if is_chained(token) => operator = token.clone(),
+                _ => break,
+            }
+            self.index += 1;
         }
 
-        vec
-    }]
-    .concat();
+        if chain.len() == 1 {
+            let (operator, rhs) = chain.remove(0);
+            return build_binary_operator(first, operator, *rhs, self.context);
+        }
+        Ok(Node::Comparison(Box::new(first), chain))
+    }
 
-    union_operators.sort_by(|a, b| a.position.cmp(&b.position));
+    /// Reads the arms of a conditional operator up to the matching `:`, so that
+    /// conditionals can be nested in both arms: `a ? b ? c : d : e` and
+    /// `a ? b : c ? d : e`.
+    fn parse_conditional(&mut self, condition: Node, question: Token) -> EvalResult<Node> {
+        let if_true = self.parse_arm(&question)?;
+        match self.peek() {
+            Some(token) if token.r#type == TokenType::Colon => {
+                let colon = token.clone();
+                self.index += 1;
+                let if_false = self.parse_arm(&colon)?;
+                let arms = Node::Binary(Box::new(if_true), TokenType::Colon, Box::new(if_false));
+                build_binary_operator(condition, TokenType::Question, arms, self.context)
+            }
+            // Reported when evaluated, as the arms are missing a `:`
+            _ => build_binary_operator(condition, TokenType::Question, if_true, self.context),
+        }
+    }
 
-    let mut ranges: Vec<(usize, usize)> = vec![];
-    let mut pos = range.0;
-    // Create ranges for all the nodes to build
-    for elem in union_operators {
-        ranges.push((pos + 1, elem.position));
-        pos = elem.position;
+    /// Reads an arm of a conditional operator, stopping at the next `:` unless it
+    /// belongs to a conditional within the arm.
+    fn parse_arm(&mut self, operator: &Token) -> EvalResult<Node> {
+        let parent = Some((operator.r#type.precedence()?, Associativity::Left));
+        let arm = self.parse_expression(parent)?;
+        let mut arm = self.expect_operand(arm, operator)?;
+        while let Some(token) = self.peek().cloned() {
+            if token.r#type != TokenType::Question {
+                break;
+            }
+            self.index += 1;
+            arm = self.parse_conditional(arm, token)?;
+        }
+        Ok(arm)
     }
-    // Add final range
-    ranges.push((pos + 1, range.1));
-
-    let nodes = {
-        let mut vec = vec![];
-        for r in ranges {
-            vec.push(Box::new(
-                match get_lowest_precedence_node_in_range(sorted_node_tokens, stream, r)? {
-                    Some(node) => node,
-                    None => return Err(ErrorType::EmptyUnion),
-                },
-            ))
+
+    /// Returns the operand of `operator`, reporting it if missing.
+    fn expect_operand(&mut self, operand: Option<Node>, operator: &Token) -> EvalResult<Node> {
+        match operand {
+            Some(node) => Ok(node),
+            None => {
+                let error = ErrorType::MissingOperatorArgument {
+                    token: operator.r#type,
+                };
+                self.recover(error, operator, Node::Invalid)
+            }
         }
-        vec
-    };
+    }
+}
+
+/// Whether the token is an operator between two operands.
+fn is_infix(r#type: TokenType) -> bool {
+    r#type.is_binary_operator() || r#type.is_union_operator()
+}
+
+/// Whether the operator takes as its left operand the expression read for `parent`,
+/// instead of the expression `parent` itself is part of.
+fn binds(r#type: TokenType, parent: Parent) -> EvalResult<bool> {
+    let precedence = r#type.precedence()?;
+    Ok(match parent {
+        None => true,
+        Some((parent, Associativity::Right)) => precedence >= parent,
+        Some((parent, _)) => precedence > parent,
+    })
+}
 
-    Ok(Node::Union(nodes))
+/// Builds a binary operator from its operands.
+fn build_binary_operator(
+    lhs: Node,
+    operator: TokenType,
+    rhs: Node,
+    context: &Context,
+) -> EvalResult<Node> {
+    // Measurements written as literals are created once, so that every use of them
+    // refers to the same source of uncertainty.
+    match (operator, lhs, rhs) {
+        (TokenType::Uncertainty, Node::Literal(value), Node::Literal(sigma)) => {
+            Ok(Node::Literal(value.with_uncertainty(sigma, context)?))
+        }
+        (operator, lhs, rhs) => Ok(Node::Binary(Box::new(lhs), operator, Box::new(rhs))),
+    }
 }
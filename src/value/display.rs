@@ -9,6 +9,8 @@ impl fmt::Display for ValueType {
             Self::ComplexType => write!(f, "Complex"),
             Self::VectorType => write!(f, "Vector"),
             Self::BoolType => write!(f, "Bool"),
+            Self::IntervalType => write!(f, "Interval"),
+            Self::UncertainType => write!(f, "Measurement"),
         }
     }
 }
@@ -43,6 +45,11 @@ impl fmt::Display for Value {
                     )
                 }
             ),
+            Self::Interval(lower, upper) => write!(f, "[{} .. {}]", lower, upper),
+            Self::Uncertain(measurement) => {
+                write!(f, "{} ± {}", measurement.value, measurement.sigma())
+            }
+            Self::Dual(dual) => write!(f, "{}", dual.value),
             Self::Vector(v) => write!(f, "[{}]", {
                 let mut as_string = vec![];
 
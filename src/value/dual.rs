@@ -0,0 +1,93 @@
+use super::{FloatValue, Value};
+use crate::out::EvalResult;
+
+/// A dual number `value + derivative * ε`, where `ε² = 0`.
+///
+/// Dual numbers are used internally for forward-mode automatic differentiation:
+/// evaluating a function on `x + ε` returns its value and its exact derivative in `x`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dual {
+    /// The real part.
+    pub(crate) value: FloatValue,
+    /// The derivative with respect to the differentiation variable.
+    pub(crate) derivative: FloatValue,
+}
+
+impl Dual {
+    pub fn new(value: FloatValue, derivative: FloatValue) -> Self {
+        Self { value, derivative }
+    }
+
+    /// Creates the differentiation variable at the provided point.
+    pub fn variable(value: FloatValue) -> Self {
+        Self::new(value, 1.0)
+    }
+
+    /// Creates a dual number that does not depend on the differentiation variable.
+    pub fn constant(value: FloatValue) -> Self {
+        Self::new(value, 0.0)
+    }
+
+    pub fn add(self, rhs: Self) -> Self {
+        Self::new(self.value + rhs.value, self.derivative + rhs.derivative)
+    }
+
+    pub fn sub(self, rhs: Self) -> Self {
+        Self::new(self.value - rhs.value, self.derivative - rhs.derivative)
+    }
+
+    pub fn mul(self, rhs: Self) -> Self {
+        Self::new(
+            self.value * rhs.value,
+            self.derivative * rhs.value + self.value * rhs.derivative,
+        )
+    }
+
+    pub fn div(self, rhs: Self) -> Self {
+        Self::new(
+            self.value / rhs.value,
+            (self.derivative * rhs.value - self.value * rhs.derivative) / (rhs.value * rhs.value),
+        )
+    }
+
+    pub fn pow(self, rhs: Self) -> Self {
+        let value = self.value.powf(rhs.value);
+        // Avoid the logarithm of the base if the exponent is constant, as the base
+        // could be negative.
+        let derivative = if rhs.derivative == 0.0 {
+            rhs.value * self.value.powf(rhs.value - 1.0) * self.derivative
+        } else {
+            value * (rhs.derivative * self.value.ln() + rhs.value * self.derivative / self.value)
+        };
+        Self::new(value, derivative)
+    }
+
+    /// Applies a real function, given its derivative.
+    pub fn propagate<F, D>(self, f: F, derivative: D) -> Self
+    where
+        F: Fn(FloatValue) -> FloatValue,
+        D: Fn(FloatValue) -> FloatValue,
+    {
+        Self::new(f(self.value), derivative(self.value) * self.derivative)
+    }
+}
+
+impl Value {
+    /// Returns the value as a dual number. Any other real value is converted to a
+    /// constant.
+    pub(crate) fn as_dual(&self) -> EvalResult<Dual> {
+        match self {
+            Value::Dual(dual) => Ok(*dual),
+            other => Ok(Dual::constant(other.as_float()?)),
+        }
+    }
+}
+
+/// Returns both operands as dual numbers if at least one of them is.
+pub(crate) fn operands(lhs: &Value, rhs: &Value) -> EvalResult<Option<(Dual, Dual)>> {
+    if lhs.is_dual() || rhs.is_dual() {
+        Ok(Some((lhs.as_dual()?, rhs.as_dual()?)))
+    } else {
+        Ok(None)
+    }
+}
@@ -0,0 +1,259 @@
+use std::f64::consts;
+
+use super::{FloatValue, Value};
+use crate::out::{ErrorType, EvalResult};
+
+/// The lower and upper bounds of an interval.
+pub type Bounds = (FloatValue, FloatValue);
+
+impl Value {
+    /// Creates an interval from a lower and an upper bound.
+    pub fn interval(self, upper: Self) -> EvalResult<Self> {
+        let lower_bound = self.as_float()?;
+        let upper_bound = upper.as_float()?;
+        if lower_bound > upper_bound || lower_bound.is_nan() || upper_bound.is_nan() {
+            return Err(ErrorType::InvalidInterval {
+                lower: self,
+                upper,
+            });
+        }
+        Ok(Value::Interval(lower_bound, upper_bound))
+    }
+
+    /// Creates an interval centered on the value with the specified radius.
+    pub fn plus_minus(self, radius: Self) -> EvalResult<Self> {
+        let center = self.as_float()?;
+        let radius_value = radius.as_float()?;
+        if radius_value < 0.0 || radius_value.is_nan() {
+            return Err(ErrorType::InvalidInterval {
+                lower: Value::Float(center - radius_value),
+                upper: Value::Float(center + radius_value),
+            });
+        }
+        Ok(Value::Interval(center - radius_value, center + radius_value))
+    }
+
+    /// Returns the bounds of the value, if it is an interval. Any other real value
+    /// is converted to a degenerate interval.
+    pub fn as_interval(&self) -> EvalResult<Bounds> {
+        match self {
+            Value::Interval(lower, upper) => Ok((*lower, *upper)),
+            other => {
+                let value = other.as_float()?;
+                Ok((value, value))
+            }
+        }
+    }
+}
+
+/// Returns the bounds of both operands if at least one of them is an interval.
+pub fn operands(lhs: &Value, rhs: &Value) -> EvalResult<Option<(Bounds, Bounds)>> {
+    if lhs.is_interval() || rhs.is_interval() {
+        Ok(Some((lhs.as_interval()?, rhs.as_interval()?)))
+    } else {
+        Ok(None)
+    }
+}
+
+fn from_candidates(candidates: &[FloatValue]) -> Bounds {
+    let lower = candidates.iter().cloned().fold(f64::INFINITY, f64::min);
+    let upper = candidates.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    (lower, upper)
+}
+
+/// Multiplies two bounds, treating `0 * inf` as zero.
+fn mul_bound(a: FloatValue, b: FloatValue) -> FloatValue {
+    if a == 0.0 || b == 0.0 {
+        0.0
+    } else {
+        a * b
+    }
+}
+
+pub fn add(a: Bounds, b: Bounds) -> Bounds {
+    (a.0 + b.0, a.1 + b.1)
+}
+
+pub fn sub(a: Bounds, b: Bounds) -> Bounds {
+    (a.0 - b.1, a.1 - b.0)
+}
+
+pub fn mul(a: Bounds, b: Bounds) -> Bounds {
+    from_candidates(&[
+        mul_bound(a.0, b.0),
+        mul_bound(a.0, b.1),
+        mul_bound(a.1, b.0),
+        mul_bound(a.1, b.1),
+    ])
+}
+
+pub fn div(a: Bounds, b: Bounds) -> EvalResult<Bounds> {
+    let reciprocal = if b.0 == 0.0 && b.1 == 0.0 {
+        return Err(ErrorType::DivideByZero {
+            numerator: Value::Interval(a.0, a.1),
+        });
+    } else if b.0 > 0.0 || b.1 < 0.0 {
+        (1.0 / b.1, 1.0 / b.0)
+    } else if b.0 == 0.0 {
+        (1.0 / b.1, f64::INFINITY)
+    } else if b.1 == 0.0 {
+        (f64::NEG_INFINITY, 1.0 / b.0)
+    } else {
+        // The divisor contains zero, so the result can be any number.
+        (f64::NEG_INFINITY, f64::INFINITY)
+    };
+    Ok(mul(a, reciprocal))
+}
+
+pub fn pow(base: Bounds, exponent: Bounds) -> EvalResult<Bounds> {
+    // x^y is monotonic in both arguments for positive bases, and 0^y is 0 for positive
+    // exponents.
+    if base.0 > 0.0 || (base.0 == 0.0 && exponent.0 > 0.0) {
+        return Ok(from_candidates(&[
+            base.0.powf(exponent.0),
+            base.0.powf(exponent.1),
+            base.1.powf(exponent.0),
+            base.1.powf(exponent.1),
+        ]));
+    }
+
+    if exponent.0 != exponent.1 || exponent.0.fract() != 0.0 {
+        return Err(ErrorType::OutOfDomain {
+            func_name: "Exponentiation".to_owned(),
+            value: Value::Interval(base.0, base.1),
+        });
+    }
+
+    let n = exponent.0 as i32;
+    if n == 0 {
+        return Ok((1.0, 1.0));
+    }
+    let magnitude = if n.abs() % 2 == 1 {
+        (base.0.powi(n.abs()), base.1.powi(n.abs()))
+    } else if base.1 <= 0.0 {
+        (base.1.powi(n.abs()), base.0.powi(n.abs()))
+    } else {
+        // The base contains zero.
+        (0.0, base.0.powi(n.abs()).max(base.1.powi(n.abs())))
+    };
+
+    if n < 0 {
+        div((1.0, 1.0), magnitude)
+    } else {
+        Ok(magnitude)
+    }
+}
+
+/// Returns `Some(bool)` if the comparison `a < b` holds, or not, for every value
+/// in the intervals.
+pub fn less_than(a: Bounds, b: Bounds) -> Option<bool> {
+    if a.1 < b.0 {
+        Some(true)
+    } else if a.0 >= b.1 {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Returns `Some(bool)` if the comparison `a <= b` holds, or not, for every value
+/// in the intervals.
+pub fn less_or_equal_to(a: Bounds, b: Bounds) -> Option<bool> {
+    if a.1 <= b.0 {
+        Some(true)
+    } else if a.0 > b.1 {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Returns `Some(bool)` if the comparison `a == b` holds, or not, for every value
+/// in the intervals.
+pub fn equal_to(a: Bounds, b: Bounds) -> Option<bool> {
+    if a.1 < b.0 || b.1 < a.0 {
+        Some(false)
+    } else if a.0 == a.1 && b.0 == b.1 {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+/// Maps a monotonic function over an interval.
+pub fn monotonic<F>(bounds: Bounds, increasing: bool, f: F) -> Value
+where
+    F: Fn(FloatValue) -> FloatValue,
+{
+    if increasing {
+        Value::Interval(f(bounds.0), f(bounds.1))
+    } else {
+        Value::Interval(f(bounds.1), f(bounds.0))
+    }
+}
+
+/// Maps a monotonic function defined over `[min, max]` over an interval.
+pub fn monotonic_in_domain<F>(
+    func_name: &str,
+    bounds: Bounds,
+    domain: Bounds,
+    increasing: bool,
+    f: F,
+) -> EvalResult<Value>
+where
+    F: Fn(FloatValue) -> FloatValue,
+{
+    if bounds.0 < domain.0 || bounds.1 > domain.1 {
+        Err(ErrorType::OutOfDomain {
+            func_name: func_name.to_owned(),
+            value: Value::Interval(bounds.0, bounds.1),
+        })
+    } else {
+        Ok(monotonic(bounds, increasing, f))
+    }
+}
+
+pub fn abs(bounds: Bounds) -> Value {
+    if bounds.0 >= 0.0 {
+        Value::Interval(bounds.0, bounds.1)
+    } else if bounds.1 <= 0.0 {
+        Value::Interval(-bounds.1, -bounds.0)
+    } else {
+        Value::Interval(0.0, (-bounds.0).max(bounds.1))
+    }
+}
+
+/// Returns true if `offset + k * period` lies inside the interval for some integer `k`.
+fn contains_periodic_point(bounds: Bounds, offset: FloatValue, period: FloatValue) -> bool {
+    let k = ((bounds.0 - offset) / period).ceil();
+    offset + k * period <= bounds.1
+}
+
+pub fn sin(bounds: Bounds) -> Value {
+    if bounds.1 - bounds.0 >= consts::TAU {
+        return Value::Interval(-1.0, 1.0);
+    }
+    let (mut lower, mut upper) = from_candidates(&[bounds.0.sin(), bounds.1.sin()]);
+    if contains_periodic_point(bounds, consts::FRAC_PI_2, consts::TAU) {
+        upper = 1.0;
+    }
+    if contains_periodic_point(bounds, -consts::FRAC_PI_2, consts::TAU) {
+        lower = -1.0;
+    }
+    Value::Interval(lower, upper)
+}
+
+pub fn cos(bounds: Bounds) -> Value {
+    sin((bounds.0 + consts::FRAC_PI_2, bounds.1 + consts::FRAC_PI_2))
+}
+
+pub fn tan(bounds: Bounds) -> EvalResult<Value> {
+    if contains_periodic_point(bounds, consts::FRAC_PI_2, consts::PI) {
+        Err(ErrorType::OutOfDomain {
+            func_name: "tan".to_owned(),
+            value: Value::Interval(bounds.0, bounds.1),
+        })
+    } else {
+        Ok(Value::Interval(bounds.0.tan(), bounds.1.tan()))
+    }
+}
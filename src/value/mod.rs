@@ -1,9 +1,12 @@
 mod display;
+pub(crate) mod dual;
+pub mod interval;
+pub mod uncertain;
 pub mod valuetype;
 
-use self::valuetype::ValueType;
+use self::{dual::Dual, uncertain::Uncertain, valuetype::ValueType};
 use super::out::*;
-use crate::{settings::Rounding, token::tokentype::TokenType};
+use crate::{context::Context, settings::Rounding, token::tokentype::TokenType};
 use num::complex::Complex64;
 
 pub type IntValue = i64;
@@ -21,6 +24,15 @@ pub enum Value {
     Complex(ComplexValue),
     Vector(VectorValue),
     Bool(BoolValue),
+    /// A closed interval of real numbers, given its lower and upper bounds.
+    Interval(FloatValue, FloatValue),
+    /// A measured value and its standard deviation.
+    Uncertain(Uncertain),
+    /// A dual number, used internally for automatic differentiation. It never leaves
+    /// the evaluation of `grad` and `jacobian`.
+    #[doc(hidden)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Dual(Dual),
 }
 
 impl Value {
@@ -31,6 +43,10 @@ impl Value {
             Value::Complex(_) => ValueType::ComplexType,
             Value::Vector(_) => ValueType::VectorType,
             Value::Bool(_) => ValueType::BoolType,
+            Value::Interval(_, _) => ValueType::IntervalType,
+            Value::Uncertain(_) => ValueType::UncertainType,
+            // Dual numbers are read as their real part.
+            Value::Dual(_) => ValueType::FloatType,
         }
     }
 
@@ -54,6 +70,72 @@ impl Value {
         matches!(self, Value::Bool(_))
     }
 
+    pub fn is_interval(&self) -> bool {
+        matches!(self, Value::Interval(_, _))
+    }
+
+    pub fn is_uncertain(&self) -> bool {
+        matches!(self, Value::Uncertain(_))
+    }
+
+    pub(crate) fn is_dual(&self) -> bool {
+        matches!(self, Value::Dual(_))
+    }
+
+    /// Returns true if the value cannot be converted to other types without losing
+    /// data, so operations need to handle it on their own.
+    pub(crate) fn is_self_contained(&self) -> bool {
+        self.is_interval() || self.is_uncertain() || self.is_dual()
+    }
+
+    /// Returns the value as a float, using the mean of measured values and the real
+    /// part of dual numbers. Used for comparisons.
+    pub fn as_mean(&self) -> EvalResult<FloatValue> {
+        match self {
+            Value::Uncertain(measurement) => Ok(measurement.value),
+            Value::Dual(dual) => Ok(dual.value),
+            other => other.as_float(),
+        }
+    }
+
+    /// Applies a real function, given its derivative, propagating the uncertainty of
+    /// measured values and the derivative of dual numbers.
+    pub fn propagate<F, D>(&self, f: F, derivative: D) -> EvalResult<Value>
+    where
+        F: Fn(FloatValue) -> FloatValue,
+        D: Fn(FloatValue) -> FloatValue,
+    {
+        match self {
+            Value::Uncertain(measurement) => Ok(measurement.propagate(f, derivative)),
+            Value::Dual(dual) => Ok(Value::Dual(dual.propagate(f, derivative))),
+            other => Ok(Value::Float(f(other.as_float()?))),
+        }
+    }
+
+    /// Applies a real function defined over `[min, max]`, given its derivative. See
+    /// `Value::propagate`.
+    pub fn propagate_in_domain<F, D>(
+        &self,
+        func_name: &str,
+        domain: (FloatValue, FloatValue),
+        f: F,
+        derivative: D,
+    ) -> EvalResult<Value>
+    where
+        F: Fn(FloatValue) -> FloatValue,
+        D: Fn(FloatValue) -> FloatValue,
+    {
+        let value = self.as_mean()?;
+        if value < domain.0 || value > domain.1 {
+            Err(ErrorType::OutOfDomain {
+                func_name: func_name.to_owned(),
+                value: self.clone(),
+            })
+        } else {
+            self.propagate(f, derivative)
+        }
+    }
+
     pub fn as_int(&self) -> EvalResult<IntValue> {
         match self {
             Value::Int(n) => Ok(*n),
@@ -121,6 +203,37 @@ impl Value {
             Value::Float(n) => Ok(Complex64::new(*n, 0.0)),
             Value::Int(n) => Ok(Complex64::new(*n as f64, 0.0)),
             Value::Bool(n) => Ok(Complex64::new(*n as i64 as f64, 0.0)),
+            Value::Interval(lower, upper) => {
+                if lower == upper {
+                    Ok(Complex64::new(*lower, 0.0))
+                } else {
+                    Err(ErrorType::FailedCast {
+                        value: self.clone(),
+                        from: ValueType::IntervalType,
+                        to: ValueType::ComplexType,
+                    })
+                }
+            }
+            Value::Uncertain(measurement) => {
+                if measurement.sigma() == 0.0 {
+                    Ok(Complex64::new(measurement.value, 0.0))
+                } else {
+                    Err(ErrorType::FailedCast {
+                        value: self.clone(),
+                        from: ValueType::UncertainType,
+                        to: ValueType::ComplexType,
+                    })
+                }
+            }
+            Value::Dual(dual) => {
+                if dual.derivative == 0.0 {
+                    Ok(Complex64::new(dual.value, 0.0))
+                } else {
+                    Err(ErrorType::NotDifferentiable {
+                        value: Value::Float(dual.value),
+                    })
+                }
+            }
             Value::Vector(v) => {
                 if v.len() == 1 {
                     v[0].as_complex()
@@ -142,6 +255,9 @@ impl Value {
             Value::Float(n) => vec![Value::Float(*n)],
             Value::Complex(n) => vec![Value::Complex(*n)],
             Value::Bool(n) => vec![Value::Bool(*n)],
+            Value::Interval(lower, upper) => vec![Value::Interval(*lower, *upper)],
+            Value::Uncertain(measurement) => vec![Value::Uncertain(measurement.clone())],
+            Value::Dual(dual) => vec![Value::Dual(*dual)],
         }
     }
 
@@ -183,6 +299,11 @@ impl Value {
             ValueType::FloatType => Ok(Value::Float(self.as_float()?)),
             ValueType::ComplexType => Ok(Value::Complex(self.as_complex()?)),
             ValueType::VectorType => Ok(Value::Vector(self.as_vector())),
+            ValueType::IntervalType => {
+                let (lower, upper) = self.as_interval()?;
+                Ok(Value::Interval(lower, upper))
+            }
+            ValueType::UncertainType => Ok(Value::Uncertain(self.as_uncertain()?)),
         }
     }
 
@@ -263,6 +384,14 @@ impl Value {
                 },
                 ValueType::ComplexType => match self.as_complex() {
                     Ok(value) => Value::Complex(value),
+                    Err(_) => self.try_as_type(ValueType::IntervalType),
+                },
+                ValueType::IntervalType => match self.as_interval() {
+                    Ok((lower, upper)) => Value::Interval(lower, upper),
+                    Err(_) => self.try_as_type(ValueType::UncertainType),
+                },
+                ValueType::UncertainType => match self.as_uncertain() {
+                    Ok(measurement) => Value::Uncertain(measurement),
                     Err(_) => self.try_as_type(ValueType::VectorType),
                 },
                 ValueType::VectorType => Value::Vector(self.as_vector()),
@@ -270,8 +399,8 @@ impl Value {
         }
     }
 
-    pub fn round(&self, rounding: Rounding) -> Self {
-        match rounding {
+    pub fn round(&self, context: &Context) -> Self {
+        match context.rounding {
             Rounding::Round(precision) => {
                 let precision = precision.clamp(0, 12);
                 let factor = (10.0f64.powi(precision as i32)) as f64;
@@ -281,10 +410,17 @@ impl Value {
                         (c.re * factor).round() / factor,
                         (c.im * factor).round() / factor,
                     )),
+                    Self::Interval(lower, upper) => Value::Interval(
+                        (*lower * factor).round() / factor,
+                        (*upper * factor).round() / factor,
+                    ),
+                    Self::Uncertain(measurement) => {
+                        Value::Uncertain(measurement.round(precision as i32, context))
+                    }
                     Self::Vector(vec) => {
                         let mut out_vec = vec![];
                         for val in vec {
-                            out_vec.push(val.round(rounding).clone());
+                            out_vec.push(val.round(context).clone());
                         }
                         Value::Vector(out_vec)
                     }
@@ -320,6 +456,24 @@ impl From<ComplexValue> for Value {
     }
 }
 
+impl From<interval::Bounds> for Value {
+    fn from(bounds: interval::Bounds) -> Self {
+        Value::Interval(bounds.0, bounds.1)
+    }
+}
+
+impl From<Uncertain> for Value {
+    fn from(measurement: Uncertain) -> Self {
+        Value::Uncertain(measurement)
+    }
+}
+
+impl From<Dual> for Value {
+    fn from(dual: Dual) -> Self {
+        Value::Dual(dual)
+    }
+}
+
 impl<T> From<Vec<T>> for Value
 where
     Value: From<T>,
@@ -332,6 +486,19 @@ where
 
 impl PartialEq for Value {
     fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            // Overlapping intervals cannot be compared by operators, but they are the
+            // same value if they have the same bounds.
+            (Value::Interval(lower, upper), Value::Interval(other_lower, other_upper)) => {
+                return lower == other_lower && upper == other_upper
+            }
+            (Value::Vector(lhs), Value::Vector(rhs))
+                if lhs.iter().chain(rhs.iter()).any(Value::is_interval) =>
+            {
+                return lhs == rhs
+            }
+            _ => (),
+        }
         // This should never panics, as it converts the two operands
         // to their highest complexity and return a Value::Bool
         match self.clone().equal_to(other.clone()) {
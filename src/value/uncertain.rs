@@ -0,0 +1,229 @@
+use std::collections::BTreeMap;
+
+use super::{valuetype::ValueType, FloatValue, Value};
+use crate::{
+    context::Context,
+    out::{ErrorType, EvalResult},
+};
+
+/// A measured value and its standard deviation.
+///
+/// The deviation is stored as the contributions of every independent source of
+/// uncertainty, so that errors are propagated linearly and correlated values
+/// cancel out: `x - x` has no uncertainty.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Uncertain {
+    /// The measured value.
+    pub value: FloatValue,
+    /// The contribution of every source to the standard deviation.
+    pub components: BTreeMap<u64, FloatValue>,
+}
+
+impl Uncertain {
+    /// Creates a measurement with the provided standard deviation. The measurement is
+    /// a new independent source of uncertainty, identified by the context.
+    pub fn new(value: FloatValue, sigma: FloatValue, context: &Context) -> Self {
+        let mut components = BTreeMap::new();
+        components.insert(context.next_source(), sigma);
+        Self { value, components }
+    }
+
+    /// Creates a value with no uncertainty.
+    pub fn exact(value: FloatValue) -> Self {
+        Self {
+            value,
+            components: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the standard deviation.
+    pub fn sigma(&self) -> FloatValue {
+        self.components
+            .values()
+            .fold(0.0, |sum, component| sum + component * component)
+            .sqrt()
+    }
+
+    /// Combines the components of two measurements, given the partial derivatives of the
+    /// result with respect to both of them.
+    fn combine(&self, rhs: &Self, value: FloatValue, lhs_d: FloatValue, rhs_d: FloatValue) -> Self {
+        let mut components = BTreeMap::new();
+        for (source, component) in &self.components {
+            *components.entry(*source).or_insert(0.0) += lhs_d * component;
+        }
+        for (source, component) in &rhs.components {
+            *components.entry(*source).or_insert(0.0) += rhs_d * component;
+        }
+        Self { value, components }
+    }
+
+    pub fn add(&self, rhs: &Self) -> Self {
+        self.combine(rhs, self.value + rhs.value, 1.0, 1.0)
+    }
+
+    pub fn sub(&self, rhs: &Self) -> Self {
+        self.combine(rhs, self.value - rhs.value, 1.0, -1.0)
+    }
+
+    pub fn mul(&self, rhs: &Self) -> Self {
+        self.combine(rhs, self.value * rhs.value, rhs.value, self.value)
+    }
+
+    pub fn div(&self, rhs: &Self) -> EvalResult<Self> {
+        if rhs.value == 0.0 {
+            return Err(ErrorType::DivideByZero {
+                numerator: Value::Uncertain(self.clone()),
+            });
+        }
+        Ok(self.combine(
+            rhs,
+            self.value / rhs.value,
+            1.0 / rhs.value,
+            -self.value / (rhs.value * rhs.value),
+        ))
+    }
+
+    pub fn pow(&self, rhs: &Self) -> EvalResult<Self> {
+        let exact_exponent = rhs.components.is_empty();
+        // Negative bases only have real powers for integer exponents, and 0^y is only
+        // defined around y for positive exponents.
+        let out_of_domain = if self.value < 0.0 {
+            !exact_exponent || rhs.value.fract() != 0.0
+        } else {
+            self.value == 0.0 && !exact_exponent && rhs.value <= 0.0
+        };
+        if out_of_domain {
+            return Err(ErrorType::OutOfDomain {
+                func_name: "Exponentiation".to_owned(),
+                value: Value::Uncertain(self.clone()),
+            });
+        }
+
+        let value = self.value.powf(rhs.value);
+        let lhs_d = rhs.value * self.value.powf(rhs.value - 1.0);
+        // The logarithm of the base is only needed if the exponent is uncertain, and
+        // 0^y does not change with y.
+        let rhs_d = if exact_exponent || self.value == 0.0 {
+            0.0
+        } else {
+            value * self.value.ln()
+        };
+        Ok(self.combine(rhs, value, lhs_d, rhs_d))
+    }
+
+    /// Propagates the uncertainty through a real function, given its derivative.
+    pub fn propagate<F, D>(&self, f: F, derivative: D) -> Value
+    where
+        F: Fn(FloatValue) -> FloatValue,
+        D: Fn(FloatValue) -> FloatValue,
+    {
+        let d = derivative(self.value);
+        Value::Uncertain(Self {
+            value: f(self.value),
+            components: self
+                .components
+                .iter()
+                .map(|(source, component)| (*source, d * component))
+                .collect(),
+        })
+    }
+
+    /// Propagates the uncertainty through a real function defined over `[min, max]`,
+    /// given its derivative.
+    pub fn propagate_in_domain<F, D>(
+        &self,
+        func_name: &str,
+        domain: (FloatValue, FloatValue),
+        f: F,
+        derivative: D,
+    ) -> EvalResult<Value>
+    where
+        F: Fn(FloatValue) -> FloatValue,
+        D: Fn(FloatValue) -> FloatValue,
+    {
+        if self.value < domain.0 || self.value > domain.1 {
+            Err(ErrorType::OutOfDomain {
+                func_name: func_name.to_owned(),
+                value: Value::Uncertain(self.clone()),
+            })
+        } else {
+            Ok(self.propagate(f, derivative))
+        }
+    }
+
+    /// Rounds the standard deviation to two significant figures and the value to the
+    /// same decimal place. No more than `max_decimals` decimal places are kept, unless
+    /// they are needed to show the first significant figure of the deviation.
+    ///
+    /// The result is a new independent measurement, as the rounded deviation can no
+    /// longer be split exactly between the original sources.
+    pub fn round(&self, max_decimals: i32, context: &Context) -> Self {
+        let sigma = self.sigma();
+        if sigma == 0.0 || !sigma.is_finite() {
+            let factor = 10.0f64.powi(max_decimals);
+            let value = (self.value * factor).round() / factor;
+            return if sigma == 0.0 {
+                Self::exact(value)
+            } else {
+                Self::new(value, sigma, context)
+            };
+        }
+
+        let decimals =
+            significant_decimals(sigma, 2).min(max_decimals.max(significant_decimals(sigma, 1)));
+        let factor = 10.0f64.powi(decimals);
+        let round = |value: FloatValue| (value * factor).round() / factor;
+        Self::new(round(self.value), round(sigma), context)
+    }
+}
+
+/// Returns the decimal places needed to show `figures` significant figures of a
+/// nonzero standard deviation. The result is negative if it has more integer digits.
+fn significant_decimals(sigma: FloatValue, figures: i32) -> i32 {
+    figures - 1 - sigma.abs().log10().floor() as i32
+}
+
+impl Value {
+    /// Creates a measured value with the provided standard deviation.
+    pub fn with_uncertainty(self, sigma: Self, context: &Context) -> EvalResult<Self> {
+        let sigma_value = sigma.as_float()?;
+        if sigma_value < 0.0 || sigma_value.is_nan() {
+            return Err(ErrorType::OutOfDomain {
+                func_name: "Uncertainty".to_owned(),
+                value: sigma,
+            });
+        }
+        Ok(Value::Uncertain(Uncertain::new(
+            self.as_float()?,
+            sigma_value,
+            context,
+        )))
+    }
+
+    /// Returns the value as a measured one. Any other real value is converted to a
+    /// value with no uncertainty.
+    pub fn as_uncertain(&self) -> EvalResult<Uncertain> {
+        match self {
+            Value::Uncertain(measurement) => Ok(measurement.clone()),
+            other => match other.as_float() {
+                Ok(value) => Ok(Uncertain::exact(value)),
+                Err(ErrorType::FailedCast { value, from, to: _ }) => Err(ErrorType::FailedCast {
+                    value,
+                    from,
+                    to: ValueType::UncertainType,
+                }),
+                Err(other) => Err(other),
+            },
+        }
+    }
+}
+
+/// Returns both operands as measured values if at least one of them is.
+pub fn operands(lhs: &Value, rhs: &Value) -> EvalResult<Option<(Uncertain, Uncertain)>> {
+    if lhs.is_uncertain() || rhs.is_uncertain() {
+        Ok(Some((lhs.as_uncertain()?, rhs.as_uncertain()?)))
+    } else {
+        Ok(None)
+    }
+}
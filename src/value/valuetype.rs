@@ -2,6 +2,7 @@ use crate::value::Value;
 
 /// Contains all possible values types.
 #[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ValueType {
     /// Type for `i64`
     IntType,
@@ -13,6 +14,10 @@ pub enum ValueType {
     VectorType,
     /// Type for `bool`
     BoolType,
+    /// Type for `(f64, f64)` intervals
+    IntervalType,
+    /// Type for measured values
+    UncertainType,
 }
 
 impl Value {
@@ -23,6 +28,9 @@ impl Value {
             Self::Complex(_) => ValueType::ComplexType,
             Self::Vector(_) => ValueType::VectorType,
             Self::Bool(_) => ValueType::BoolType,
+            Self::Interval(_, _) => ValueType::IntervalType,
+            Self::Uncertain(_) => ValueType::UncertainType,
+            Self::Dual(_) => ValueType::FloatType,
         }
     }
 }
@@ -36,7 +44,9 @@ impl ValueType {
             Self::IntType => 2,
             Self::FloatType => 3,
             Self::ComplexType => 4,
-            Self::VectorType => 5,
+            Self::IntervalType => 5,
+            Self::UncertainType => 6,
+            Self::VectorType => 7,
         }
     }
 